@@ -1,4 +1,5 @@
-DROP DATABASE IF EXISTS digital_cookbook;
+-- FORCE drops the connections left open by the previous test
+DROP DATABASE IF EXISTS digital_cookbook WITH (FORCE);
 
 
 -- DEV ONLY -- for quick iteration
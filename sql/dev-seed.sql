@@ -0,0 +1,10 @@
+-- Dev seed
+INSERT INTO chef (firebase_id, username) VALUES ('firebase_auth_123', 'Goombah!');
+
+
+INSERT INTO recipe (id, cid, title, steps, tags, total_time, total_minutes) VALUES (1, 'firebase_auth_123', 'Hunter`s Stew', '{"Tenderize the Boy", "Put him in the stew"}', '{"Weeknight-Dinner", "Easy"}', '25 mins', 25);
+INSERT INTO recipe (id, cid, title, steps, tags, total_time, total_minutes) VALUES (2, 'firebase_auth_123', 'Lemon Pound Cake', '{"He is a pound of cake!"}', '{"Weeknight-Dinner", "Easy"}', '1 hr 15 mins', 75);
+INSERT INTO recipe (id, cid, title, steps, tags, total_time, total_minutes) VALUES (3, 'firebase_auth_123', 'Roast Beast', '{"Make sure to slice him thin!"}', '{"Weeknight-Dinner", "Hard"}', '3 hrs', 180);
+
+ALTER SEQUENCE recipe_id_seq RESTART WITH 4;
+-- ALTER SEQUENCE chef_id_seq RESTART WITH 2;
@@ -1,5 +1,7 @@
+-- IF NOT EXISTS so databases created before schema_migrations existed can be adopted
+
 -- Chef
-CREATE TABLE chef (
+CREATE TABLE IF NOT EXISTS chef (
     -- ctime timestamp with time zone DEFAULT now(),
     firebase_id text primary key,
     username varchar(255),
@@ -7,7 +9,7 @@ CREATE TABLE chef (
 );
 
 -- Recipe
-CREATE TABLE recipe (
+CREATE TABLE IF NOT EXISTS recipe (
     id bigserial primary key,
     cid text NOT NULL,
     ctime timestamp with time zone DEFAULT now(),
@@ -27,6 +29,3 @@ CREATE TABLE recipe (
         FOREIGN KEY(cid)
         REFERENCES chef(firebase_id)
 );
-
-ALTER SEQUENCE recipe_id_seq RESTART WITH 4;
--- ALTER SEQUENCE chef_id_seq RESTART WITH 2;
\ No newline at end of file
@@ -0,0 +1,15 @@
+-- Recipe full text search
+
+-- array_to_string is only STABLE, generated columns need IMMUTABLE
+CREATE OR REPLACE FUNCTION immutable_array_to_string(text[]) RETURNS text
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE
+    AS $$ SELECT array_to_string($1, ' ') $$;
+
+ALTER TABLE recipe ADD COLUMN IF NOT EXISTS search tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+    setweight(to_tsvector('english', coalesce(header, '')), 'B') ||
+    setweight(to_tsvector('english', immutable_array_to_string(coalesce(ingredients, array[]::text[]))), 'C') ||
+    setweight(to_tsvector('english', immutable_array_to_string(coalesce(steps, array[]::text[]))), 'D')
+) STORED;
+
+CREATE INDEX IF NOT EXISTS recipe_search_idx ON recipe USING GIN (search);
@@ -0,0 +1,2 @@
+-- Number of servings a recipe makes, the base for scaling
+ALTER TABLE recipe ADD COLUMN IF NOT EXISTS servings integer;
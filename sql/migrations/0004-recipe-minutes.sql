@@ -0,0 +1,6 @@
+-- Cook, prep and total time in minutes, parsed from the free text columns on write
+ALTER TABLE recipe ADD COLUMN IF NOT EXISTS prep_minutes integer;
+ALTER TABLE recipe ADD COLUMN IF NOT EXISTS cook_minutes integer;
+ALTER TABLE recipe ADD COLUMN IF NOT EXISTS total_minutes integer;
+
+CREATE INDEX IF NOT EXISTS recipe_total_minutes_idx ON recipe (cid, total_minutes);
@@ -0,0 +1,2 @@
+-- Page a recipe was imported from, NULL for recipes typed in by hand
+ALTER TABLE recipe ADD COLUMN IF NOT EXISTS source_url text;
@@ -0,0 +1,6 @@
+-- Where an imported recipe came from, to credit the author and find it again
+ALTER TABLE recipe ADD COLUMN IF NOT EXISTS source_name text;
+ALTER TABLE recipe ADD COLUMN IF NOT EXISTS original_author text;
+
+-- Importing a url again finds the recipe already saved from it
+CREATE INDEX IF NOT EXISTS recipe_source_url_idx ON recipe (cid, source_url);
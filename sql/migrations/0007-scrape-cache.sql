@@ -0,0 +1,8 @@
+-- Scrape results by normalized url, with the validators to revalidate them
+CREATE TABLE IF NOT EXISTS scrape_cache (
+    url text PRIMARY KEY,
+    result jsonb NOT NULL,
+    etag text,
+    last_modified text,
+    fetched_at timestamp with time zone NOT NULL DEFAULT now()
+);
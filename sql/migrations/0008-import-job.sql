@@ -0,0 +1,14 @@
+-- Background imports (batch of urls), with a per-url report clients poll
+CREATE TABLE IF NOT EXISTS import_job (
+    id bigserial primary key,
+    cid text NOT NULL,
+    ctime timestamp with time zone DEFAULT now(),
+    mtime timestamp with time zone,
+    status text NOT NULL DEFAULT 'running',
+    report jsonb NOT NULL DEFAULT '[]'::jsonb,
+
+    CONSTRAINT fk_chef
+        FOREIGN KEY(cid)
+        REFERENCES chef(firebase_id)
+        ON DELETE CASCADE
+);
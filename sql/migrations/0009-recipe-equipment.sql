@@ -0,0 +1,2 @@
+-- Cookware a recipe needs, e.g. from Cooklang #cookware{} markup
+ALTER TABLE recipe ADD COLUMN IF NOT EXISTS equipment text[];
@@ -0,0 +1,2 @@
+-- Why a job stopped before every url had an outcome, set along with status 'failed'
+ALTER TABLE import_job ADD COLUMN IF NOT EXISTS error text;
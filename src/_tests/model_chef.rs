@@ -1,6 +1,6 @@
 use sea_orm::Set;
 
-use crate::{model::{init_db, ChefMac, ChefPatch}, entities::{chef, recipe}};
+use crate::{model::{init_db, ChefMac, ChefPatch, ListOptions}, entities::{chef, recipe}};
 
 
 #[tokio::test]
@@ -52,7 +52,7 @@ async fn model_chef_get_recipes() -> Result<(), Box<dyn std::error::Error>> {
     let db = init_db().await?;
 
     // -- ACTION
-    let recipes: Vec<recipe::Model> = ChefMac::get_recipes(&db, String::from("firebase_auth_123")).await?;
+    let recipes: Vec<recipe::Model> = ChefMac::get_recipes(&db, String::from("firebase_auth_123"), ListOptions::default()).await?.items;
 
     // -- CHECK
     assert_eq!(recipes.len(), 3);
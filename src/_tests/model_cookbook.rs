@@ -0,0 +1,165 @@
+use std::io::{Cursor, Read};
+
+use sea_orm::{ConnectionTrait, DbBackend, Statement};
+use zip::ZipArchive;
+
+use super::{recipes_by_tag, CookbookMac, CookbookOptions, MAX_COOKBOOK_RECIPES};
+use crate::{
+    model::{init_db, Error, RecipeMac, RecipePatch},
+    security::UserCtx,
+    utils::PageSize,
+};
+
+fn chef_utx() -> UserCtx {
+    UserCtx {
+        user_id: "firebase_auth_123".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn model_cookbook_recipes() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+
+    // -- ACTION
+    let (chef, all) = CookbookMac::recipes(&db, &chef_utx(), &CookbookOptions::default()).await?;
+    let by_tag = CookbookOptions {
+        tags: Some("hard, Missing".to_string()),
+        ..Default::default()
+    };
+    let (_, hard) = CookbookMac::recipes(&db, &chef_utx(), &by_tag).await?;
+    let by_id = CookbookOptions {
+        ids: Some("2,1".to_string()),
+        tags: Some("easy".to_string()),
+        ..Default::default()
+    };
+    let (_, picked) = CookbookMac::recipes(&db, &chef_utx(), &by_id).await?;
+    let bad_ids = CookbookOptions {
+        ids: Some("1,two".to_string()),
+        ..Default::default()
+    };
+    let bad_ids = CookbookMac::recipes(&db, &chef_utx(), &bad_ids).await;
+
+    // -- CHECK
+    assert_eq!(chef.username.as_deref(), Some("Goombah!"));
+    let titles: Vec<&str> = all.iter().map(|recipe| recipe.title.as_str()).collect();
+    assert_eq!(titles, ["Hunter`s Stew", "Lemon Pound Cake", "Roast Beast"]);
+    assert_eq!(hard.len(), 1);
+    assert_eq!(hard[0].title, "Roast Beast");
+    assert_eq!(picked.iter().map(|recipe| recipe.id).collect::<Vec<_>>(), [1, 2]);
+    assert!(matches!(bad_ids, Err(Error::InvalidListOption(_))));
+
+    let groups = recipes_by_tag(&all);
+    let tags: Vec<&str> = groups.iter().map(|(tag, _)| tag.as_str()).collect();
+    assert_eq!(tags, ["Easy", "Hard", "Weeknight-Dinner"]);
+    assert_eq!(groups[2].1.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_cookbook_too_many_recipes() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    db.execute(Statement::from_string(
+        DbBackend::Postgres,
+        format!(
+            "INSERT INTO recipe (cid, title, tags) SELECT 'firebase_auth_123', 'Bulk ' || n, '{{Bulk}}' FROM generate_series(1, {}) AS n",
+            MAX_COOKBOOK_RECIPES + 1
+        ),
+    ))
+    .await?;
+    let bulk = CookbookOptions {
+        tags: Some("bulk".to_string()),
+        ..Default::default()
+    };
+    let others = CookbookOptions {
+        tags: Some("easy".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let too_many = CookbookMac::recipes(&db, &chef_utx(), &bulk).await;
+    let (_, easy) = CookbookMac::recipes(&db, &chef_utx(), &others).await?;
+
+    // -- CHECK
+    assert!(matches!(too_many, Err(Error::InvalidListOption(_))));
+    assert!(!easy.is_empty() && easy.len() < MAX_COOKBOOK_RECIPES);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_cookbook_pdf() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let options = CookbookOptions {
+        page_size: Some(PageSize::A4),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let pdf = CookbookMac::pdf(&db, &chef_utx(), &options).await?;
+
+    // -- CHECK
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.starts_with("%PDF-"));
+    assert!(text.contains("/Count 5"), "title, contents and one page per recipe");
+    assert!(text.contains("/MediaBox [0 0 595.28 841.89]"));
+    assert!(text.contains("(Goombah!'s Cookbook)"));
+    assert!(text.contains("(Roast Beast)"));
+    assert!(text.contains("(1. Make sure to slice him thin!)"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_cookbook_epub() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let data = RecipePatch {
+        title: Some("Mac & Cheese".to_string()),
+        ingredients: Some(vec!["2 cups <sharp> cheddar".to_string()]),
+        steps: Some(vec!["Bake.".to_string()]),
+        tags: Some(vec!["Dinner".to_string()]),
+        prep_time: Some("15 mins".to_string()),
+        image_url: Some("data:image/png;base64,iVBORw==".to_string()),
+        ..Default::default()
+    };
+    let recipe = RecipeMac::create(&db, data, chef_utx()).await?;
+    let options = CookbookOptions {
+        ids: Some(format!("{},3", recipe.id)),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let epub = CookbookMac::epub(&db, &chef_utx(), &options).await?;
+
+    // -- CHECK
+    let mut zip = ZipArchive::new(Cursor::new(epub))?;
+    let mut nav = String::new();
+    zip.by_name("OEBPS/nav.xhtml")?.read_to_string(&mut nav)?;
+    assert!(nav.contains("<li><span>Dinner</span>"));
+    assert!(nav.contains(r#"<a href="recipe-3.xhtml">Roast Beast</a>"#));
+
+    // sha256 of "firebase_auth_123:3,4", the same for every build
+    let mut package = String::new();
+    zip.by_name("OEBPS/content.opf")?.read_to_string(&mut package)?;
+    assert_eq!(recipe.id, 4);
+    assert!(package.contains(
+        "urn:digital-cookbook:f2e08740f6790f00af5b1e6532ee2a183a60a964db27d518f2fa676bed6970d5"
+    ));
+    assert!(!package.contains("firebase_auth_123"), "the uid stays out of the book");
+
+    let mut chapter = String::new();
+    zip.by_name(&format!("OEBPS/recipe-{}.xhtml", recipe.id))?
+        .read_to_string(&mut chapter)?;
+    assert!(chapter.contains(r#"itemtype="https://schema.org/Recipe""#));
+    assert!(chapter.contains(r#"<h1 itemprop="name">Mac &amp; Cheese</h1>"#));
+    assert!(chapter.contains(r#"<li itemprop="recipeIngredient">2 cups &lt;sharp&gt; cheddar</li>"#));
+    assert!(chapter.contains(r#"<time itemprop="prepTime" datetime="PT15M">15 mins</time>"#));
+    assert!(chapter.contains(&format!(r#"src="images/recipe-{}.png""#, recipe.id)));
+    assert!(zip.by_name(&format!("OEBPS/images/recipe-{}.png", recipe.id)).is_ok());
+
+    Ok(())
+}
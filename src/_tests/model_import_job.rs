@@ -0,0 +1,293 @@
+use std::{sync::Arc, time::Duration};
+
+use super::{BatchImportRequest, CrawlRequest, ImportJobMac, ImportOutcome, MAX_RUNNING_JOBS};
+use crate::{
+    model::{init_db, Error, RecipeMac, ScrapeCache},
+    security::UserCtx,
+    test_utils::{http_response, stand_in},
+    utils::{read_archive, FetchPolicy},
+};
+
+const JSON_LD_PAGE: &str = include_str!("fixtures/recipe_json_ld.html");
+const HEURISTIC_PAGE: &str = include_str!("fixtures/recipe_heuristic.html");
+
+fn chef_utx() -> UserCtx {
+    UserCtx {
+        user_id: "firebase_auth_123".to_string(),
+    }
+}
+
+fn test_cache() -> ScrapeCache {
+    ScrapeCache {
+        policy: FetchPolicy {
+            allow_private: true,
+            ..Default::default()
+        },
+        ttl: Duration::from_secs(3600),
+    }
+}
+
+fn recipe_site(request: &str) -> String {
+    let html = [("Content-Type", "text/html")];
+    if request.starts_with("GET /cornbread ") {
+        http_response("200 OK", &html, JSON_LD_PAGE)
+    } else if request.starts_with("GET /pancakes ") {
+        http_response("200 OK", &html, HEURISTIC_PAGE)
+    } else {
+        http_response("404 Not Found", &html, "<p>Gone</p>")
+    }
+}
+
+#[tokio::test]
+async fn model_import_job_run_batch() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = Arc::new(init_db().await?);
+    let (addr, _) = stand_in(recipe_site).await;
+    let request = BatchImportRequest {
+        urls: vec![
+            format!("http://{}/cornbread", addr),
+            format!("http://{}/cornbread#reviews", addr),
+            format!("http://{}/pancakes", addr),
+            format!("http://{}/missing", addr),
+        ],
+        tags: Some(vec!["imported".to_string()]),
+    };
+    let job = ImportJobMac::create_batch(&db, &chef_utx(), &request).await?;
+
+    // -- ACTION
+    let job = ImportJobMac::run_batch(db.clone(), chef_utx(), job, request.tags, test_cache()).await?;
+
+    // -- CHECK
+    assert!(job.is_done());
+    assert_eq!(job.report.len(), 3, "repeated url imported once");
+    let ImportOutcome::Imported { recipe_id } = job.report[0].outcome else {
+        panic!("cornbread not imported: {:?}", job.report[0]);
+    };
+    let recipe = RecipeMac::get(&db, chef_utx(), recipe_id).await?;
+    assert_eq!(recipe.title, "Skillet Cornbread");
+    assert_eq!(recipe.tags, Some(vec!["imported".to_string()]));
+    assert!(matches!(job.report[1].outcome, ImportOutcome::Imported { .. }));
+    assert!(
+        matches!(&job.report[2].outcome, ImportOutcome::Failed { reason } if reason.contains("404")),
+        "{:?}",
+        job.report[2]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_import_job_duplicates() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = Arc::new(init_db().await?);
+    let (addr, _) = stand_in(recipe_site).await;
+    let request = BatchImportRequest {
+        urls: vec![format!("http://{}/pancakes", addr)],
+        tags: None,
+    };
+    let job = ImportJobMac::create_batch(&db, &chef_utx(), &request).await?;
+    let first = ImportJobMac::run_batch(db.clone(), chef_utx(), job, None, test_cache()).await?;
+
+    // -- ACTION
+    let job = ImportJobMac::create_batch(&db, &chef_utx(), &request).await?;
+    let second = ImportJobMac::run_batch(db.clone(), chef_utx(), job, None, test_cache()).await?;
+
+    // -- CHECK
+    let ImportOutcome::Imported { recipe_id } = first.report[0].outcome else {
+        panic!("pancakes not imported: {:?}", first.report[0]);
+    };
+    assert_eq!(second.report[0].outcome, ImportOutcome::Duplicate { recipe_id });
+
+    Ok(())
+}
+
+/// A blog with robots.txt, a sitemap index, one recipe with JSON-LD and one without
+fn recipe_blog(request: &str) -> String {
+    let host = request
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .find(|(name, _)| name.eq_ignore_ascii_case("host"))
+        .map(|(_, host)| host.trim().to_string())
+        .unwrap_or_default();
+    let xml = [("Content-Type", "application/xml")];
+    if request.starts_with("GET /robots.txt ") {
+        let robots = format!("User-agent: *\nDisallow: /private/\n\nSitemap: http://{}/sitemap_index.xml\n", host);
+        http_response("200 OK", &[("Content-Type", "text/plain")], &robots)
+    } else if request.starts_with("GET /sitemap_index.xml ") {
+        let index = format!("<sitemapindex><sitemap><loc>http://{}/post-sitemap.xml</loc></sitemap></sitemapindex>", host);
+        http_response("200 OK", &xml, &index)
+    } else if request.starts_with("GET /post-sitemap.xml ") {
+        let urls: String = ["/cornbread", "/pancakes", "/private/drafts"]
+            .iter()
+            .map(|path| format!("<url><loc>http://{}{}</loc></url>", host, path))
+            .collect();
+        http_response("200 OK", &xml, &format!("<urlset>{}</urlset>", urls))
+    } else {
+        recipe_site(request)
+    }
+}
+
+#[tokio::test]
+async fn model_import_job_run_crawl() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = Arc::new(init_db().await?);
+    let (addr, mut requests) = stand_in(recipe_blog).await;
+    let request = CrawlRequest {
+        url: format!("http://{}/", addr),
+        max_pages: None,
+        tags: None,
+    };
+    let job = ImportJobMac::create_crawl(&db, &chef_utx(), &request).await?;
+
+    // -- ACTION
+    let job = ImportJobMac::run_crawl(db.clone(), chef_utx(), job, request, test_cache(), Duration::ZERO).await?;
+
+    // -- CHECK
+    assert!(job.is_done());
+    let outcomes: Vec<(&str, &ImportOutcome)> = job
+        .report
+        .iter()
+        .map(|item| (item.url.rsplit_once(&addr.to_string()).unwrap().1, &item.outcome))
+        .collect();
+    assert_eq!(outcomes.len(), 3, "{:?}", outcomes);
+    assert_eq!(outcomes[0].0, "/cornbread");
+    assert!(matches!(outcomes[0].1, ImportOutcome::Imported { .. }), "{:?}", outcomes);
+    assert_eq!(outcomes[1].0, "/pancakes");
+    assert!(matches!(outcomes[1].1, ImportOutcome::Skipped { .. }), "no JSON-LD or microdata");
+    assert_eq!(outcomes[2].0, "/private/drafts");
+    assert!(matches!(outcomes[2].1, ImportOutcome::Skipped { reason } if reason.contains("robots.txt")));
+    while let Ok(received) = requests.try_recv() {
+        assert!(!received.starts_with("GET /private/"), "disallowed page fetched");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_import_job_run_archive() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = Arc::new(init_db().await?);
+    let export = r#"[
+        {"name": "Pancakes", "recipeIngredient": ["2 cups flour"], "tags": [{"name": "Breakfast"}],
+         "prepTime": "PT10M", "orgURL": "https://example.com/pancakes/?utm_source=mealie"},
+        {"name": "Waffles", "recipeIngredient": ["2 cups flour"]},
+        {"description": "No name"}
+    ]"#;
+    let entries = read_archive(export.as_bytes(), None)?;
+    let job = ImportJobMac::create_archive(&db, &chef_utx(), &entries).await?;
+    let first = ImportJobMac::run_archive(db.clone(), chef_utx(), job, entries.clone()).await?;
+
+    // -- ACTION
+    let job = ImportJobMac::create_archive(&db, &chef_utx(), &entries).await?;
+    let second = ImportJobMac::run_archive(db.clone(), chef_utx(), job, entries).await?;
+
+    // -- CHECK
+    assert!(first.is_done());
+    assert_eq!(first.report[0].title.as_deref(), Some("Pancakes"));
+    let ImportOutcome::Imported { recipe_id } = first.report[0].outcome else {
+        panic!("pancakes not imported: {:?}", first.report[0]);
+    };
+    let recipe = RecipeMac::get(&db, chef_utx(), recipe_id).await?;
+    assert_eq!(recipe.tags, Some(vec!["Breakfast".to_string()]));
+    assert_eq!(recipe.prep_minutes, Some(10));
+    assert_eq!(recipe.source_url.as_deref(), Some("https://example.com/pancakes"));
+    assert!(matches!(first.report[1].outcome, ImportOutcome::Imported { .. }));
+    assert!(matches!(first.report[2].outcome, ImportOutcome::Failed { .. }));
+    assert_eq!(second.report[0].outcome, ImportOutcome::Duplicate { recipe_id }, "same source url");
+    assert!(matches!(second.report[1].outcome, ImportOutcome::Imported { .. }), "no source url to match");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_import_job_invalid_batch() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let too_many = BatchImportRequest {
+        urls: (0..101).map(|i| format!("https://example.com/{}", i)).collect(),
+        tags: None,
+    };
+
+    // -- ACTION
+    let empty = ImportJobMac::create_batch(&db, &chef_utx(), &BatchImportRequest::default()).await;
+    let too_many = ImportJobMac::create_batch(&db, &chef_utx(), &too_many).await;
+
+    // -- CHECK
+    assert!(matches!(empty, Err(Error::InvalidImport(_))));
+    assert!(matches!(too_many, Err(Error::InvalidImport(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_import_job_get_other_chef() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let request = BatchImportRequest {
+        urls: vec!["https://example.com/cornbread".to_string()],
+        tags: None,
+    };
+    let job = ImportJobMac::create_batch(&db, &chef_utx(), &request).await?;
+    let other = UserCtx {
+        user_id: "firebase_auth_456".to_string(),
+    };
+
+    // -- ACTION
+    let result = ImportJobMac::get(&db, &other, job.id).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::AccessDenied(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_import_job_fail_interrupted() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let request = BatchImportRequest {
+        urls: vec!["https://example.com/cornbread".to_string()],
+        tags: None,
+    };
+    let job = ImportJobMac::create_batch(&db, &chef_utx(), &request).await?;
+
+    // -- ACTION
+    let count = ImportJobMac::fail_interrupted(&db).await?;
+
+    // -- CHECK
+    let job = ImportJobMac::get(&db, &chef_utx(), job.id).await?;
+    assert!(count >= 1);
+    assert!(job.is_failed());
+    assert_eq!(
+        job.error.as_deref(),
+        Some("Interrupted by a server restart")
+    );
+    assert!(matches!(job.report[0].outcome, ImportOutcome::Failed { .. }));
+    assert_eq!(ImportJobMac::fail_interrupted(&db).await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_import_job_too_many_running() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let request = BatchImportRequest {
+        urls: vec!["https://example.com/cornbread".to_string()],
+        tags: None,
+    };
+    for _ in 0..MAX_RUNNING_JOBS {
+        ImportJobMac::create_batch(&db, &chef_utx(), &request).await?;
+    }
+
+    // -- ACTION
+    let too_many = ImportJobMac::create_batch(&db, &chef_utx(), &request).await;
+    ImportJobMac::fail_interrupted(&db).await?;
+    let after_failed = ImportJobMac::create_batch(&db, &chef_utx(), &request).await;
+
+    // -- CHECK
+    assert!(matches!(too_many, Err(Error::TooManyImportJobs(_))), "{:?}", too_many);
+    assert!(after_failed.is_ok(), "failed jobs no longer count");
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use std::env;
+
+use sqlx::postgres::PgPoolOptions;
+
+use super::{migrate, parse_version, read_migrations, MIGRATIONS_DIR};
+use crate::model::{init_db, Error};
+
+#[test]
+fn model_migration_parse_version() {
+    assert_eq!(parse_version("0002-recipe-search.sql").ok(), Some(2));
+    assert_eq!(parse_version("12_add_column.sql").ok(), Some(12));
+    assert!(matches!(
+        parse_version("recipe-search.sql"),
+        Err(Error::InvalidMigration(_))
+    ));
+}
+
+#[test]
+fn model_migration_read_ordered() -> Result<(), Box<dyn std::error::Error>> {
+    // -- ACTION
+    let migrations = read_migrations(MIGRATIONS_DIR)?;
+
+    // -- CHECK
+    let versions: Vec<i64> = migrations.iter().map(|m| m.version).collect();
+    assert!(versions.windows(2).all(|w| w[0] < w[1]), "ordered: {:?}", versions);
+    assert_eq!(versions[..3], [1, 2, 3]);
+    assert_eq!(migrations[0].name, "0001-create-schema.sql");
+    assert_eq!(migrations[0].checksum.len(), 64);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_migration_rerun_noop() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let _db = init_db().await?;
+    let pool = PgPoolOptions::new()
+        .connect(&env::var("PRODUCTION_DB_URL")?)
+        .await?;
+
+    // -- ACTION
+    let applied = migrate(&pool, MIGRATIONS_DIR).await?;
+
+    // -- CHECK
+    assert!(applied.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_migration_checksum_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let _db = init_db().await?;
+    let pool = PgPoolOptions::new()
+        .connect(&env::var("PRODUCTION_DB_URL")?)
+        .await?;
+    sqlx::query("UPDATE schema_migrations SET checksum = 'edited' WHERE version = 1")
+        .execute(&pool)
+        .await?;
+
+    // -- ACTION
+    let result = migrate(&pool, MIGRATIONS_DIR).await;
+
+    // -- CHECK
+    assert!(
+        matches!(result, Err(Error::MigrationChecksumMismatch(name)) if name == "0001-create-schema.sql")
+    );
+
+    Ok(())
+}
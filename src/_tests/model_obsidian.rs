@@ -0,0 +1,100 @@
+use super::{ObsidianMac, VaultOutcome};
+use crate::{
+    model::{init_db, CookbookOptions, RecipeMac, RecipePatch},
+    security::UserCtx,
+    utils::{read_vault, RecipeNote, VaultEntry},
+};
+
+fn chef_utx() -> UserCtx {
+    UserCtx {
+        user_id: "firebase_auth_123".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn model_obsidian_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let vault = ObsidianMac::export(&db, &chef_utx(), &CookbookOptions::default()).await?;
+    let notes: Vec<RecipeNote> = read_vault(&vault)?
+        .into_iter()
+        .map(|entry| entry.note.unwrap())
+        .collect();
+    let note = |id: i64| notes.iter().find(|note| note.id == Some(id)).unwrap().clone();
+
+    // Stew edited in the vault, cake untouched, beast edited in both
+    let mut stew = note(1);
+    stew.steps = Some(vec!["Simmer for an hour.".to_string()]);
+    let cake = note(2);
+    let mut beast = note(3);
+    beast.title = "Roast Beast, Sliced".to_string();
+    let app_edit = RecipePatch {
+        servings: Some(8),
+        ..Default::default()
+    };
+    RecipeMac::update(&db, app_edit, chef_utx(), 3).await?;
+    let new = RecipeNote::parse("# Toast\n\n## Steps\n\n1. Toast the bread.\n", "Toast").unwrap();
+
+    let entries = [stew, cake, beast, new]
+        .into_iter()
+        .map(|note| VaultEntry {
+            name: note.file_name(),
+            note: Ok(note),
+        })
+        .collect();
+
+    // -- ACTION
+    let report = ObsidianMac::import(&db, &chef_utx(), entries).await;
+
+    // -- CHECK
+    assert_eq!(notes.len(), 3);
+    assert!(notes.iter().all(|note| note.updated.is_some()));
+    assert_eq!(report[0].outcome, VaultOutcome::Updated { recipe_id: 1 });
+    assert_eq!(report[1].outcome, VaultOutcome::Unchanged { recipe_id: 2 });
+    assert!(
+        matches!(&report[2].outcome, VaultOutcome::Conflict { recipe_id: 3, reason } if reason.ends_with("changes title")),
+        "{:?}",
+        report[2]
+    );
+    let VaultOutcome::Created { recipe_id } = report[3].outcome else {
+        panic!("{:?}", report[3]);
+    };
+
+    let stew = RecipeMac::get(&db, chef_utx(), 1).await?;
+    assert_eq!(stew.steps.unwrap(), ["Simmer for an hour."]);
+    let beast = RecipeMac::get(&db, chef_utx(), 3).await?;
+    assert_eq!(beast.title, "Roast Beast", "a conflict leaves the recipe alone");
+    assert_eq!(beast.servings, Some(8));
+    let toast = RecipeMac::get(&db, chef_utx(), recipe_id).await?;
+    assert_eq!(toast.title, "Toast");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_obsidian_import_keeps_going() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let untitled = RecipeNote::default();
+    let toast = RecipeNote::parse("# Toast\n\n## Steps\n\n1. Toast the bread.\n", "Toast").unwrap();
+    let entries = [untitled, toast]
+        .into_iter()
+        .map(|note| VaultEntry {
+            name: note.file_name(),
+            note: Ok(note),
+        })
+        .collect();
+
+    // -- ACTION
+    let report = ObsidianMac::import(&db, &chef_utx(), entries).await;
+
+    // -- CHECK
+    assert!(
+        matches!(&report[0].outcome, VaultOutcome::Failed { reason } if reason.contains("title")),
+        "{:?}",
+        report[0]
+    );
+    assert!(matches!(report[1].outcome, VaultOutcome::Created { .. }));
+
+    Ok(())
+}
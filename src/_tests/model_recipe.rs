@@ -1,4 +1,4 @@
-use crate::{model::{init_db, RecipeMac, RecipePatch}, entities::recipe, security::{UserCtx, utx_from_token}};
+use crate::{model::{init_db, CooklangImportOptions, Error, ImportRequest, ListOptions, RecipeMac, RecipePatch, RecipeView}, entities::recipe, security::UserCtx, utils::ScrapedRecipe};
 
 #[tokio::test]
 async fn model_recipe_create_ok() -> Result<(), Box<dyn std::error::Error>> {
@@ -14,13 +14,18 @@ async fn model_recipe_create_ok() -> Result<(), Box<dyn std::error::Error>> {
         cook_time: Some("".to_string()),
         prep_time: Some("".to_string()),
         total_time: Some("".to_string()),
+        servings: None,
+        source_url: None,
+        source_name: None,
+        original_author: None,
+        equipment: None,
     };
-    let utx = utx_from_token(&db, "firebase_auth_123").await?;
+    let utx = chef_utx();
 
     // -- ACTION
     let recipe = RecipeMac::create(&db, data_fx.clone(), utx).await?;
 
-    let recipes = RecipeMac::list(&db).await?;
+    let recipes = RecipeMac::list(&db, chef_utx(), ListOptions::default()).await?.items;
 
     // -- CHECK
 
@@ -38,7 +43,7 @@ async fn model_recipe_list() -> Result<(), Box<dyn std::error::Error>> {
     let db = init_db().await?;
 
     // -- ACTION
-    let recipes: Vec<recipe::Model> = RecipeMac::list(&db).await?;
+    let recipes: Vec<recipe::Model> = RecipeMac::list(&db, chef_utx(), ListOptions::default()).await?.items;
 
     // -- CHECK
     assert_eq!(recipes.len(), 3);
@@ -52,12 +57,12 @@ async fn model_recipe_delete() -> Result<(), Box<dyn std::error::Error>> {
     // -- FIXTURE
     let db = init_db().await?;
 
-    let utx = UserCtx { user_id: "test_delete".to_owned()};
+    let utx = chef_utx();
 
     // -- ACTION
     let deleted_id = RecipeMac::delete(&db, utx, 3).await?;
 
-    let recipes = RecipeMac::list(&db).await?;
+    let recipes = RecipeMac::list(&db, chef_utx(), ListOptions::default()).await?.items;
 
     // -- CHECK
     assert_eq!(recipes.len(), 2);
@@ -80,13 +85,18 @@ async fn model_recipe_update() -> Result<(), Box<dyn std::error::Error>> {
         cook_time: Some("3 hours".to_string()),
         prep_time: Some("".to_string()),
         total_time: Some("".to_string()),
+        servings: None,
+        source_url: None,
+        source_name: None,
+        original_author: None,
+        equipment: None,
     };
-    let utx = utx_from_token(&db, "firebase_auth_123").await?;
+    let utx = chef_utx();
 
     // -- ACTION
     let recipe = RecipeMac::create(&db, data_fx.clone(), utx).await?;
 
-    let recipes = RecipeMac::list(&db).await?;
+    let recipes = RecipeMac::list(&db, chef_utx(), ListOptions::default()).await?.items;
 
     // -- CHECK
 
@@ -98,15 +108,88 @@ async fn model_recipe_update() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn model_recipe_create_times() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let data_fx = RecipePatch {
+        title: Some("Quick Stir Fry".to_string()),
+        prep_time: Some("20 mins".to_string()),
+        cook_time: Some("1 hr 5 mins".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let recipe = RecipeMac::create(&db, data_fx, chef_utx()).await?;
+
+    // -- CHECK
+    assert_eq!(recipe.prep_minutes, Some(20));
+    assert_eq!(recipe.cook_minutes, Some(65));
+    assert_eq!(recipe.total_minutes, Some(85), "total derived from prep + cook");
+
+    // -- ACTION - an explicit total wins
+    let data_fx = RecipePatch {
+        total_time: Some("1 1/2 hours".to_string()),
+        ..Default::default()
+    };
+    let recipe = RecipeMac::update(&db, data_fx, chef_utx(), recipe.id).await?;
+
+    // -- CHECK
+    assert_eq!(recipe.total_minutes, Some(90));
+    assert_eq!(RecipeView::from(recipe).total_duration.as_deref(), Some("PT1H30M"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_create_negative_time() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let data_fx = RecipePatch {
+        title: Some("Time Travel Toast".to_string()),
+        prep_time: Some("-30 mins".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let result = RecipeMac::create(&db, data_fx, chef_utx()).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::InvalidRecipe(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_list_max_total_minutes() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let options = ListOptions {
+        max_total_minutes: Some(75),
+        sort: Some("total_minutes".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let page = RecipeMac::list(&db, chef_utx(), options).await?;
+
+    // -- CHECK
+    let titles: Vec<&str> = page.items.iter().map(|r| r.title.as_str()).collect();
+    assert_eq!(titles, vec!["Hunter`s Stew", "Lemon Pound Cake"]);
+    assert_eq!(page.total, 2);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn model_recipe_get_by_tags_ok() -> Result<(), Box<dyn std::error::Error>> {
     // -- FIXTURE
     let db = init_db().await?;
 
-    let utx: UserCtx = utx_from_token(&db, "firebase_auth_123").await?;
+    let utx = chef_utx();
 
     // -- ACTION
-    let recipes = RecipeMac::get_by_tag(&db, utx, "Easy").await?;
+    let recipes = RecipeMac::get_by_tag(&db, utx, "Easy", ListOptions::default()).await?.items;
 
     // -- CHECK
     assert_eq!(recipes.len(), 2);
@@ -121,11 +204,326 @@ async fn model_recipe_get() -> Result<(), Box<dyn std::error::Error>> {
     let db = init_db().await?;
 
     // -- ACTION
-    let recipe = RecipeMac::get(&db, 1).await?;
+    let recipe = RecipeMac::get(&db, chef_utx(), 1).await?;
 
     // -- CHECK
     assert_eq!(recipe.id, 1);
     assert_eq!(recipe.title, "Hunter`s Stew");
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn model_recipe_list_paginated() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let options = ListOptions {
+        limit: Some(2),
+        offset: None,
+        sort: Some("-title".to_string()),
+        max_total_minutes: None,
+    };
+
+    // -- ACTION
+    let page = RecipeMac::list(&db, chef_utx(), options).await?;
+
+    // -- CHECK
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.total, 3);
+    assert_eq!(page.next_cursor, Some(2));
+    assert_eq!(page.items[0].title, "Roast Beast");
+    assert_eq!(page.items[1].title, "Lemon Pound Cake");
+
+    // -- ACTION - last page
+    let options = ListOptions {
+        limit: Some(2),
+        offset: page.next_cursor,
+        sort: Some("-title".to_string()),
+        max_total_minutes: None,
+    };
+    let page = RecipeMac::list(&db, chef_utx(), options).await?;
+
+    // -- CHECK
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.next_cursor, None);
+    assert_eq!(page.items[0].title, "Hunter`s Stew");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_list_invalid_sort() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let options = ListOptions {
+        sort: Some("cid".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let result = RecipeMac::list(&db, chef_utx(), options).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::InvalidListOption(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_list_own_only() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+
+    // -- ACTION
+    let recipes: Vec<recipe::Model> = RecipeMac::list(&db, other_utx(), ListOptions::default()).await?.items;
+
+    // -- CHECK
+    assert_eq!(recipes.len(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_get_access_denied() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+
+    // -- ACTION
+    let result = RecipeMac::get(&db, other_utx(), 1).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::AccessDenied(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_update_access_denied() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let data_fx = RecipePatch {
+        title: Some("Not Yours".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let result = RecipeMac::update(&db, data_fx, other_utx(), 1).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::AccessDenied(_))));
+    assert_eq!(RecipeMac::get(&db, chef_utx(), 1).await?.title, "Hunter`s Stew");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_delete_access_denied() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+
+    // -- ACTION
+    let result = RecipeMac::delete(&db, other_utx(), 1).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::AccessDenied(_))));
+    assert_eq!(RecipeMac::list(&db, chef_utx(), ListOptions::default()).await?.items.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_get_not_found() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+
+    // -- ACTION
+    let result = RecipeMac::get(&db, chef_utx(), 999).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::EntityNotFound("recipe", id)) if id == "999"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_update_not_found() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let data_fx = RecipePatch {
+        title: Some("Ghost Soup".to_string()),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let result = RecipeMac::update(&db, data_fx, chef_utx(), 999).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::EntityNotFound("recipe", id)) if id == "999"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_delete_not_found() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+
+    // -- ACTION
+    let result = RecipeMac::delete(&db, chef_utx(), 999).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::EntityNotFound("recipe", id)) if id == "999"));
+
+    Ok(())
+}
+
+// region: Model Test Utils
+fn chef_utx() -> UserCtx {
+    UserCtx { user_id: "firebase_auth_123".to_owned() }
+}
+
+fn other_utx() -> UserCtx {
+    UserCtx { user_id: "firebase_auth_other".to_owned() }
+}
+// endregion: Model Test Utils
+
+#[test]
+fn model_recipe_patch_from_scraped() {
+    // -- FIXTURE
+    let scraped = ScrapedRecipe {
+        title: Some("Skillet Cornbread".to_string()),
+        ingredients: vec!["1 cup cornmeal".to_string()],
+        steps: vec!["Bake it.".to_string()],
+        total_time: Some("35 mins".to_string()),
+        servings: Some(8),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let patch = RecipePatch::from(scraped);
+
+    // -- CHECK
+    assert_eq!(patch.title.as_deref(), Some("Skillet Cornbread"));
+    assert_eq!(patch.ingredients, Some(vec!["1 cup cornmeal".to_string()]));
+    assert_eq!(patch.total_time.as_deref(), Some("35 mins"));
+    assert_eq!(patch.servings, Some(8));
+}
+
+#[tokio::test]
+async fn model_recipe_import() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let scraped = ScrapedRecipe {
+        url: "https://example.com/cornbread".to_string(),
+        title: Some("Skillet Cornbread".to_string()),
+        ingredients: vec!["1 cup cornmeal".to_string()],
+        steps: vec!["Bake it.".to_string()],
+        tags: vec!["bread".to_string()],
+        source_url: "https://example.com/cornbread".to_string(),
+        source_name: Some("Test Kitchen".to_string()),
+        author: Some("Ada Baker".to_string()),
+        ..Default::default()
+    };
+    let request = ImportRequest {
+        url: "https://example.com/cornbread".to_string(),
+        title: Some("Grandma's Cornbread".to_string()),
+        tags: None,
+    };
+
+    // -- ACTION
+    let imported = RecipeMac::import(&db, chef_utx(), scraped, request).await?;
+    let recipe = imported.recipe;
+
+    // -- CHECK
+    assert!(!imported.existing);
+    assert_eq!(recipe.title, "Grandma's Cornbread", "title override");
+    assert_eq!(recipe.tags, Some(vec!["bread".to_string()]), "scraped tags kept");
+    assert_eq!(recipe.source_url.as_deref(), Some("https://example.com/cornbread"));
+    assert_eq!(recipe.source_name.as_deref(), Some("Test Kitchen"));
+    assert_eq!(recipe.original_author.as_deref(), Some("Ada Baker"));
+    assert_eq!(RecipeMac::get(&db, chef_utx(), recipe.id).await?, recipe);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_import_without_title() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let scraped = ScrapedRecipe {
+        url: "https://example.com/untitled".to_string(),
+        steps: vec!["Bake it.".to_string()],
+        ..Default::default()
+    };
+    let request = ImportRequest {
+        url: "https://example.com/untitled".to_string(),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let result = RecipeMac::import(&db, chef_utx(), scraped, request).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::InvalidImport(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_import_twice() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let scraped = ScrapedRecipe {
+        url: "https://example.com/cornbread?utm_source=feed".to_string(),
+        source_url: "https://example.com/cornbread".to_string(),
+        title: Some("Skillet Cornbread".to_string()),
+        ..Default::default()
+    };
+    let request = ImportRequest {
+        url: "https://example.com/cornbread?utm_source=feed".to_string(),
+        ..Default::default()
+    };
+    let first = RecipeMac::import(&db, chef_utx(), scraped.clone(), request.clone()).await?.recipe;
+
+    // -- ACTION
+    let second = RecipeMac::import(&db, chef_utx(), scraped, request).await?;
+    let found = RecipeMac::find_by_source_url(&db, &chef_utx(), "https://example.com/cornbread/#top").await?;
+    let other = RecipeMac::find_by_source_url(&db, &other_utx(), "https://example.com/cornbread").await?;
+
+    // -- CHECK
+    assert!(second.existing);
+    assert_eq!(second.recipe.id, first.id, "no duplicate");
+    assert_eq!(found.map(|recipe| recipe.id), Some(first.id));
+    assert!(other.is_none(), "only the caller's recipes");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_recipe_import_cooklang() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let text = ">> servings: 4\n>> tags: breakfast, sweet\n>> source: https://example.com/pancakes/\n\n\
+        Whisk @flour{2%cups} and @eggs{2} in a #bowl{}.\n\nFry for ~{3%minutes}, flip and fry ~{2%minutes}.\n";
+    let options = CooklangImportOptions {
+        title: Some("Pancakes".to_string()),
+    };
+
+    // -- ACTION
+    let recipe = RecipeMac::import_cooklang(&db, chef_utx(), text, options).await?;
+
+    // -- CHECK
+    assert_eq!(recipe.title, "Pancakes");
+    assert_eq!(recipe.ingredients, Some(vec!["2 cups flour".to_string(), "2 eggs".to_string()]));
+    assert_eq!(recipe.steps, Some(vec!["Whisk flour and eggs in a bowl.".to_string(), "Fry for 3 minutes, flip and fry 2 minutes.".to_string()]));
+    assert_eq!(recipe.equipment, Some(vec!["bowl".to_string()]));
+    assert_eq!(recipe.tags, Some(vec!["breakfast".to_string(), "sweet".to_string()]));
+    assert_eq!(recipe.servings, Some(4));
+    assert_eq!(recipe.cook_minutes, Some(5), "timers add up");
+    assert_eq!(recipe.source_url.as_deref(), Some("https://example.com/pancakes"));
+
+    let untitled = RecipeMac::import_cooklang(&db, chef_utx(), text, CooklangImportOptions::default()).await;
+    assert!(matches!(untitled, Err(Error::InvalidImport(_))));
+
+    Ok(())
+}
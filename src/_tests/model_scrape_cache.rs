@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use super::ScrapeCache;
+use crate::{
+    model::init_db,
+    test_utils::{http_response, stand_in},
+    utils::{FetchPolicy, UnitSystem},
+};
+
+const PAGE: &str = include_str!("fixtures/recipe_json_ld.html");
+const ETAG: &str = "\"cornbread-v1\"";
+
+fn test_cache(ttl: Duration) -> ScrapeCache {
+    ScrapeCache {
+        policy: FetchPolicy {
+            allow_private: true,
+            ..Default::default()
+        },
+        ttl,
+    }
+}
+
+/// Serves the recipe with an ETag, and a 304 to requests that send it back
+fn recipe_site(request: &str) -> String {
+    if request.to_lowercase().contains(&format!("if-none-match: {}", ETAG)) {
+        return http_response("304 Not Modified", &[("ETag", ETAG)], "");
+    }
+    http_response("200 OK", &[("Content-Type", "text/html"), ("ETag", ETAG)], PAGE)
+}
+
+fn received(requests: &mut UnboundedReceiver<String>) -> Vec<String> {
+    let mut received = Vec::new();
+    while let Ok(request) = requests.try_recv() {
+        received.push(request);
+    }
+    received
+}
+
+#[tokio::test]
+async fn model_scrape_cache_fresh() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let (addr, mut requests) = stand_in(recipe_site).await;
+    let url = format!("http://{}/cornbread", addr);
+    let cache = test_cache(Duration::from_secs(3600));
+    let first = cache.scrape(&db, &url, None).await?;
+
+    // -- ACTION
+    let second = cache.scrape(&db, &format!("{}#comments", url), None).await?;
+    let metric = cache.scrape(&db, &url, Some(UnitSystem::Metric)).await?;
+
+    // -- CHECK
+    assert_eq!(received(&mut requests).len(), 1, "fetched once");
+    assert_eq!(second, first);
+    assert_eq!(first.ingredients[0], "1 1/2 cups cornmeal");
+    assert_ne!(metric.ingredients[0], first.ingredients[0], "units applied to the cached copy");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_scrape_cache_revalidate() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let (addr, mut requests) = stand_in(recipe_site).await;
+    let url = format!("http://{}/cornbread", addr);
+    let cache = test_cache(Duration::ZERO);
+    let first = cache.scrape(&db, &url, None).await?;
+
+    // -- ACTION
+    let second = cache.scrape(&db, &url, None).await?;
+
+    // -- CHECK
+    let received = received(&mut requests);
+    assert_eq!(received.len(), 2, "stale entries are revalidated");
+    assert!(
+        received[1].to_lowercase().contains("if-none-match: \"cornbread-v1\""),
+        "{}",
+        received[1]
+    );
+    assert_eq!(second, first, "304 serves the cached copy");
+
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use super::{parse_search_query, SearchOptions, SearchTerm};
+use crate::{model::init_db, model::RecipeMac, model::RecipePatch, security::UserCtx};
+
+#[test]
+fn model_search_parse_query() {
+    // -- ACTION
+    let terms = parse_search_query(r#"lemon "pound  cake" chick* ***"#);
+
+    // -- CHECK
+    assert_eq!(
+        terms,
+        vec![
+            SearchTerm::Word("lemon".to_string()),
+            SearchTerm::Phrase("pound cake".to_string()),
+            SearchTerm::Prefix("chick".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn model_search_parse_prefix_sanitized() {
+    // -- ACTION
+    let terms = parse_search_query("sl'|ic!*");
+
+    // -- CHECK
+    assert_eq!(terms, vec![SearchTerm::Prefix("slic".to_string())]);
+}
+
+#[tokio::test]
+async fn model_search_word() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let options = SearchOptions {
+        q: "stew".to_string(),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let page = RecipeMac::search(&db, chef_utx(), options).await?;
+
+    // -- CHECK
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items[0].recipe.recipe.title, "Hunter`s Stew");
+    assert_eq!(page.items[0].title, "Hunter`s <mark>Stew</mark>");
+    assert!(page.items[0].snippet.contains("<mark>stew</mark>"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_search_phrase_and_prefix() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+
+    // -- ACTION
+    let phrase = SearchOptions {
+        q: r#""slice him thin""#.to_string(),
+        ..Default::default()
+    };
+    let phrase = RecipeMac::search(&db, chef_utx(), phrase).await?;
+
+    let prefix = SearchOptions {
+        q: "lem*".to_string(),
+        ..Default::default()
+    };
+    let prefix = RecipeMac::search(&db, chef_utx(), prefix).await?;
+
+    let wrong_order = SearchOptions {
+        q: r#""thin slice""#.to_string(),
+        ..Default::default()
+    };
+    let wrong_order = RecipeMac::search(&db, chef_utx(), wrong_order).await?;
+
+    // -- CHECK
+    assert_eq!(phrase.items[0].recipe.recipe.title, "Roast Beast");
+    assert_eq!(prefix.items[0].recipe.recipe.title, "Lemon Pound Cake");
+    assert_eq!(wrong_order.total, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_search_own_only() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let utx = UserCtx {
+        user_id: "firebase_auth_other".to_owned(),
+    };
+    let options = SearchOptions {
+        q: "stew".to_string(),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let page = RecipeMac::search(&db, utx, options).await?;
+
+    // -- CHECK
+    assert_eq!(page.total, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_search_highlight_escaped() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let data_fx = RecipePatch {
+        title: Some("Flan <img src=x onerror=alert(1)>".to_string()),
+        steps: Some(vec!["Bake the flan & serve it \"cold\"".to_string()]),
+        ..Default::default()
+    };
+    RecipeMac::create(&db, data_fx, chef_utx()).await?;
+    let options = SearchOptions {
+        q: "flan".to_string(),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let page = RecipeMac::search(&db, chef_utx(), options).await?;
+
+    // -- CHECK
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items[0].title, "<mark>Flan</mark> &lt;img src=x onerror=alert(1)&gt;");
+    assert!(!page.items[0].snippet.contains('"'), "{}", page.items[0].snippet);
+    assert!(page.items[0].snippet.contains("&amp;"), "{}", page.items[0].snippet);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn model_search_huge_offset() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let options = SearchOptions {
+        q: "stew".to_string(),
+        offset: Some(u64::MAX),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let page = RecipeMac::search(&db, chef_utx(), options).await?;
+
+    // -- CHECK
+    assert_eq!(page.total, 1);
+    assert!(page.items.is_empty());
+    assert_eq!(page.next_cursor, None);
+
+    Ok(())
+}
+
+// region: Model Test Utils
+fn chef_utx() -> UserCtx {
+    UserCtx {
+        user_id: "firebase_auth_123".to_owned(),
+    }
+}
+// endregion: Model Test Utils
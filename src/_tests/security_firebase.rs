@@ -0,0 +1,131 @@
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+
+use crate::test_utils::{sign_test_token, test_claims, test_token, TEST_KEY_ID};
+
+use super::{verify_id_token, Error, KeyStore, MIN_RELOAD_INTERVAL};
+
+#[tokio::test]
+async fn security_firebase_verify_ok() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let token = test_token("firebase_auth_123");
+
+    // -- ACTION
+    let claims = verify_id_token(&token).await?;
+
+    // -- CHECK
+    assert_eq!(claims.sub, "firebase_auth_123");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn security_firebase_verify_expired() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let mut claims = test_claims("firebase_auth_123");
+    claims["iat"] = (claims["iat"].as_u64().unwrap() - 7200).into();
+    claims["exp"] = (claims["exp"].as_u64().unwrap() - 7200).into();
+    let token = sign_test_token(&claims, TEST_KEY_ID);
+
+    // -- ACTION
+    let result = verify_id_token(&token).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::InvalidToken(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn security_firebase_verify_wrong_audience() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let mut claims = test_claims("firebase_auth_123");
+    claims["aud"] = "another-project".into();
+    let token = sign_test_token(&claims, TEST_KEY_ID);
+
+    // -- ACTION
+    let result = verify_id_token(&token).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::InvalidToken(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn security_firebase_verify_wrong_issuer() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let mut claims = test_claims("firebase_auth_123");
+    claims["iss"] = "https://securetoken.google.com/another-project".into();
+    let token = sign_test_token(&claims, TEST_KEY_ID);
+
+    // -- ACTION
+    let result = verify_id_token(&token).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::InvalidToken(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn security_firebase_verify_unknown_kid() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let token = sign_test_token(&test_claims("firebase_auth_123"), "rotated-away");
+
+    // -- ACTION
+    let result = verify_id_token(&token).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::InvalidToken(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn security_firebase_verify_rejects_raw_id() -> Result<(), Box<dyn std::error::Error>> {
+    // -- ACTION
+    let result = verify_id_token("firebase_auth_123").await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::InvalidToken(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn security_firebase_verify_rejects_hs256() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let mut header = Header::new(Algorithm::HS256);
+    header.kid = Some(TEST_KEY_ID.to_string());
+    let token = encode(
+        &header,
+        &test_claims("firebase_auth_123"),
+        &EncodingKey::from_secret(b"secret"),
+    )?;
+
+    // -- ACTION
+    let result = verify_id_token(&token).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(Error::InvalidToken(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn security_firebase_reload_throttled() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    crate::test_utils::init_test_auth();
+    let store = KeyStore::from_env().await?;
+    *store.last_reload.lock().unwrap() -= MIN_RELOAD_INTERVAL;
+
+    // -- ACTION
+    let first = store.claim_reload();
+    let second = store.claim_reload();
+
+    // -- CHECK
+    assert!(first, "reload allowed once the interval passed");
+    assert!(!second, "no second reload within the interval");
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::{env, net::SocketAddr};
+
+use jsonwebtoken::{encode, get_current_timestamp, Algorithm, EncodingKey, Header};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    sync::mpsc,
+};
+
+pub const TEST_PROJECT_ID: &str = "digital-cookbook-test";
+pub const TEST_KEY_ID: &str = "test-key-1";
+
+// Throwaway key, only ever trusted through the test JWKS file below
+const TEST_PRIVATE_KEY: &[u8] = include_bytes!("fixtures/test_rsa_key.pem");
+const TEST_JWKS_PATH: &str = "src/_tests/fixtures/test_jwks.json";
+
+/// Points the token verifier at the test JWKS file and project
+pub fn init_test_auth() {
+    env::set_var("FIREBASE_PROJECT_ID", TEST_PROJECT_ID);
+    env::set_var("FIREBASE_JWKS_PATH", TEST_JWKS_PATH);
+}
+
+/// Claims of a valid Firebase ID token for `user_id`
+pub fn test_claims(user_id: &str) -> Value {
+    let now = get_current_timestamp();
+
+    json!({
+        "iss": format!("https://securetoken.google.com/{}", TEST_PROJECT_ID),
+        "aud": TEST_PROJECT_ID,
+        "sub": user_id,
+        "iat": now,
+        "auth_time": now,
+        "exp": now + 3600,
+    })
+}
+
+/// Signs `claims` with the test key under `kid`
+pub fn sign_test_token(claims: &Value, kid: &str) -> String {
+    init_test_auth();
+
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(kid.to_string());
+
+    let key = EncodingKey::from_rsa_pem(TEST_PRIVATE_KEY).expect("Invalid test key");
+    encode(&header, claims, &key).expect("Cannot sign test token")
+}
+
+/// A valid X-Auth-Token for `user_id`
+pub fn test_token(user_id: &str) -> String {
+    sign_test_token(&test_claims(user_id), TEST_KEY_ID)
+}
+
+/// Local stand-in for a recipe site, answers every connection with `respond(request)`
+/// and sends each request it got back through the channel
+pub async fn stand_in<F>(respond: F) -> (SocketAddr, mpsc::UnboundedReceiver<String>)
+where
+    F: Fn(&str) -> String + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut request = vec![0; 4096];
+            let read = socket.read(&mut request).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request[..read]).into_owned();
+            let response = respond(&request);
+            let _ = tx.send(request);
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        }
+    });
+
+    (addr, rx)
+}
+
+/// Raw HTTP/1.1 response for `stand_in`
+pub fn http_response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\n", status, body.len());
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("Connection: close\r\n\r\n");
+    response.push_str(body);
+    response
+}
@@ -0,0 +1,229 @@
+use std::io::{Cursor, Write};
+
+use flate2::{write::GzEncoder, Compression};
+use serde_json::json;
+use zip::{write::FileOptions, ZipWriter};
+
+use super::{read_archive, ArchiveError, ArchiveFormat};
+
+/// Zip of (name, content) files
+fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in files {
+        zip.start_file(*name, FileOptions::default()).unwrap();
+        zip.write_all(content).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+fn gzip(content: &[u8]) -> Vec<u8> {
+    let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+    gzip.write_all(content).unwrap();
+    gzip.finish().unwrap()
+}
+
+#[test]
+fn utils_archive_paprika() -> Result<(), ArchiveError> {
+    // -- FIXTURE
+    let cornbread = json!({
+        "name": "Skillet Cornbread",
+        "description": "Crisp edges.",
+        "notes": "Best warm.",
+        "ingredients": "1 1/2 cups cornmeal\n\n2 eggs\n",
+        "directions": "Heat the skillet.\nBake 20 minutes.",
+        "categories": ["Bread", "Southern"],
+        "prep_time": "15 min",
+        "cook_time": "1 hour 5 mins",
+        "servings": "8 servings",
+        "source": "Example Kitchen",
+        "source_url": "https://example.com/cornbread",
+        "photo": "ABC123.jpg",
+        "photo_data": "/9j/4AAQ",
+    });
+    let archive = zip(&[
+        ("Skillet Cornbread.paprikarecipe", &gzip(cornbread.to_string().as_bytes())),
+        ("Broken.paprikarecipe", &gzip(b"{not json")),
+    ]);
+
+    // -- ACTION
+    let entries = read_archive(&archive, None)?;
+
+    // -- CHECK
+    assert_eq!(entries.len(), 2);
+    let recipe = entries[0].recipe.as_ref().unwrap();
+    assert_eq!(recipe.title, "Skillet Cornbread");
+    assert_eq!(recipe.header.as_deref(), Some("Crisp edges.\n\nBest warm."));
+    assert_eq!(recipe.ingredients, ["1 1/2 cups cornmeal", "2 eggs"]);
+    assert_eq!(recipe.steps, ["Heat the skillet.", "Bake 20 minutes."]);
+    assert_eq!(recipe.tags, ["Bread", "Southern"]);
+    assert_eq!(recipe.prep_time.as_deref(), Some("15 mins"));
+    assert_eq!(recipe.cook_time.as_deref(), Some("1 hr 5 mins"));
+    assert_eq!(recipe.servings, Some(8));
+    assert_eq!(recipe.source_name.as_deref(), Some("Example Kitchen"));
+    assert_eq!(recipe.image_url.as_deref(), Some("data:image/jpeg;base64,/9j/4AAQ"));
+    assert_eq!(entries[1].name, "Broken.paprikarecipe");
+    assert!(entries[1].recipe.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn utils_archive_mealie() -> Result<(), ArchiveError> {
+    // -- FIXTURE
+    let export = json!([
+        {
+            "name": "Pancakes",
+            "slug": "pancakes",
+            "description": "Fluffy.",
+            "recipeIngredient": [
+                {"quantity": 2.0, "unit": {"name": "cups"}, "food": {"name": "flour"}, "note": "sifted"},
+                {"display": "1 egg"},
+                "1 cup milk",
+            ],
+            "recipeInstructions": [{"text": "Whisk."}, {"text": "Fry."}],
+            "tags": [{"name": "Breakfast"}],
+            "recipeCategory": [{"name": "Sweet"}, {"name": "Breakfast"}],
+            "prepTime": "PT10M",
+            "totalTime": "25 minutes",
+            "recipeYield": "4 servings",
+            "orgURL": "https://example.com/pancakes",
+            "image": "abc123",
+        },
+        {"slug": "nameless"},
+    ]);
+
+    // -- ACTION
+    let entries = read_archive(export.to_string().as_bytes(), None)?;
+
+    // -- CHECK
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].name, "pancakes");
+    let recipe = entries[0].recipe.as_ref().unwrap();
+    assert_eq!(recipe.ingredients, ["2 cups flour sifted", "1 egg", "1 cup milk"]);
+    assert_eq!(recipe.steps, ["Whisk.", "Fry."]);
+    assert_eq!(recipe.tags, ["Breakfast", "Sweet"]);
+    assert_eq!(recipe.prep_time.as_deref(), Some("10 mins"));
+    assert_eq!(recipe.total_time.as_deref(), Some("25 mins"));
+    assert_eq!(recipe.servings, Some(4));
+    assert_eq!(recipe.source_url.as_deref(), Some("https://example.com/pancakes"));
+    assert_eq!(recipe.image_url, None, "a local image id is no url");
+    assert!(entries[1].recipe.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn utils_archive_tandoor() -> Result<(), ArchiveError> {
+    // -- FIXTURE
+    let recipe = json!({
+        "name": "Chili",
+        "description": "Weeknight chili.",
+        "keywords": [{"name": "Dinner"}],
+        "working_time": 20,
+        "waiting_time": 60,
+        "servings": 6,
+        "source_url": "https://example.com/chili",
+        "steps": [
+            {
+                "instruction": "Brown the beef.",
+                "ingredients": [
+                    {"food": {"name": "ground beef"}, "unit": {"name": "lb"}, "amount": 1.5, "note": ""},
+                    {"food": {"name": "salt"}, "unit": null, "amount": 0, "no_amount": true},
+                ],
+            },
+            {"instruction": "Simmer.", "ingredients": [{"original_text": "1 can beans"}]},
+        ],
+    });
+    let recipe_zip = zip(&[
+        ("recipe.json", recipe.to_string().as_bytes()),
+        ("image.png", b"\x89PNG"),
+    ]);
+    let archive = zip(&[("1.zip", &recipe_zip), ("2.zip", b"not a zip")]);
+
+    // -- ACTION
+    let entries = read_archive(&archive, Some(ArchiveFormat::Tandoor))?;
+
+    // -- CHECK
+    assert_eq!(entries.len(), 2);
+    let recipe = entries[0].recipe.as_ref().unwrap();
+    assert_eq!(recipe.title, "Chili");
+    assert_eq!(recipe.ingredients, ["1.5 lb ground beef", "salt", "1 can beans"]);
+    assert_eq!(recipe.steps, ["Brown the beef.", "Simmer."]);
+    assert_eq!(recipe.tags, ["Dinner"]);
+    assert_eq!(recipe.prep_time.as_deref(), Some("20 mins"));
+    assert_eq!(recipe.cook_time.as_deref(), Some("1 hr"));
+    assert_eq!(recipe.total_time.as_deref(), Some("1 hr 20 mins"));
+    assert_eq!(recipe.servings, Some(6));
+    assert!(recipe.image_url.as_deref().unwrap().starts_with("data:image/png;base64,"));
+    assert!(entries[1].recipe.is_err(), "a broken recipe zip fails alone");
+
+    Ok(())
+}
+
+#[test]
+fn utils_archive_tandoor_time_overflow() -> Result<(), ArchiveError> {
+    // -- FIXTURE
+    let recipe = json!({"name": "Forever Stew", "working_time": i64::MAX, "waiting_time": 1});
+    let recipe_zip = zip(&[("recipe.json", recipe.to_string().as_bytes())]);
+    let archive = zip(&[("1.zip", &recipe_zip)]);
+
+    // -- ACTION
+    let entries = read_archive(&archive, Some(ArchiveFormat::Tandoor))?;
+
+    // -- CHECK
+    let recipe = entries[0].recipe.as_ref().unwrap();
+    assert!(recipe.prep_time.is_some());
+    assert_eq!(recipe.total_time, None);
+
+    Ok(())
+}
+
+#[test]
+fn utils_archive_unknown_format() {
+    let result = read_archive(b"Just some notes", None);
+
+    assert!(matches!(result, Err(ArchiveError::UnknownFormat)), "{:?}", result);
+}
+
+#[test]
+fn utils_archive_zip_bomb() {
+    // -- FIXTURE
+    // 15 MB of spaces per recipe zip, a few KB once compressed
+    let padding = vec![b' '; 15 * 1024 * 1024];
+    let recipe = zip(&[("recipe.json", &padding)]);
+    let names: Vec<String> = (0..9).map(|i| format!("recipe_{}.zip", i)).collect();
+    let files: Vec<(&str, &[u8])> = names
+        .iter()
+        .map(|name| (name.as_str(), recipe.as_slice()))
+        .collect();
+    let bomb = zip(&files);
+
+    // -- ACTION
+    let result = read_archive(&bomb, Some(ArchiveFormat::Tandoor));
+
+    // -- CHECK
+    assert!(bomb.len() < 1024 * 1024, "{} bytes", bomb.len());
+    assert!(
+        matches!(result, Err(ArchiveError::TooLargeUnpacked(_))),
+        "{:?}",
+        result.map(|entries| entries.len())
+    );
+}
+
+#[test]
+fn utils_archive_too_many_files() {
+    // -- FIXTURE
+    let names: Vec<String> = (0..20_001).map(|i| format!("{}.txt", i)).collect();
+    let files: Vec<(&str, &[u8])> = names.iter().map(|name| (name.as_str(), &b""[..])).collect();
+    let archive = zip(&files);
+
+    // -- ACTION
+    let result = read_archive(&archive, Some(ArchiveFormat::Mealie));
+
+    // -- CHECK
+    assert!(
+        matches!(result, Err(ArchiveError::TooManyFiles(_))),
+        "{:?}",
+        result.map(|entries| entries.len())
+    );
+}
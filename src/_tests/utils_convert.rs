@@ -0,0 +1,51 @@
+use super::{convert_ingredient, convert_temperatures};
+use crate::utils::{parse_ingredient, Quantity, UnitSystem};
+
+#[test]
+fn utils_convert_ingredient_to_metric() {
+    // -- ACTION
+    let ingredient = convert_ingredient(&parse_ingredient("2 cups milk, warmed"), UnitSystem::Metric);
+
+    // -- CHECK
+    assert_eq!(ingredient.quantity, Some(Quantity { value: 473.0, max: None }));
+    assert_eq!(ingredient.unit.as_deref(), Some("ml"));
+    assert_eq!(ingredient.raw, "473 ml milk, warmed");
+
+    let ingredient = convert_ingredient(&parse_ingredient("2 lbs chicken thighs"), UnitSystem::Metric);
+    assert_eq!(ingredient.raw, "907 g chicken thighs");
+
+    // weight stays weight, counts are left alone
+    let ingredient = convert_ingredient(&parse_ingredient("3 cloves garlic"), UnitSystem::Metric);
+    assert_eq!(ingredient.raw, "3 cloves garlic");
+}
+
+#[test]
+fn utils_convert_ingredient_to_us() {
+    let ingredient = convert_ingredient(&parse_ingredient("1 kg potatoes"), UnitSystem::Us);
+    assert_eq!(ingredient.unit.as_deref(), Some("lb"));
+    assert_eq!(ingredient.raw, "2 1/4 lb potatoes");
+
+    let ingredient = convert_ingredient(&parse_ingredient("15 ml olive oil"), UnitSystem::Us);
+    assert_eq!(ingredient.raw, "1 tbsp olive oil");
+
+    // already us
+    let ingredient = convert_ingredient(&parse_ingredient("1 cup sugar"), UnitSystem::Us);
+    assert_eq!(ingredient.raw, "1 cup sugar");
+}
+
+#[test]
+fn utils_convert_temperatures() {
+    assert_eq!(
+        convert_temperatures("Bake at 350°F for 20 minutes, or 375 degrees F", UnitSystem::Metric),
+        "Bake at 180°C for 20 minutes, or 190°C"
+    );
+    assert_eq!(
+        convert_temperatures("Preheat the oven to 200 °C", UnitSystem::Us),
+        "Preheat the oven to 400°F"
+    );
+    assert_eq!(
+        convert_temperatures("Cook to 165°F inside, add 2 C flour", UnitSystem::Metric),
+        "Cook to 74°C inside, add 2 C flour"
+    );
+    assert_eq!(convert_temperatures("Bake at 350°F", UnitSystem::Us), "Bake at 350°F");
+}
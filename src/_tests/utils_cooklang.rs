@@ -0,0 +1,157 @@
+use super::{CooklangIngredient, CooklangRecipe, CooklangTimer};
+
+const PANCAKES: &str = r#">> title: Pancakes
+>> servings: 4
+>> tags: breakfast, sweet
+>> source: https://example.com/pancakes
+
+> Fluffy, weekend only.
+
+-- dry first
+Whisk @flour{2%cups} with @salt and @baking powder{1%tbsp}
+in a #large bowl{}.
+
+== Cook ==
+Fry in a #skillet for ~{3%minutes} per side. [- flip once -]
+Serve with @maple syrup{}(warm).
+"#;
+
+#[test]
+fn utils_cooklang_parse() {
+    // -- ACTION
+    let recipe = CooklangRecipe::parse(PANCAKES);
+
+    // -- CHECK
+    assert_eq!(recipe.meta(&["title"]), Some("Pancakes"));
+    assert_eq!(recipe.meta(&["serves", "servings"]), Some("4"));
+    assert_eq!(recipe.notes, ["Fluffy, weekend only."]);
+    assert_eq!(
+        recipe.steps,
+        [
+            "Whisk flour with salt and baking powder in a large bowl.",
+            "Fry in a skillet for 3 minutes per side. Serve with maple syrup.",
+        ]
+    );
+    assert_eq!(
+        recipe.ingredients[0],
+        CooklangIngredient {
+            name: "flour".to_string(),
+            quantity: Some("2".to_string()),
+            unit: Some("cups".to_string()),
+            preparation: None,
+        }
+    );
+    assert_eq!(recipe.ingredients[1].name, "salt");
+    assert_eq!(recipe.ingredients[3].preparation.as_deref(), Some("warm"));
+    assert_eq!(recipe.cookware, ["large bowl", "skillet"]);
+    assert_eq!(
+        recipe.timers,
+        [CooklangTimer {
+            name: None,
+            quantity: Some("3".to_string()),
+            unit: Some("minutes".to_string()),
+        }]
+    );
+    assert_eq!(recipe.ingredients[0].line(), "2 cups flour");
+}
+
+#[test]
+fn utils_cooklang_front_matter() {
+    // -- FIXTURE
+    let text = "---\ntitle: Toast\ntags:\n  - breakfast\n  - quick\n---\nToast the @bread{2%slices}.\n";
+
+    // -- ACTION
+    let recipe = CooklangRecipe::parse(text);
+
+    // -- CHECK
+    assert_eq!(recipe.meta(&["title"]), Some("Toast"));
+    assert_eq!(recipe.meta(&["tags"]), Some("breakfast, quick"));
+    assert_eq!(recipe.steps, ["Toast the bread."]);
+}
+
+#[test]
+fn utils_cooklang_comments() {
+    // -- FIXTURE
+    let recipe = CooklangRecipe {
+        metadata: vec![
+            ("title".to_string(), "Cornbread".to_string()),
+            ("source".to_string(), "https://example.com/a--b".to_string()),
+        ],
+        steps: vec!["Bake 20 minutes.".to_string()],
+        ..Default::default()
+    };
+    let text = format!("{}-- a comment line\n>> servings: 8 -- a trailing comment\n", recipe);
+
+    // -- ACTION
+    let parsed = CooklangRecipe::parse(&text);
+
+    // -- CHECK
+    assert_eq!(parsed.meta(&["source"]), Some("https://example.com/a--b"));
+    assert_eq!(parsed.meta(&["servings"]), Some("8"));
+    assert_eq!(parsed.steps, ["Bake 20 minutes."]);
+}
+
+#[test]
+fn utils_cooklang_write_comment_markers() {
+    // -- FIXTURE
+    let recipe = CooklangRecipe {
+        metadata: vec![
+            ("title".to_string(), "Cornbread".to_string()),
+            ("source".to_string(), "Grandma -- the best [-ever-]".to_string()),
+        ],
+        notes: vec!["--- keep warm".to_string()],
+        steps: vec!["Whisk -- gently -- then bake.".to_string()],
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let parsed = CooklangRecipe::parse(&recipe.to_string());
+
+    // -- CHECK
+    assert_eq!(parsed.meta(&["source"]), Some("Grandma - the best [ -ever-]"));
+    assert_eq!(parsed.notes, ["- keep warm"]);
+    assert_eq!(parsed.steps, ["Whisk - gently - then bake."]);
+}
+
+#[test]
+fn utils_cooklang_write() {
+    // -- FIXTURE
+    let recipe = CooklangRecipe {
+        metadata: vec![("title".to_string(), "Cornbread".to_string())],
+        ingredients: vec![
+            CooklangIngredient {
+                name: "yellow cornmeal".to_string(),
+                quantity: Some("1.5".to_string()),
+                unit: Some("cup".to_string()),
+                preparation: None,
+            },
+            CooklangIngredient {
+                name: "butter".to_string(),
+                preparation: Some("melted".to_string()),
+                ..Default::default()
+            },
+        ],
+        cookware: vec!["skillet".to_string(), "whisk".to_string()],
+        steps: vec!["Heat the skillet, stir in the cornmeal and bake 20 minutes.".to_string()],
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let text = recipe.to_string();
+
+    // -- CHECK
+    assert_eq!(
+        text,
+        ">> title: Cornbread\n\
+         \nIngredients: @butter{}(melted).\n\
+         \nCookware: #whisk{}.\n\
+         \nHeat the #skillet{}, stir in the @yellow cornmeal{1.5%cup} and bake ~{20%minutes}.\n"
+    );
+    let parsed = CooklangRecipe::parse(&text);
+    assert_eq!(parsed.ingredients.len(), 2);
+    assert_eq!(parsed.cookware, ["whisk", "skillet"]);
+    assert_eq!(
+        parsed.steps.last().unwrap(),
+        "Heat the skillet, stir in the yellow cornmeal and bake 20 minutes."
+    );
+}
@@ -0,0 +1,36 @@
+use super::{format_minutes, iso8601_duration, parse_minutes};
+
+#[test]
+fn utils_duration_parse_minutes() {
+    assert_eq!(parse_minutes("1 hr 20 mins"), Some(80));
+    assert_eq!(parse_minutes("1 hour 20 minutes"), Some(80));
+    assert_eq!(parse_minutes("1h20m"), Some(80));
+    assert_eq!(parse_minutes("1½ hours"), Some(90));
+    assert_eq!(parse_minutes("20-25 minutes"), Some(25));
+    assert_eq!(parse_minutes("1:20"), Some(80));
+    assert_eq!(parse_minutes("45"), Some(45));
+    assert_eq!(parse_minutes("PT1H20M"), Some(80));
+    assert_eq!(parse_minutes("P1DT2H"), Some(1560));
+    assert_eq!(parse_minutes(""), None);
+    assert_eq!(parse_minutes("a while"), None);
+}
+
+#[test]
+fn utils_duration_parse_minutes_bounded() {
+    assert_eq!(parse_minutes("99999999:00"), None);
+    assert_eq!(parse_minutes("PT9999999999M"), None);
+    assert_eq!(parse_minutes("99999999999 hours"), None);
+    assert_eq!(parse_minutes("-30"), None);
+    assert_eq!(parse_minutes("P365D"), Some(525_600));
+}
+
+#[test]
+fn utils_duration_format() {
+    assert_eq!(iso8601_duration(80), "PT1H20M");
+    assert_eq!(iso8601_duration(45), "PT45M");
+    assert_eq!(iso8601_duration(120), "PT2H");
+
+    assert_eq!(format_minutes(80), "1 hr 20 mins");
+    assert_eq!(format_minutes(1), "1 min");
+    assert_eq!(format_minutes(120), "2 hrs");
+}
@@ -0,0 +1,65 @@
+use std::io::{Cursor, Read};
+
+use zip::{CompressionMethod, ZipArchive};
+
+use super::{data_url_image, xml_escape, EpubBook, EpubChapter, EpubImage};
+
+#[test]
+fn utils_epub_write() -> Result<(), Box<dyn std::error::Error>> {
+    // -- FIXTURE
+    let book = EpubBook {
+        identifier: "urn:test:book".to_string(),
+        title: "Mac & Cheese".to_string(),
+        author: Some("Goombah!".to_string()),
+        language: "en".to_string(),
+        modified: "2024-01-02T03:04:05Z".to_string(),
+        subjects: vec!["Dinner".to_string()],
+        chapters: vec![EpubChapter {
+            file: "recipe-1.xhtml".to_string(),
+            title: "Mac & Cheese".to_string(),
+            body: "<h1>Mac &amp; Cheese</h1>\n<img src=\"images/recipe-1.png\" alt=\"\"/>\n".to_string(),
+        }],
+        images: vec![EpubImage {
+            file: "recipe-1.png".to_string(),
+            media_type: "image/png".to_string(),
+            data: b"\x89PNG".to_vec(),
+        }],
+        sections: vec![("Dinner".to_string(), vec![0])],
+    };
+
+    // -- ACTION
+    let bytes = book.write()?;
+
+    // -- CHECK
+    let mut zip = ZipArchive::new(Cursor::new(bytes))?;
+    let mimetype = zip.by_index(0)?;
+    assert_eq!(mimetype.name(), "mimetype");
+    assert_eq!(mimetype.compression(), CompressionMethod::Stored);
+    drop(mimetype);
+
+    let mut package = String::new();
+    zip.by_name("OEBPS/content.opf")?.read_to_string(&mut package)?;
+    assert!(package.contains("<dc:title>Mac &amp; Cheese</dc:title>"));
+    assert!(package.contains(r#"<meta property="schema:accessMode">visual</meta>"#));
+    assert!(package.contains(r#"href="images/recipe-1.png" media-type="image/png""#));
+    assert!(package.contains(r#"<itemref idref="chapter-0"/>"#));
+
+    let mut nav = String::new();
+    zip.by_name("OEBPS/nav.xhtml")?.read_to_string(&mut nav)?;
+    assert!(nav.contains("<li><span>Dinner</span>"));
+    assert!(nav.contains(r#"<a href="recipe-1.xhtml">Mac &amp; Cheese</a>"#));
+    assert!(zip.by_name("OEBPS/images/recipe-1.png").is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn utils_epub_helpers() {
+    assert_eq!(xml_escape("<b>\"Salt\" & 'pepper'</b>\u{1}"), "&lt;b&gt;&quot;Salt&quot; &amp; &#39;pepper&#39;&lt;/b&gt;");
+    assert_eq!(
+        data_url_image("data:image/png;base64,iVBORw=="),
+        Some(("image/png".to_string(), vec![0x89, 0x50, 0x4e, 0x47]))
+    );
+    assert_eq!(data_url_image("https://example.com/cake.png"), None);
+    assert_eq!(data_url_image("data:text/html;base64,PGI+"), None);
+}
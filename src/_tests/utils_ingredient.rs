@@ -0,0 +1,78 @@
+use super::{normalize_fractions, parse_ingredient, Quantity};
+
+#[test]
+fn utils_ingredient_mixed_fraction_with_preparation() {
+    // -- ACTION
+    let ingredient = parse_ingredient("2 1/2 cups all-purpose flour, sifted");
+
+    // -- CHECK
+    assert_eq!(ingredient.quantity, Some(Quantity { value: 2.5, max: None }));
+    assert_eq!(ingredient.unit.as_deref(), Some("cup"));
+    assert_eq!(ingredient.name, "all-purpose flour");
+    assert_eq!(ingredient.preparation.as_deref(), Some("sifted"));
+    assert_eq!(ingredient.raw, "2 1/2 cups all-purpose flour, sifted");
+}
+
+#[test]
+fn utils_ingredient_unicode_fractions() {
+    assert_eq!(normalize_fractions("1½ cups"), "1 1/2 cups");
+
+    let ingredient = parse_ingredient("¾ tsp. kosher salt");
+    assert_eq!(ingredient.quantity, Some(Quantity { value: 0.75, max: None }));
+    assert_eq!(ingredient.unit.as_deref(), Some("tsp"));
+    assert_eq!(ingredient.name, "kosher salt");
+
+    let ingredient = parse_ingredient("1⅓ cup milk");
+    assert_eq!(ingredient.quantity.map(|q| q.value), Some(1.0 + 1.0 / 3.0));
+}
+
+#[test]
+fn utils_ingredient_ranges() {
+    let ingredient = parse_ingredient("2-3 cloves garlic, minced");
+    assert_eq!(ingredient.quantity, Some(Quantity { value: 2.0, max: Some(3.0) }));
+    assert_eq!(ingredient.unit.as_deref(), Some("clove"));
+    assert_eq!(ingredient.name, "garlic");
+
+    let ingredient = parse_ingredient("1 to 1 1/2 lbs chicken thighs");
+    assert_eq!(ingredient.quantity, Some(Quantity { value: 1.0, max: Some(1.5) }));
+    assert_eq!(ingredient.unit.as_deref(), Some("lb"));
+
+    let ingredient = parse_ingredient("3–4 T. butter");
+    assert_eq!(ingredient.quantity, Some(Quantity { value: 3.0, max: Some(4.0) }));
+    assert_eq!(ingredient.unit.as_deref(), Some("tbsp"));
+}
+
+#[test]
+fn utils_ingredient_parenthetical_notes() {
+    let ingredient = parse_ingredient("1 (14 oz) can diced tomatoes (optional)");
+    assert_eq!(ingredient.quantity.map(|q| q.value), Some(1.0));
+    assert_eq!(ingredient.unit.as_deref(), Some("can"));
+    assert_eq!(ingredient.name, "diced tomatoes");
+    assert_eq!(ingredient.note.as_deref(), Some("14 oz; optional"));
+}
+
+#[test]
+fn utils_ingredient_no_quantity() {
+    let ingredient = parse_ingredient("Salt and pepper, to taste");
+    assert_eq!(ingredient.quantity, None);
+    assert_eq!(ingredient.unit, None);
+    assert_eq!(ingredient.name, "Salt and pepper");
+    assert_eq!(ingredient.preparation.as_deref(), Some("to taste"));
+
+    // no quantity, so "cups" is part of the name
+    let ingredient = parse_ingredient("cups of ice");
+    assert_eq!(ingredient.unit, None);
+}
+
+#[test]
+fn utils_ingredient_attached_unit_and_decimal() {
+    let ingredient = parse_ingredient("100g dark chocolate");
+    assert_eq!(ingredient.quantity.map(|q| q.value), Some(100.0));
+    assert_eq!(ingredient.unit.as_deref(), Some("g"));
+    assert_eq!(ingredient.name, "dark chocolate");
+
+    let ingredient = parse_ingredient("1.5 fl oz of lime juice");
+    assert_eq!(ingredient.quantity.map(|q| q.value), Some(1.5));
+    assert_eq!(ingredient.unit.as_deref(), Some("fl oz"));
+    assert_eq!(ingredient.name, "lime juice");
+}
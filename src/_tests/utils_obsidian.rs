@@ -0,0 +1,124 @@
+use std::io::{Cursor, Write};
+
+use zip::{write::FileOptions, ZipWriter};
+
+use super::{read_vault, RecipeNote};
+use crate::utils::ArchiveError;
+
+const HAND_WRITTEN: &str = "---
+tags: dinner, quick
+servings: 4 people
+cook: 20 mins
+---
+# Weeknight Chili
+
+Our go-to.
+
+## Ingredients
+- [ ] 1 lb ground beef
+* 1 can beans
+
+### Toppings
+- cheddar
+
+## Directions
+1) Brown the beef,
+   then drain it.
+2) Simmer.
+
+## Notes
+Freezes well.
+";
+
+#[test]
+fn utils_obsidian_parse() {
+    // -- ACTION
+    let note = RecipeNote::parse(HAND_WRITTEN, "chili").unwrap();
+
+    // -- CHECK
+    assert_eq!(note.id, None);
+    assert_eq!(note.title, "Weeknight Chili");
+    assert_eq!(note.header.as_deref(), Some("Our go-to."));
+    assert_eq!(note.tags, Some(vec!["dinner".to_string(), "quick".to_string()]));
+    assert_eq!(note.servings, Some(4));
+    assert_eq!(note.cook_time.as_deref(), Some("20 mins"));
+    assert_eq!(
+        note.ingredients.unwrap(),
+        ["1 lb ground beef", "1 can beans", "cheddar"]
+    );
+    assert_eq!(note.steps.unwrap(), ["Brown the beef, then drain it.", "Simmer."]);
+    assert_eq!(note.equipment, None, "no section, nothing to change");
+}
+
+#[test]
+fn utils_obsidian_round_trip() {
+    // -- FIXTURE
+    let note = RecipeNote {
+        id: Some(7),
+        title: "Mac & Cheese: Baked".to_string(),
+        header: Some("Crisp top.\n\nBest the same day.".to_string()),
+        tags: Some(vec!["Dinner".to_string()]),
+        prep_time: Some("15 mins".to_string()),
+        servings: Some(6),
+        source_url: Some("https://example.com/mac".to_string()),
+        updated: Some("2024-01-02T03:04:05.123456+00:00".to_string()),
+        ingredients: Some(vec!["1. 5 cups macaroni".to_string(), "- 2 cups cheddar".to_string()]),
+        steps: Some(vec!["Boil.".to_string(), "Bake 20 minutes.".to_string()]),
+        equipment: Some(vec!["baking dish".to_string()]),
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let text = note.to_string();
+    let parsed = RecipeNote::parse(&text, "ignored").unwrap();
+
+    // -- CHECK
+    assert!(text.starts_with("---\nid: 7\ntitle: 'Mac & Cheese: Baked'\n"), "{}", text);
+    assert!(text.contains("\n## Steps\n\n1. Boil.\n2. Bake 20 minutes.\n"));
+    assert_eq!(note.file_name(), "Mac & Cheese Baked.md");
+    assert_eq!(parsed, note);
+}
+
+#[test]
+fn utils_obsidian_read_vault() {
+    // -- FIXTURE
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let files: [(&str, &[u8]); 4] = [
+        ("Vault/Recipes/Chili.md", HAND_WRITTEN.as_bytes()),
+        ("Vault/.obsidian/workspace.md", b"# Not a recipe"),
+        ("Vault/Broken.md", b"---\nid: [1\n---\n"),
+        ("Vault/pantry.csv", b"flour,1"),
+    ];
+    for (name, content) in files {
+        zip.start_file(name, FileOptions::default()).unwrap();
+        zip.write_all(content).unwrap();
+    }
+    let bytes = zip.finish().unwrap().into_inner();
+
+    // -- ACTION
+    let entries = read_vault(&bytes).unwrap();
+
+    // -- CHECK
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].name, "Vault/Recipes/Chili.md");
+    assert_eq!(entries[0].note.as_ref().unwrap().title, "Weeknight Chili");
+    assert!(entries[1].note.is_err());
+}
+
+#[test]
+fn utils_obsidian_read_vault_too_many_notes() {
+    // -- FIXTURE
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for i in 0..201 {
+        zip.start_file(format!("Vault/{}.md", i), FileOptions::default())
+            .unwrap();
+        zip.write_all(b"# Toast").unwrap();
+    }
+    let bytes = zip.finish().unwrap().into_inner();
+
+    // -- ACTION
+    let result = read_vault(&bytes);
+
+    // -- CHECK
+    assert!(matches!(result, Err(ArchiveError::TooManyRecipes(200))));
+}
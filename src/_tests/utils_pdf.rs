@@ -0,0 +1,38 @@
+use super::{win_ansi, wrap, Font, PageSize, PdfDocument};
+
+#[test]
+fn utils_pdf_wrap() {
+    // -- ACTION
+    let lines = wrap("Whisk the flour with the salt and baking powder", Font::Regular, 12.0, 120.0);
+
+    // -- CHECK
+    assert_eq!(lines, ["Whisk the flour with", "the salt and baking", "powder"]);
+    assert_eq!(win_ansi("Crème brûlée – 2½ cups…"), b"Cr\xe8me br\xfbl\xe9e \x96 2\xbd cups\x85");
+    assert_eq!(win_ansi("鍋"), b"?");
+}
+
+#[test]
+fn utils_pdf_pages() {
+    // -- FIXTURE
+    let mut doc = PdfDocument::new(PageSize::A4);
+    doc.centered("My Cookbook", Font::Bold, 28.0);
+    let mut body = PdfDocument::new(PageSize::A4);
+    body.new_page();
+    for index in 0..80 {
+        body.paragraph(&format!("Step {}", index), Font::Regular, 11.0, 0.0);
+    }
+
+    // -- ACTION
+    doc.link_line("Step 0", "2", Font::Regular, 11.0, 0.0, 1);
+    doc.append(body);
+    doc.number_pages(1);
+    let pdf = doc.finish("My Cookbook");
+
+    // -- CHECK
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.starts_with("%PDF-"));
+    assert!(text.contains("/Count 3"), "80 lines run over two body pages");
+    assert!(text.contains("/MediaBox [0 0 595.28 841.89]"));
+    assert!(text.contains("/Subtype /Link"));
+    assert!(text.contains("(My Cookbook)"));
+}
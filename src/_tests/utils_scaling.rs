@@ -0,0 +1,43 @@
+use super::scale_ingredient;
+use crate::utils::{parse_ingredient, Quantity};
+
+#[test]
+fn utils_scaling_promotes_units() {
+    // -- ACTION
+    let ingredient = scale_ingredient(&parse_ingredient("3 tbsp sugar"), 16.0);
+
+    // -- CHECK
+    assert_eq!(ingredient.quantity, Some(Quantity { value: 3.0, max: None }));
+    assert_eq!(ingredient.unit.as_deref(), Some("cup"));
+    assert_eq!(ingredient.raw, "3 cups sugar");
+}
+
+#[test]
+fn utils_scaling_demotes_units_and_rounds() {
+    // -- ACTION
+    let ingredient = scale_ingredient(&parse_ingredient("1/2 cup butter, melted"), 0.25);
+
+    // -- CHECK
+    assert_eq!(ingredient.quantity, Some(Quantity { value: 2.0, max: None }));
+    assert_eq!(ingredient.unit.as_deref(), Some("tbsp"));
+    assert_eq!(ingredient.raw, "2 tbsp butter, melted");
+
+    let ingredient = scale_ingredient(&parse_ingredient("1 cup milk"), 1.0 / 3.0);
+    assert_eq!(ingredient.raw, "1/3 cup milk");
+}
+
+#[test]
+fn utils_scaling_counts_ranges_and_no_quantity() {
+    let ingredient = scale_ingredient(&parse_ingredient("2-3 cloves garlic, minced"), 1.5);
+    assert_eq!(ingredient.quantity, Some(Quantity { value: 3.0, max: Some(4.5) }));
+    assert_eq!(ingredient.raw, "3-4 1/2 cloves garlic, minced");
+
+    let ingredient = scale_ingredient(&parse_ingredient("3 eggs"), 0.5);
+    assert_eq!(ingredient.raw, "1 1/2 eggs");
+
+    let ingredient = scale_ingredient(&parse_ingredient("800 g flour"), 2.0);
+    assert_eq!(ingredient.raw, "1.6 kg flour");
+
+    let ingredient = scale_ingredient(&parse_ingredient("salt to taste"), 2.0);
+    assert_eq!(ingredient.raw, "salt to taste");
+}
@@ -0,0 +1,81 @@
+use super::{fetch_html, normalize_source_url, scrape_recipe_html, FetchPolicy, ScrapeError};
+use crate::utils::UnitSystem;
+
+const JSON_LD_PAGE: &str = include_str!("fixtures/recipe_json_ld.html");
+const HEURISTIC_PAGE: &str = include_str!("fixtures/recipe_heuristic.html");
+
+#[test]
+fn utils_scraping_prefers_json_ld() -> Result<(), ScrapeError> {
+    // -- ACTION
+    let recipe = scrape_recipe_html("https://example.com/cornbread", JSON_LD_PAGE, None)?;
+
+    // -- CHECK
+    assert_eq!(recipe.source, "json-ld");
+    assert_eq!(recipe.title.as_deref(), Some("Skillet Cornbread"));
+    assert_eq!(recipe.image_url.as_deref(), Some("https://example.com/cornbread.jpg"));
+    assert_eq!(recipe.ingredients.len(), 3);
+    assert_eq!(recipe.ingredients[0], "1 1/2 cups cornmeal");
+    assert_eq!(recipe.steps[2], "Bake at 425°F until golden.");
+    assert_eq!(recipe.total_minutes, Some(35), "derived from prep + cook");
+    assert_eq!(recipe.servings, Some(8));
+    assert_eq!(recipe.prep_time.as_deref(), Some("10 mins"));
+    assert_eq!(
+        recipe.header.as_deref(),
+        Some("Crispy edges & a tender middle."),
+        "og:description only fills gaps"
+    );
+    assert_eq!(recipe.parsed_ingredients[0].unit.as_deref(), Some("cup"));
+    assert_eq!(recipe.author.as_deref(), Some("Ada Baker"));
+    assert_eq!(recipe.source_url, "https://example.com/recipes/skillet-cornbread", "og:url, normalized");
+    assert_eq!(recipe.source_name.as_deref(), Some("Test Kitchen"));
+
+    Ok(())
+}
+
+#[test]
+fn utils_scraping_heuristic_fallback() -> Result<(), ScrapeError> {
+    // -- ACTION
+    let recipe = scrape_recipe_html(
+        "https://example.com/pancakes",
+        HEURISTIC_PAGE,
+        Some(UnitSystem::Metric),
+    )?;
+
+    // -- CHECK
+    assert_eq!(recipe.source, "heuristic");
+    assert_eq!(recipe.title.as_deref(), Some("Grandma's Pancakes"));
+    assert_eq!(recipe.ingredients[0], "473 ml flour");
+    assert_eq!(recipe.steps[1], "Cook on a hot griddle.");
+    assert_eq!(recipe.prep_time.as_deref(), Some("1 hr 20 mins"), "page order is kept");
+    assert_eq!(recipe.prep_minutes, Some(80));
+
+    Ok(())
+}
+
+#[test]
+fn utils_scraping_no_recipe() {
+    let result = scrape_recipe_html("https://example.com/about", "<p>About us</p>", None);
+
+    assert!(matches!(result, Err(ScrapeError::NoRecipeFound(_))));
+}
+
+#[tokio::test]
+async fn utils_scraping_invalid_url() {
+    for url in ["not a url", "ftp://example.com/recipe", "file:///etc/passwd"] {
+        let result = fetch_html(url, &FetchPolicy::default()).await;
+
+        assert!(matches!(result, Err(ScrapeError::InvalidUrl(_))), "{}", url);
+    }
+}
+
+#[test]
+fn utils_scraping_normalize_source_url() {
+    for (url, expected) in [
+        ("https://example.com/cornbread/", "https://example.com/cornbread"),
+        ("https://Example.com/cornbread#comments", "https://example.com/cornbread"),
+        ("https://example.com/r?id=7&utm_source=feed", "https://example.com/r?id=7"),
+        ("https://example.com/", "https://example.com/"),
+    ] {
+        assert_eq!(normalize_source_url(url), expected, "{}", url);
+    }
+}
@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+use super::{discover_site, Robots, Sitemap};
+use crate::{
+    test_utils::{http_response, stand_in},
+    utils::{FetchPolicy, ScrapeError},
+};
+
+const ROBOTS: &str = "
+# everyone
+User-agent: *
+Disallow: /private/
+Allow: /private/recipes/
+Disallow: /*.pdf$
+Crawl-delay: 2
+
+User-agent: BadBot
+User-agent: digital_cookbook
+Disallow: /drafts
+
+Sitemap: https://example.com/sitemap_index.xml
+";
+
+#[test]
+fn utils_scraping_crawl_robots_default_group() {
+    let robots = Robots::parse(ROBOTS, "SomeOtherAgent/1.0");
+
+    assert!(robots.is_allowed("/recipes/cornbread"));
+    assert!(!robots.is_allowed("/private/notes"));
+    assert!(robots.is_allowed("/private/recipes/stew"), "longest rule wins");
+    assert!(!robots.is_allowed("/menus/dinner.pdf"));
+    assert!(robots.is_allowed("/menus/dinner.pdf?page=2"), "$ anchors the end");
+    assert_eq!(robots.crawl_delay, Some(Duration::from_secs(2)));
+    assert_eq!(robots.sitemaps, vec!["https://example.com/sitemap_index.xml"]);
+}
+
+#[test]
+fn utils_scraping_crawl_robots_own_group() {
+    let robots = Robots::parse(ROBOTS, "digital_cookbook/0.1.0");
+
+    assert!(!robots.is_allowed("/drafts/cornbread"));
+    assert!(robots.is_allowed("/private/notes"), "only our group applies");
+    assert_eq!(robots.crawl_delay, None);
+}
+
+#[test]
+fn utils_scraping_crawl_robots_agent_exact() {
+    let text = "User-agent: Digital\nDisallow: /\n\nUser-agent: DIGITAL_COOKBOOK\nDisallow: /drafts\n";
+
+    let robots = Robots::parse(text, "digital_cookbook/0.1.0");
+
+    assert!(robots.is_allowed("/recipes"), "a part of our token is another agent");
+    assert!(!robots.is_allowed("/drafts/cornbread"), "tokens compare case-insensitively");
+}
+
+#[test]
+fn utils_scraping_crawl_robots_capped() {
+    let mut text = "User-agent: *\n".to_string();
+    for i in 0..1000 {
+        text.push_str(&format!("Disallow: /page-{}$\nSitemap: https://example.com/{}.xml\n", i, i));
+    }
+
+    let robots = Robots::parse(&text, "digital_cookbook/0.1.0");
+
+    assert!(!robots.is_allowed("/page-499"));
+    assert!(robots.is_allowed("/page-500"), "rules past the cap are dropped");
+    assert_eq!(robots.sitemaps.len(), 20);
+}
+
+#[test]
+fn utils_scraping_crawl_robots_delay_capped() {
+    let robots = Robots::parse("User-agent: *\nCrawl-delay: 86400\n", "digital_cookbook/0.1.0");
+
+    assert_eq!(robots.crawl_delay, Some(Duration::from_secs(5)));
+}
+
+#[test]
+fn utils_scraping_crawl_sitemap() {
+    let index = Sitemap::parse(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+        <sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+          <sitemap><loc>https://example.com/sitemap-posts.xml</loc></sitemap>
+          <sitemap><loc>https://example.com/sitemap-pages.xml</loc></sitemap>
+        </sitemapindex>"#,
+    );
+    let urlset = Sitemap::parse(
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+          <url><loc>
+            https://example.com/recipes/stew?a=1&amp;b=2
+          </loc><lastmod>2020-01-01</lastmod></url>
+          <url><loc><![CDATA[https://example.com/recipes/cornbread]]></loc></url>
+        </urlset>"#,
+    );
+
+    assert_eq!(index.sitemaps.len(), 2);
+    assert!(index.pages.is_empty());
+    assert_eq!(
+        urlset.pages,
+        vec!["https://example.com/recipes/stew?a=1&b=2", "https://example.com/recipes/cornbread"]
+    );
+}
+
+#[tokio::test]
+async fn utils_scraping_crawl_discover_site() -> Result<(), ScrapeError> {
+    // -- FIXTURE
+    let (addr, _) = stand_in(move |request| {
+        let base = format!("http://{}", request_host(request));
+        let xml = [("Content-Type", "application/xml")];
+        let path = request.split_whitespace().nth(1).unwrap_or_default();
+        match path {
+            "/robots.txt" => http_response(
+                "200 OK",
+                &[("Content-Type", "text/plain")],
+                &format!("User-agent: *\nDisallow: /private/\nSitemap: {}/sitemap_index.xml\n", base),
+            ),
+            "/sitemap_index.xml" => http_response(
+                "200 OK",
+                &xml,
+                &format!(
+                    "<sitemapindex><sitemap><loc>{0}/posts.xml</loc></sitemap>\
+                     <sitemap><loc>{0}/missing.xml</loc></sitemap>\
+                     <sitemap><loc>https://elsewhere.example/sitemap.xml</loc></sitemap></sitemapindex>",
+                    base
+                ),
+            ),
+            "/posts.xml" => http_response(
+                "200 OK",
+                &xml,
+                &format!(
+                    "<urlset><url><loc>{0}/recipes/cornbread</loc></url>\
+                     <url><loc>{0}/private/secret-sauce</loc></url>\
+                     <url><loc>https://elsewhere.example/recipes/stew</loc></url>\
+                     <url><loc>{0}/recipes/pancakes</loc></url></urlset>",
+                    base
+                ),
+            ),
+            _ => http_response("404 Not Found", &[], ""),
+        }
+    })
+    .await;
+    let policy = FetchPolicy {
+        allow_private: true,
+        ..Default::default()
+    };
+    let base = format!("http://{}", addr);
+
+    // -- ACTION
+    let discovery = discover_site(&format!("{}/any/page", base), &policy, 10).await?;
+    let capped = discover_site(&base, &policy, 1).await?;
+
+    // -- CHECK
+    assert_eq!(
+        discovery.pages,
+        vec![format!("{}/recipes/cornbread", base), format!("{}/recipes/pancakes", base)],
+        "same host, allowed, in sitemap order"
+    );
+    assert_eq!(discovery.disallowed, vec![format!("{}/private/secret-sauce", base)]);
+    assert_eq!(capped.pages.len(), 1);
+    assert_eq!(capped.disallowed.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn utils_scraping_crawl_disallowed_capped() -> Result<(), ScrapeError> {
+    // -- FIXTURE
+    let (addr, _) = stand_in(move |request| {
+        let base = format!("http://{}", request_host(request));
+        let path = request.split_whitespace().nth(1).unwrap_or_default();
+        match path {
+            "/robots.txt" => http_response("200 OK", &[("Content-Type", "text/plain")], "User-agent: *\nDisallow: /private/\n"),
+            "/sitemap.xml" => {
+                let urls: String = (0..50)
+                    .map(|i| format!("<url><loc>{}/private/{}</loc></url>", base, i))
+                    .collect();
+                http_response("200 OK", &[("Content-Type", "application/xml")], &format!("<urlset>{}</urlset>", urls))
+            }
+            _ => http_response("404 Not Found", &[], ""),
+        }
+    })
+    .await;
+    let policy = FetchPolicy {
+        allow_private: true,
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let discovery = discover_site(&format!("http://{}/", addr), &policy, 10).await?;
+
+    // -- CHECK
+    assert!(discovery.pages.is_empty());
+    assert_eq!(discovery.disallowed.len(), 10);
+    assert_eq!(discovery.disallowed_more, 40);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn utils_scraping_crawl_no_sitemap() {
+    // -- FIXTURE
+    let (addr, _) = stand_in(|_| http_response("404 Not Found", &[], "")).await;
+    let policy = FetchPolicy {
+        allow_private: true,
+        ..Default::default()
+    };
+
+    // -- ACTION
+    let result = discover_site(&format!("http://{}/", addr), &policy, 10).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(ScrapeError::FetchFailed(..))), "{:?}", result);
+}
+
+fn request_host(request: &str) -> &str {
+    request
+        .lines()
+        .find_map(|line| line.strip_prefix("host: ").or_else(|| line.strip_prefix("Host: ")))
+        .unwrap_or_default()
+        .trim()
+}
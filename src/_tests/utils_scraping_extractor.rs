@@ -0,0 +1,86 @@
+use scraper::{Html, Selector};
+
+use super::{ExtractedRecipe, ExtractorRegistry, RecipeExtractor};
+
+const HEURISTIC_PAGE: &str = include_str!("fixtures/recipe_heuristic.html");
+const JSON_LD_PAGE: &str = include_str!("fixtures/recipe_json_ld.html");
+
+/// A site whose steps live in `<p class="method">` paragraphs
+struct MethodParagraphs;
+
+impl RecipeExtractor for MethodParagraphs {
+    fn name(&self) -> &'static str {
+        "method-paragraphs"
+    }
+
+    fn extract(&self, html: &Html) -> Option<ExtractedRecipe> {
+        let selector = Selector::parse("p.method").unwrap();
+        let steps: Vec<String> = html.select(&selector).map(|p| p.text().collect()).collect();
+
+        Some(ExtractedRecipe {
+            title: Some("Site Title".to_string()),
+            ingredients: vec!["1 egg".to_string()],
+            steps,
+            ..Default::default()
+        })
+    }
+}
+
+#[test]
+fn utils_scraping_extractor_site_specific() {
+    // -- FIXTURE
+    let mut registry = ExtractorRegistry::with_defaults();
+    registry.register_site("www.example.com", Box::new(MethodParagraphs));
+    let html = Html::parse_document(r#"<p class="method">Crack the egg.</p><p class="method">Fry it.</p>"#);
+
+    // -- ACTION
+    let extraction = registry.extract(Some("example.com"), &html).unwrap();
+    let other_site = registry.extract(Some("other.com"), &html);
+
+    // -- CHECK
+    assert_eq!(extraction.source, "method-paragraphs");
+    assert_eq!(extraction.recipe.steps, vec!["Crack the egg.", "Fry it."]);
+    assert!(other_site.is_none(), "registered for example.com only");
+}
+
+#[test]
+fn utils_scraping_extractor_confidence_and_fill() {
+    // -- FIXTURE
+    let registry = ExtractorRegistry::with_defaults();
+
+    // -- ACTION
+    let json_ld = registry.extract(None, &Html::parse_document(JSON_LD_PAGE)).unwrap();
+    let heuristic = registry.extract(None, &Html::parse_document(HEURISTIC_PAGE)).unwrap();
+
+    // -- CHECK
+    assert_eq!(json_ld.source, "json-ld");
+    assert_eq!(json_ld.recipe.ingredients.len(), 3, "not the heuristic list");
+    assert_eq!(
+        json_ld.recipe.image_url.as_deref(),
+        Some("https://example.com/cornbread.jpg")
+    );
+    assert!(json_ld.confidence > heuristic.confidence);
+
+    assert_eq!(heuristic.source, "heuristic");
+    assert_eq!(heuristic.recipe.ingredients.len(), 2);
+}
+
+#[test]
+fn utils_scraping_extractor_completeness() {
+    let empty = ExtractedRecipe::default();
+    assert_eq!(empty.completeness(), 0.0);
+
+    let mut partial = ExtractedRecipe {
+        title: Some("Toast".to_string()),
+        ..Default::default()
+    };
+    partial.fill_missing(&ExtractedRecipe {
+        title: Some("Ignored".to_string()),
+        steps: vec!["Toast the bread.".to_string()],
+        ..Default::default()
+    });
+
+    assert_eq!(partial.title.as_deref(), Some("Toast"));
+    assert_eq!(partial.steps, vec!["Toast the bread."]);
+    assert!((partial.completeness() - 0.5).abs() < 0.001);
+}
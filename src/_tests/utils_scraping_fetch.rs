@@ -0,0 +1,128 @@
+use std::net::IpAddr;
+
+use super::{fetch_html, is_blocked_ip, FetchPolicy};
+use crate::{
+    test_utils::{http_response, stand_in},
+    utils::ScrapeError,
+};
+
+fn test_policy() -> FetchPolicy {
+    FetchPolicy {
+        allow_private: true,
+        user_agent: "cookbook-test-agent".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn utils_scraping_fetch_blocked_ips() {
+    for ip in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.1",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "::1",
+        "::ffff:127.0.0.1",
+        "fd00:ec2::254",
+        "fe80::1",
+        "64:ff9b::169.254.169.254",
+        "64:ff9b::10.0.0.1",
+        "64:ff9b:1::1",
+        "2002:a9fe:a9fe::1",
+        "2002:7f00:1::",
+        "::169.254.169.254",
+        "::10.0.0.1",
+    ] {
+        assert!(is_blocked_ip(ip.parse::<IpAddr>().unwrap()), "{}", ip);
+    }
+
+    for ip in [
+        "93.184.216.34",
+        "8.8.8.8",
+        "2606:2800:220:1::1",
+        "64:ff9b::8.8.8.8",
+        "2002:808:808::1",
+    ] {
+        assert!(!is_blocked_ip(ip.parse::<IpAddr>().unwrap()), "{}", ip);
+    }
+}
+
+#[tokio::test]
+async fn utils_scraping_fetch_blocks_private_hosts() {
+    // -- FIXTURE
+    let body = "<html><h1>Internal</h1></html>";
+    let response = http_response("200 OK", &[("Content-Type", "text/html")], body);
+    let (addr, _) = stand_in(move |_| response.clone()).await;
+
+    // -- ACTION
+    let result = fetch_html(&format!("http://{}/", addr), &FetchPolicy::default()).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(ScrapeError::BlockedAddress(_))), "{:?}", result);
+}
+
+#[tokio::test]
+async fn utils_scraping_fetch_ok() -> Result<(), ScrapeError> {
+    // -- FIXTURE
+    let body = "<html><h1>Cornbread</h1></html>";
+    let response = http_response("200 OK", &[("Content-Type", "text/html")], body);
+    let (addr, mut requests) = stand_in(move |_| response.clone()).await;
+
+    // -- ACTION
+    let (url, html) = fetch_html(&format!("http://{}/cornbread", addr), &test_policy()).await?;
+
+    // -- CHECK
+    assert_eq!(url.path(), "/cornbread");
+    assert_eq!(html, body);
+    let request = requests.recv().await.unwrap().to_lowercase();
+    assert!(request.contains("user-agent: cookbook-test-agent"), "{}", request);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn utils_scraping_fetch_too_large() {
+    // -- FIXTURE
+    let body = "x".repeat(2048);
+    let response = http_response("200 OK", &[("Content-Type", "text/html")], &body);
+    let (addr, _) = stand_in(move |_| response.clone()).await;
+    let policy = FetchPolicy {
+        max_bytes: 1024,
+        ..test_policy()
+    };
+
+    // -- ACTION
+    let result = fetch_html(&format!("http://{}/", addr), &policy).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(ScrapeError::TooLarge(_, 1024))), "{:?}", result);
+}
+
+#[tokio::test]
+async fn utils_scraping_fetch_redirect_loop() {
+    // -- FIXTURE
+    let response = http_response("302 Found", &[("Location", "/again")], "");
+    let (addr, _) = stand_in(move |_| response.clone()).await;
+
+    // -- ACTION
+    let result = fetch_html(&format!("http://{}/", addr), &test_policy()).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(ScrapeError::TooManyRedirects(_))), "{:?}", result);
+}
+
+#[tokio::test]
+async fn utils_scraping_fetch_not_html() {
+    // -- FIXTURE
+    let response = http_response("200 OK", &[("Content-Type", "application/pdf")], "%PDF");
+    let (addr, _) = stand_in(move |_| response.clone()).await;
+
+    // -- ACTION
+    let result = fetch_html(&format!("http://{}/menu.pdf", addr), &test_policy()).await;
+
+    // -- CHECK
+    assert!(matches!(result, Err(ScrapeError::NotHtml(..))), "{:?}", result);
+}
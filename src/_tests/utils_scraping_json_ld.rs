@@ -0,0 +1,51 @@
+use scraper::Html;
+
+use super::extract_json_ld_recipe;
+
+const JSON_LD_PAGE: &str = include_str!("fixtures/recipe_json_ld.html");
+
+#[test]
+fn utils_scraping_json_ld_graph_and_sections() {
+    // -- ACTION
+    let recipe = extract_json_ld_recipe(&Html::parse_document(JSON_LD_PAGE)).unwrap();
+
+    // -- CHECK
+    assert_eq!(recipe.title.as_deref(), Some("Skillet Cornbread"));
+    assert_eq!(recipe.header.as_deref(), Some("Crispy edges & a tender middle."));
+    assert_eq!(recipe.author.as_deref(), Some("Ada Baker"));
+    assert_eq!(recipe.recipe_yield.as_deref(), Some("8"));
+    assert_eq!(recipe.prep_minutes, Some(10));
+    assert_eq!(recipe.cook_minutes, Some(25));
+    assert_eq!(recipe.total_minutes, None);
+    assert_eq!(
+        recipe.steps,
+        vec![
+            "Whisk the cornmeal and buttermilk.",
+            "Beat in the eggs.",
+            "Bake at 425°F until golden."
+        ]
+    );
+    assert_eq!(recipe.nutrition.get("calories").map(String::as_str), Some("240 kcal"));
+    assert!(!recipe.nutrition.contains_key("@type"));
+}
+
+#[test]
+fn utils_scraping_json_ld_plain_shapes() {
+    let page = r#"<script type="application/ld+json">
+        {"@type": "Recipe", "name": "Toast", "author": "Sam", "image": "https://example.com/toast.jpg",
+         "recipeYield": "Serves 2", "totalTime": "PT5M",
+         "recipeIngredient": ["2 slices bread"], "recipeInstructions": "Toast the bread.\nButter it."}
+    </script>"#;
+
+    // -- ACTION
+    let recipe = extract_json_ld_recipe(&Html::parse_document(page)).unwrap();
+
+    // -- CHECK
+    assert_eq!(recipe.author.as_deref(), Some("Sam"));
+    assert_eq!(recipe.image_url.as_deref(), Some("https://example.com/toast.jpg"));
+    assert_eq!(recipe.servings, Some(2));
+    assert_eq!(recipe.total_minutes, Some(5));
+    assert_eq!(recipe.steps, vec!["Toast the bread.", "Butter it."]);
+
+    assert!(extract_json_ld_recipe(&Html::parse_document("<p>No recipe</p>")).is_none());
+}
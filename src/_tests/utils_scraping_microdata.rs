@@ -0,0 +1,44 @@
+use scraper::Html;
+
+use super::extract_microdata_recipe;
+
+const MICRODATA_PAGE: &str = r#"
+<div itemscope itemtype="https://schema.org/Recipe">
+  <h1 itemprop="name">Garlic Knots</h1>
+  <img itemprop="image" src="https://example.com/knots.jpg">
+  <span itemprop="author" itemscope itemtype="https://schema.org/Person">
+    <span itemprop="name">Lou Dough</span>
+  </span>
+  <meta itemprop="prepTime" content="PT15M"><meta itemprop="cookTime" content="PT12M">
+  <span itemprop="recipeYield">12 knots</span>
+  <ul>
+    <li itemprop="recipeIngredient">1 lb pizza dough</li>
+    <li itemprop="recipeIngredient">3 cloves garlic, minced</li>
+  </ul>
+  <ol itemprop="recipeInstructions">
+    <li>Tie the dough into knots.</li>
+    <li>Bake at 400°F.</li>
+  </ol>
+  <div itemprop="nutrition" itemscope itemtype="https://schema.org/NutritionInformation">
+    <span itemprop="calories">110 calories</span>
+  </div>
+</div>"#;
+
+#[test]
+fn utils_scraping_microdata_recipe() {
+    // -- ACTION
+    let recipe = extract_microdata_recipe(&Html::parse_document(MICRODATA_PAGE)).unwrap();
+
+    // -- CHECK
+    assert_eq!(recipe.title.as_deref(), Some("Garlic Knots"), "not the author's name");
+    assert_eq!(recipe.author.as_deref(), Some("Lou Dough"));
+    assert_eq!(recipe.image_url.as_deref(), Some("https://example.com/knots.jpg"));
+    assert_eq!(recipe.servings, Some(12));
+    assert_eq!(recipe.prep_minutes, Some(15));
+    assert_eq!(recipe.cook_time.as_deref(), Some("12 mins"));
+    assert_eq!(recipe.ingredients, vec!["1 lb pizza dough", "3 cloves garlic, minced"]);
+    assert_eq!(recipe.steps, vec!["Tie the dough into knots.", "Bake at 400°F."]);
+    assert_eq!(recipe.nutrition.get("calories").map(String::as_str), Some("110 calories"));
+
+    assert!(extract_microdata_recipe(&Html::parse_document("<p>No recipe</p>")).is_none());
+}
@@ -0,0 +1,42 @@
+use super::{best_unit, format_quantity, round_quantity, unit_def, UnitSystem};
+
+#[test]
+fn utils_units_best_unit() {
+    let tsp = unit_def("tsp").unwrap();
+    let (unit, amount) = best_unit(48.0, tsp, UnitSystem::Us);
+    assert_eq!(unit.name, "cup");
+    assert!((amount - 1.0).abs() < 0.001);
+
+    let (unit, amount) = best_unit(6.0, tsp, UnitSystem::Us);
+    assert_eq!(unit.name, "tbsp");
+    assert!((amount - 2.0).abs() < 0.001);
+
+    let cup = unit_def("cup").unwrap();
+    let (unit, amount) = best_unit(1.0 / 48.0, cup, UnitSystem::Us);
+    assert_eq!(unit.name, "tsp");
+    assert!((amount - 1.0).abs() < 0.001);
+
+    let g = unit_def("g").unwrap();
+    let (unit, amount) = best_unit(1500.0, g, UnitSystem::Metric);
+    assert_eq!(unit.name, "kg");
+    assert!((amount - 1.5).abs() < 0.001);
+
+    // off the ladder, left alone
+    let pint = unit_def("pint").unwrap();
+    assert_eq!(best_unit(3.0, pint, UnitSystem::Us).0.name, "pint");
+}
+
+#[test]
+fn utils_units_round_and_format() {
+    assert_eq!(round_quantity(0.3, Some("cup")), 1.0 / 3.0);
+    assert_eq!(round_quantity(1.55, Some("tsp")), 1.5);
+    assert_eq!(round_quantity(0.01, Some("tsp")), 0.125);
+    assert_eq!(round_quantity(252.4, Some("g")), 252.0);
+    assert_eq!(round_quantity(1.234, Some("kg")), 1.23);
+
+    assert_eq!(format_quantity(1.5, Some("cup")), "1 1/2");
+    assert_eq!(format_quantity(1.0 / 3.0, Some("cup")), "1/3");
+    assert_eq!(format_quantity(3.0, None), "3");
+    assert_eq!(format_quantity(250.0, Some("g")), "250");
+    assert_eq!(format_quantity(1.25, Some("kg")), "1.25");
+}
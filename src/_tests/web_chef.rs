@@ -1,11 +1,13 @@
 use std::{sync::Arc, str::from_utf8};
-use crate::{web::{chef_rest_filters, handle_rejection}, entities::{chef, recipe}, model::ChefMac};
+use crate::{web::{chef_rest_filters, handle_rejection}, entities::{chef, recipe}, model::{ChefMac, Page}};
 use anyhow::{Result, Context};
 use serde::Deserialize;
 use serde_json::{Value, from_value, from_str, json};
 use warp::{hyper::{body::Bytes, Response}, Filter};
 
 use crate::model::init_db;
+use crate::test_utils::test_token;
+use crate::utils::read_vault;
 
 #[tokio::test]
 async fn web_chef_list() -> Result<()> {
@@ -17,7 +19,7 @@ async fn web_chef_list() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("GET")
-        .header("X-Auth-Token", "1")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
         .path("/api/chefs")
         .reply(&chef_apis)
         .await;
@@ -46,7 +48,7 @@ async fn web_chef_get() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("GET")
-        .header("X-Auth-Token", "1")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
         .path("/api/chefs/firebase_auth_123")
         .reply(&chef_apis)
         .await;
@@ -79,7 +81,7 @@ async fn web_chef_delete() -> Result<()> {
     // -- ACTION
     warp::test::request()
         .method("POST")
-        .header("X-Auth-Token", "firebase_auth_1234 ")
+        .header("X-Auth-Token", test_token("firebase_auth_1234"))
         .path("/api/chefs")
         .json(&body)
         .reply(&chef_apis)
@@ -89,7 +91,7 @@ async fn web_chef_delete() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("DELETE")
-        .header("X-Auth-Token", "firebase_auth_1234")
+        .header("X-Auth-Token", test_token("firebase_auth_1234"))
         .path("/api/chefs")
         .reply(&chef_apis)
         .await;
@@ -124,7 +126,7 @@ async fn web_chef_create() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("POST")
-        .header("X-Auth-Token", "1")
+        .header("X-Auth-Token", test_token("firebase_auth_1234"))
         .path("/api/chefs")
         .json(&body)
         .reply(&chef_apis)
@@ -161,14 +163,16 @@ async fn web_chef_create_fail() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("POST")
-        .header("X-Auth-Token", "firebase_auth_123")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
         .path("/api/chefs")
         .json(&body)
         .reply(&chef_apis)
         .await;
 
     // -- CHECK
-    assert_eq!(400, resp.status(), "http status");
+    assert_eq!(409, resp.status(), "http status");
+    let code = extract_error_code(resp)?;
+    assert_eq!("ENTITY_ALREADY_EXISTS", code);
 
     Ok(())
 }
@@ -187,7 +191,7 @@ async fn web_chef_update() -> Result<()> {
 
     warp::test::request()
         .method("POST")
-        .header("X-Auth-Token", "firebase_auth_1234")
+        .header("X-Auth-Token", test_token("firebase_auth_1234"))
         .path("/api/chefs")
         .json(&body)
         .reply(&chef_apis)
@@ -200,7 +204,7 @@ async fn web_chef_update() -> Result<()> {
 
     let resp = warp::test::request()
         .method("PATCH")
-        .header("X-Auth-Token", "firebase_auth_1234")
+        .header("X-Auth-Token", test_token("firebase_auth_1234"))
         .path("/api/chefs/firebase_auth_1234")
         .json(&update_body)
         .reply(&chef_apis)
@@ -231,13 +235,13 @@ async fn web_chef_get_recipes() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("GET")
-        .header("X-Auth-Token", "firebase_auth_123")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
         .path("/api/chefs/recipes/firebase_auth_123")
         .reply(&chef_apis)
         .await;
 
     // extract the data
-    let recipes: Vec<recipe::Model> = extract_body_data(resp)?;
+    let recipes: Vec<recipe::Model> = extract_body_data::<Page<recipe::Model>>(resp)?.items;
 
     // -- CHECK
     assert_eq!(recipes.len(), 3);
@@ -245,6 +249,88 @@ async fn web_chef_get_recipes() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn web_chef_cookbook_pdf() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let chef_apis = chef_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/chefs/cookbook.pdf?tags=Easy&page_size=letter")
+        .reply(&chef_apis)
+        .await;
+    let missing = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/chefs/cookbook.pdf?tags=Dessert")
+        .reply(&chef_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    assert_eq!(resp.headers()["Content-Type"], "application/pdf");
+    assert!(resp.body().starts_with(b"%PDF-"));
+    assert!(String::from_utf8_lossy(resp.body()).contains("/Count 4"), "two easy recipes");
+    assert_eq!(400, missing.status(), "http status");
+    assert_eq!(extract_error_code(missing)?, "INVALID_QUERY");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_chef_cookbook_epub() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let chef_apis = chef_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/chefs/cookbook.epub?ids=1,2")
+        .reply(&chef_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    assert_eq!(resp.headers()["Content-Type"], "application/epub+zip");
+    assert!(resp.body().starts_with(b"PK\x03\x04"));
+    assert_eq!(&resp.body()[30..38], b"mimetype");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_chef_vault() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let chef_apis = chef_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/chefs/vault.zip?tags=hard")
+        .reply(&chef_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    assert_eq!(resp.headers()["Content-Type"], "application/zip");
+    let notes = read_vault(resp.body())?;
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].name, "Roast Beast.md");
+    assert_eq!(notes[0].note.as_ref().unwrap().id, Some(3));
+
+    Ok(())
+}
+
 
 // region: Web Test Utils
 fn extract_body_data<D>(resp: Response<Bytes>) -> Result<D>
@@ -264,4 +350,11 @@ where
 
     Ok(data)
 }
+
+fn extract_error_code(resp: Response<Bytes>) -> Result<String> {
+    let body = from_utf8(resp.body())?;
+    let body: Value = from_str(body)?;
+
+    Ok(body["error"]["code"].as_str().unwrap_or_default().to_string())
+}
 // endregion: Web Test Utils
\ No newline at end of file
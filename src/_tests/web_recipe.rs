@@ -7,7 +7,7 @@ use serde_json::{from_value, from_str, Value, json};
 
 use warp::{hyper::{ Response, body::Bytes }, Filter};
 
-use crate::{model::{init_db, RecipeMac}, web::{handle_rejection, recipe_rest_filters}, entities::recipe};
+use crate::{model::{init_db, BatchImportRequest, ImportJobMac, ImportJobView, MAX_RUNNING_JOBS, ImportOutcome, ListOptions, Page, RecipeMac, RecipePatch, RecipeSearchHit, RecipeView, ScaledRecipe, VaultOutcome, VaultReportItem}, web::{handle_rejection, recipe_rest_filters}, entities::recipe, security::UserCtx, test_utils::test_token};
 
 #[tokio::test]
 async fn web_recipe_list() -> Result<()> {
@@ -19,7 +19,7 @@ async fn web_recipe_list() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("GET")
-        .header("X-Auth-Token", "1")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
         .path("/api/recipes/")
         .reply(&recipe_apis)
         .await;
@@ -28,7 +28,7 @@ async fn web_recipe_list() -> Result<()> {
     assert_eq!(200, resp.status(), "http status");
 
     // extract response.data
-    let recipes: Vec<recipe::Model> = extract_body_data(resp)?;
+    let recipes: Vec<recipe::Model> = extract_body_data::<Page<recipe::Model>>(resp)?.items;
 
     // -- CHECK - recipes
     assert_eq!(3, recipes.len(), "number of recipes");
@@ -38,6 +38,105 @@ async fn web_recipe_list() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn web_recipe_list_paginated() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/?limit=1&sort=title")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+
+    let page: Page<recipe::Model> = extract_body_data(resp)?;
+
+    assert_eq!(1, page.items.len(), "number of recipes");
+    assert_eq!(3, page.total, "total");
+    assert_eq!(Some(1), page.next_cursor, "next_cursor");
+    assert_eq!("Hunter`s Stew", page.items[0].title);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_list_max_total_minutes() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/?max_total_minutes=30")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+
+    let page: Page<RecipeView> = extract_body_data(resp)?;
+
+    assert_eq!(1, page.total, "total");
+    assert_eq!("Hunter`s Stew", page.items[0].recipe.title);
+    assert_eq!(Some(25), page.items[0].recipe.total_minutes);
+    assert_eq!(Some("PT25M"), page.items[0].total_duration.as_deref());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_list_raw_id_rejected() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", "firebase_auth_123")
+        .path("/api/recipes/")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(401, resp.status(), "http status");
+    assert_eq!("AUTH_INVALID_TOKEN", extract_error_code(resp)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_list_missing_token() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .path("/api/recipes/")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(401, resp.status(), "http status");
+    assert_eq!("AUTH_MISSING_TOKEN", extract_error_code(resp)?);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn web_recipe_get() -> Result<()> {
     // -- FIXTURE
@@ -48,7 +147,7 @@ async fn web_recipe_get() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("GET")
-        .header("X-Auth-Token", "1")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
         .path("/api/recipes/1")
         .reply(&recipe_apis)
         .await;
@@ -66,6 +165,89 @@ async fn web_recipe_get() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn web_recipe_get_metric() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    let data_fx = RecipePatch {
+        title: Some("Roast Chicken".to_string()),
+        ingredients: Some(vec!["2 lbs chicken".to_string(), "1 tbsp salt".to_string()]),
+        steps: Some(vec!["Roast at 425°F for an hour".to_string()]),
+        ..Default::default()
+    };
+    let utx = UserCtx { user_id: "firebase_auth_123".to_string() };
+    let recipe_fx = RecipeMac::create(&db, data_fx, utx).await?;
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path(&format!("/api/recipes/{}?units=metric", recipe_fx.id))
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+
+    let recipe: RecipeView = extract_body_data(resp)?;
+
+    assert_eq!(Some(vec!["907 g chicken".to_string(), "15 ml salt".to_string()]), recipe.recipe.ingredients);
+    assert_eq!(Some(vec!["Roast at 220°C for an hour".to_string()]), recipe.recipe.steps);
+
+    // -- CHECK - nothing stored
+    let stored = RecipeMac::get(&db, UserCtx { user_id: "firebase_auth_123".to_string() }, recipe_fx.id).await?;
+    assert_eq!("2 lbs chicken", stored.ingredients.unwrap()[0]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_get_not_found() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/999")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(404, resp.status(), "http status");
+    assert_eq!("ENTITY_NOT_FOUND", extract_error_code(resp)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_get_forbidden() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_other"))
+        .path("/api/recipes/1")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(403, resp.status(), "http status");
+    assert_eq!("ACCESS_DENIED", extract_error_code(resp)?);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn web_recipe_create_ok() -> Result<()> {
         // -- FIXTURE
@@ -81,7 +263,7 @@ async fn web_recipe_create_ok() -> Result<()> {
         // -- ACTION
         let resp = warp::test::request()
             .method("POST")
-            .header("X-Auth-Token", "firebase_auth_123")
+            .header("X-Auth-Token", test_token("firebase_auth_123"))
             .path("/api/recipes")
             .json(&body)
             .reply(&recipe_apis)
@@ -100,6 +282,436 @@ async fn web_recipe_create_ok() -> Result<()> {
         Ok(())
 }
 
+#[tokio::test]
+async fn web_recipe_create_without_title() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes")
+        .json(&json!({ "header": "Bean time!" }))
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(400, resp.status(), "http status");
+    assert_eq!("INVALID_RECIPE", extract_error_code(resp)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_create_parsed_ingredients() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    let body = json!({
+        "title": "Bag of Beans",
+        "ingredients": ["1½ cups dried beans, soaked", "Salt"]
+    });
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes")
+        .json(&body)
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+
+    let recipe: RecipeView = extract_body_data(resp)?;
+
+    assert_eq!(2, recipe.parsed_ingredients.len());
+    assert_eq!("1½ cups dried beans, soaked", recipe.parsed_ingredients[0].raw);
+    assert_eq!(Some("cup"), recipe.parsed_ingredients[0].unit.as_deref());
+    assert_eq!("dried beans", recipe.parsed_ingredients[0].name);
+    assert_eq!("Salt", recipe.parsed_ingredients[1].name);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_create_invalid_body() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    let body = json!({
+        "title": ["Not", "a", "string"]
+    });
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes")
+        .json(&body)
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(422, resp.status(), "http status");
+    assert_eq!("INVALID_BODY", extract_error_code(resp)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_scaled_servings() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    let data_fx = RecipePatch {
+        title: Some("Big Batch Cookies".to_string()),
+        ingredients: Some(vec!["1 tbsp vanilla".to_string(), "2 eggs".to_string(), "Salt".to_string()]),
+        servings: Some(12),
+        ..Default::default()
+    };
+    let utx = UserCtx { user_id: "firebase_auth_123".to_string() };
+    let recipe_fx = RecipeMac::create(&db, data_fx, utx).await?;
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path(&format!("/api/recipes/{}/scaled?servings=48", recipe_fx.id))
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+
+    let scaled: ScaledRecipe = extract_body_data(resp)?;
+
+    assert_eq!(4.0, scaled.factor);
+    assert_eq!(Some(48), scaled.recipe.recipe.servings);
+    assert_eq!(
+        Some(vec!["1/4 cup vanilla".to_string(), "8 eggs".to_string(), "Salt".to_string()]),
+        scaled.recipe.recipe.ingredients
+    );
+
+    // -- CHECK - nothing stored
+    let stored = RecipeMac::get(&db, UserCtx { user_id: "firebase_auth_123".to_string() }, recipe_fx.id).await?;
+    assert_eq!(Some(12), stored.servings);
+    assert_eq!("1 tbsp vanilla", stored.ingredients.unwrap()[0]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_scaled_without_servings() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/1/scaled?servings=4")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(400, resp.status(), "http status");
+    assert_eq!("INVALID_QUERY", extract_error_code(resp)?);
+
+    // -- ACTION - a factor works without servings
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/1/scaled?factor=2")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_scrape_invalid_url() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/scrape/not%20a%20url")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(400, resp.status(), "http status");
+    assert_eq!("SCRAPE_INVALID_URL", extract_error_code(resp)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_scrape_missing_token() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .path("/api/recipes/scrape/https%3A%2F%2Fexample.com%2Fcornbread")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(401, resp.status(), "http status");
+    assert_eq!("AUTH_MISSING_TOKEN", extract_error_code(resp)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_scrape_blocked_address() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/scrape/http%3A%2F%2F169.254.169.254%2Flatest%2Fmeta-data")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(403, resp.status(), "http status");
+    assert_eq!("SCRAPE_BLOCKED_ADDRESS", extract_error_code(resp)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_import_blocked_address() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/import")
+        .json(&json!({ "url": "http://localhost:8080/recipe", "title": "Sneaky" }))
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(403, resp.status(), "http status");
+    assert_eq!("SCRAPE_BLOCKED_ADDRESS", extract_error_code(resp)?);
+
+    let page = RecipeMac::list(&db, UserCtx { user_id: "firebase_auth_123".to_string() }, ListOptions::default()).await?;
+    assert!(page.items.iter().all(|recipe| recipe.title != "Sneaky"), "nothing saved");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_import_batch() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/import/batch")
+        .json(&json!({ "urls": ["http://127.0.0.1/internal", "not a url"] }))
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let job: ImportJobView = extract_body_data(resp)?;
+    assert_eq!(job.report.len(), 2);
+
+    // the batch runs in the background, poll until it is done
+    let mut job = job;
+    for _ in 0..50 {
+        if job.is_done() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let resp = warp::test::request()
+            .method("GET")
+            .header("X-Auth-Token", test_token("firebase_auth_123"))
+            .path(&format!("/api/recipes/import/batch/{}", job.id))
+            .reply(&recipe_apis)
+            .await;
+        assert_eq!(200, resp.status(), "http status");
+        job = extract_body_data(resp)?;
+    }
+    assert!(job.is_done(), "batch finished");
+    assert!(job.report.iter().all(|item| matches!(item.outcome, ImportOutcome::Failed { .. })));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_import_batch_too_many_jobs() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+    let utx = UserCtx { user_id: "firebase_auth_123".to_string() };
+    let request = BatchImportRequest { urls: vec!["https://example.com/cornbread".to_string()], tags: None };
+    for _ in 0..MAX_RUNNING_JOBS {
+        ImportJobMac::create_batch(&db, &utx, &request).await?;
+    }
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/import/batch")
+        .json(&json!({ "urls": ["https://example.com/pancakes"] }))
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(429, resp.status(), "http status");
+    assert_eq!("TOO_MANY_IMPORT_JOBS", extract_error_code(resp)?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_cooklang_round_trip() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+    let cook = ">> title: Toast\n\nToast @bread{2%slices} in the #toaster{} for 3 minutes.\n";
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/import/cooklang")
+        .body(cook)
+        .reply(&recipe_apis)
+        .await;
+    assert_eq!(200, resp.status(), "http status");
+    let recipe: RecipeView = extract_body_data(resp)?;
+
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path(&format!("/api/recipes/{}.cook", recipe.recipe.id))
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    assert_eq!(resp.headers()["Content-Type"], "text/plain; charset=utf-8");
+    let body = from_utf8(resp.body())?;
+    assert!(body.starts_with(">> title: Toast\n"), "{}", body);
+    assert!(body.contains("Toast @bread{2%slice} in the #toaster{} for ~{3%minutes}."), "{}", body);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_inline_image() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+    let data_fx = RecipePatch {
+        title: Some("Photo Toast".to_string()),
+        image_url: Some("data:image/png;base64,iVBORw==".to_string()),
+        ..Default::default()
+    };
+    let recipe = RecipeMac::create(&db, data_fx, UserCtx { user_id: "firebase_auth_123".to_string() }).await?;
+    let image_path = format!("/api/recipes/{}/image", recipe.id);
+
+    // -- ACTION
+    let list = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/?limit=200")
+        .reply(&recipe_apis)
+        .await;
+    let image = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path(&image_path)
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, list.status(), "http status");
+    let page: Page<recipe::Model> = extract_body_data(list)?;
+    let listed = page.items.iter().find(|item| item.id == recipe.id).context("created recipe listed")?;
+    assert_eq!(listed.image_url.as_deref(), Some(image_path.as_str()));
+    assert_eq!(200, image.status(), "http status");
+    assert_eq!(image.headers()["Content-Type"], "image/png");
+    assert_eq!(image.body().as_ref(), b"\x89PNG");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn web_recipe_import_obsidian() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+    let note = "---\nid: 2\ntags: [Dessert]\n---\n# Lemon Pound Cake\n\n## Steps\n\n1. Bake it.\n";
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("POST")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/import/obsidian")
+        .body(note)
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+    let report: Vec<VaultReportItem> = extract_body_data(resp)?;
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].file, "note.md");
+    assert!(
+        matches!(report[0].outcome, VaultOutcome::Conflict { recipe_id: 2, .. }),
+        "a note without `updated` can't tell whose edit is newer"
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn web_get_by_tag_ok() -> Result<()> {
     // -- FIXTURE
@@ -110,7 +722,7 @@ async fn web_get_by_tag_ok() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("GET")
-        .header("X-Auth-Token", "firebase_auth_123")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
         .path("/api/recipes/tags/Easy")
         .reply(&recipe_apis)
         .await;
@@ -120,7 +732,7 @@ async fn web_get_by_tag_ok() -> Result<()> {
     
     // extract response.data
 
-    let recipes: Vec<recipe::Model> = extract_body_data(resp)?;
+    let recipes: Vec<recipe::Model> = extract_body_data::<Page<recipe::Model>>(resp)?.items;
 
     // -- CHECK
 
@@ -128,6 +740,32 @@ async fn web_get_by_tag_ok() -> Result<()> {
 
     Ok(())
 }
+#[tokio::test]
+async fn web_recipe_search_ok() -> Result<()> {
+    // -- FIXTURE
+    let db = init_db().await?;
+    let db = Arc::new(db);
+    let recipe_apis = recipe_rest_filters("api", db.clone()).recover(handle_rejection);
+
+    // -- ACTION
+    let resp = warp::test::request()
+        .method("GET")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
+        .path("/api/recipes/search?q=pound%20cake")
+        .reply(&recipe_apis)
+        .await;
+
+    // -- CHECK
+    assert_eq!(200, resp.status(), "http status");
+
+    let page: Page<RecipeSearchHit> = extract_body_data(resp)?;
+
+    assert_eq!(1, page.total);
+    assert_eq!("Lemon Pound Cake", page.items[0].recipe.recipe.title);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn web_recipe_delete_ok() -> Result<()> {
     // -- FIXTURE
@@ -138,7 +776,7 @@ async fn web_recipe_delete_ok() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("DELETE")
-        .header("X-Auth-Token", "1")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
         .path("/api/recipes/3")
         .reply(&recipe_apis)
         .await;
@@ -149,7 +787,7 @@ async fn web_recipe_delete_ok() -> Result<()> {
     // extract response.data
     let recipe_id: i64 = extract_body_data(resp)?;
 
-    let recipes: Vec<recipe::Model> = RecipeMac::list(&db).await?;
+    let recipes: Vec<recipe::Model> = RecipeMac::list(&db, UserCtx { user_id: "firebase_auth_123".to_owned() }, ListOptions::default()).await?.items;
 
 
     // -- CHECK
@@ -175,7 +813,7 @@ async fn web_recipe_update_ok() -> Result<()> {
     // -- ACTION
     let resp = warp::test::request()
         .method("PATCH")
-        .header("X-Auth-Token", "1")
+        .header("X-Auth-Token", test_token("firebase_auth_123"))
         .path("/api/recipes/3")
         .json(&body)
         .reply(&recipe_apis)
@@ -210,4 +848,11 @@ where
 
     Ok(data)
 }
+
+fn extract_error_code(resp: Response<Bytes>) -> Result<String> {
+    let body = from_utf8(resp.body())?;
+    let body: Value = from_str(body)?;
+
+    Ok(body["error"]["code"].as_str().unwrap_or_default().to_string())
+}
 // endregion: Web Test Utils
\ No newline at end of file
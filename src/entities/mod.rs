@@ -3,4 +3,6 @@
 pub mod prelude;
 
 pub mod chef;
+pub mod import_job;
 pub mod recipe;
+pub mod scrape_cache;
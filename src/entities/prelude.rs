@@ -1,4 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.1
 
 pub use super::chef::Entity as Chef;
+pub use super::import_job::Entity as ImportJob;
 pub use super::recipe::Entity as Recipe;
+pub use super::scrape_cache::Entity as ScrapeCache;
@@ -29,6 +29,17 @@ pub struct Model {
     pub prep_time: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub total_time: Option<String>,
+    pub servings: Option<i32>,
+    pub prep_minutes: Option<i32>,
+    pub cook_minutes: Option<i32>,
+    pub total_minutes: Option<i32>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub source_url: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub source_name: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub original_author: Option<String>,
+    pub equipment: Option<Vec<String>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
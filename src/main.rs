@@ -2,7 +2,7 @@
 
 use std::{env, sync::Arc};
 
-use model::init_db;
+use model::{init_db, ImportJobMac};
 use web::start_web;
 
 mod entities;
@@ -11,6 +11,10 @@ mod security;
 mod utils;
 mod web;
 
+#[cfg(test)]
+#[path = "_tests/test_utils.rs"]
+mod test_utils;
+
 const DEFAULT_WEB_FOLDER: &str = "web-folder/";
 const DEFAULT_WEB_PORT: u16 = 8080;
 
@@ -30,6 +34,13 @@ async fn main() {
     let db = init_db().await.expect("Cannot init db");
     let db = Arc::new(db);
 
+    // Import jobs left running by the previous process will never finish
+    match ImportJobMac::fail_interrupted(&db).await {
+        Ok(0) => (),
+        Ok(count) => println!("Failed {} import jobs interrupted by the restart", count),
+        Err(ex) => println!("ERROR - failing interrupted import jobs. Cause {:?}", ex),
+    }
+
     // Start the server
     match start_web(&web_folder, web_port, db).await {
         Ok(_) => println!("Server ended"),
@@ -2,6 +2,8 @@ use sea_orm::{DatabaseConnection, ModelTrait, Set};
 use serde::{Deserialize, Serialize};
 
 use crate::entities::{chef, prelude::*, recipe};
+use crate::model::page::paginate_recipes;
+use crate::model::{ListOptions, Page};
 
 use sea_orm::prelude::*;
 
@@ -25,11 +27,7 @@ impl ChefMac {
             .one(db)
             .await?;
 
-        if chef.is_none() {
-            return Err(super::Error::EntityNotFound(firebase_id));
-        }
-
-        let chef = chef.unwrap();
+        let chef = chef.ok_or(super::Error::EntityNotFound("chef", firebase_id))?;
 
         Ok(chef)
     }
@@ -94,11 +92,11 @@ impl ChefMac {
     pub async fn get_recipes(
         db: &DatabaseConnection,
         firebase_id: String,
-    ) -> Result<Vec<recipe::Model>, super::Error> {
+        options: ListOptions,
+    ) -> Result<Page<recipe::Model>, super::Error> {
         let chef: chef::Model = ChefMac::get(db, firebase_id).await?;
 
-        let recipes: Vec<recipe::Model> = chef.find_related(Recipe).all(db).await?;
-        Ok(recipes)
+        paginate_recipes(db, chef.find_related(Recipe), options).await
     }
 }
 
@@ -0,0 +1,541 @@
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use sea_orm::sea_query::Expr;
+use sea_orm::{prelude::*, DatabaseConnection, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::entities::{chef, prelude::Recipe, recipe};
+use crate::model::{ChefMac, Error};
+use crate::security::UserCtx;
+use crate::utils::{
+    data_url_image, iso8601_duration, xml_escape, EpubBook, EpubChapter, EpubImage, Font, PageSize,
+    PdfDocument,
+};
+
+/// Recipes printed in one cookbook
+pub const MAX_COOKBOOK_RECIPES: usize = 500;
+/// TOC heading of the recipes without tags
+const UNTAGGED: &str = "Other recipes";
+
+/// Which recipes go in a cookbook, e.g. `?tags=dessert,easy&page_size=a4`.
+/// Without `tags` or `ids` every recipe of the chef is included.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct CookbookOptions {
+    /// Comma separated, recipes with any of them (case insensitive)
+    pub tags: Option<String>,
+    /// Comma separated recipe ids
+    pub ids: Option<String>,
+    pub page_size: Option<PageSize>,
+}
+
+pub struct CookbookMac;
+
+impl CookbookMac {
+    /// The caller and their recipes picked by `options`, sorted by title
+    pub async fn recipes(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        options: &CookbookOptions,
+    ) -> Result<(chef::Model, Vec<recipe::Model>), Error> {
+        let chef = ChefMac::get(db, utx.user_id.clone()).await?;
+        let ids = parse_ids(options.ids.as_deref())?;
+        let tags = split_list(options.tags.as_deref())
+            .map(|tag| tag.to_lowercase())
+            .collect::<Vec<_>>();
+
+        let mut select = Recipe::find().filter(recipe::Column::Cid.eq(chef.firebase_id.as_str()));
+        if !ids.is_empty() {
+            select = select.filter(recipe::Column::Id.is_in(ids));
+        }
+        if !tags.is_empty() {
+            select = select.filter(Expr::cust_with_values(
+                "EXISTS (SELECT 1 FROM unnest(recipe.tags) AS tag WHERE lower(trim(tag)) = ANY($1))",
+                [tags],
+            ));
+        }
+        // one past the limit is enough to know the selection is too large
+        let recipes = select
+            .order_by_asc(recipe::Column::Title)
+            .order_by_asc(recipe::Column::Id)
+            .limit(MAX_COOKBOOK_RECIPES as u64 + 1)
+            .all(db)
+            .await?;
+
+        if recipes.is_empty() {
+            return Err(Error::InvalidListOption(
+                "no recipes match the cookbook selection".to_string(),
+            ));
+        }
+        if recipes.len() > MAX_COOKBOOK_RECIPES {
+            return Err(Error::InvalidListOption(format!(
+                "a cookbook holds at most {} recipes, narrow it with tags or ids",
+                MAX_COOKBOOK_RECIPES
+            )));
+        }
+
+        Ok((chef, recipes))
+    }
+
+    /// Printable PDF of the selected recipes: a title page, a table of contents
+    /// grouped by tag, then one recipe per page
+    pub async fn pdf(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        options: &CookbookOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let (chef, recipes) = CookbookMac::recipes(db, utx, options).await?;
+        let title = cookbook_title(&chef);
+        let page_size = options.page_size.unwrap_or_default();
+
+        // laying out hundreds of pages is cpu bound, keep it off the async workers
+        tokio::task::spawn_blocking(move || render_pdf(&title, &recipes, page_size))
+            .await
+            .map_err(|ex| Error::IOErr(ex.into()))
+    }
+
+    /// EPUB 3 of the selected recipes, one chapter per recipe with schema.org `Recipe`
+    /// microdata and the contents grouped by tag. `options.page_size` is ignored.
+    /// Photos stored in the recipe (data urls) are embedded, remote ones are left out.
+    pub async fn epub(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        options: &CookbookOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let (chef, recipes) = CookbookMac::recipes(db, utx, options).await?;
+
+        let epub = tokio::task::spawn_blocking(move || render_epub(&chef, &recipes))
+            .await
+            .map_err(|ex| Error::IOErr(ex.into()))?;
+
+        Ok(epub?)
+    }
+}
+
+/// "Goombah!'s Cookbook", or a generic title for chefs without a username
+pub(crate) fn cookbook_title(chef: &chef::Model) -> String {
+    match chef.username.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => format!("{}'s Cookbook", name),
+        _ => "My Cookbook".to_string(),
+    }
+}
+
+/// (tag, recipes) sorted by tag, a recipe is listed under each of its tags
+pub(crate) fn recipes_by_tag(recipes: &[recipe::Model]) -> Vec<(String, Vec<&recipe::Model>)> {
+    let mut groups: BTreeMap<String, (String, Vec<&recipe::Model>)> = BTreeMap::new();
+    let mut untagged = Vec::new();
+
+    for recipe in recipes {
+        let tags: Vec<&str> = recipe
+            .tags
+            .iter()
+            .flatten()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        if tags.is_empty() {
+            untagged.push(recipe);
+        }
+        for tag in tags {
+            let group = groups
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| (tag.to_string(), Vec::new()));
+            if !group.1.iter().any(|listed| listed.id == recipe.id) {
+                group.1.push(recipe);
+            }
+        }
+    }
+
+    let mut groups: Vec<_> = groups.into_values().collect();
+    if !untagged.is_empty() {
+        groups.push((UNTAGGED.to_string(), untagged));
+    }
+    groups
+}
+
+// region:    PDF layout
+
+fn render_pdf(title: &str, recipes: &[recipe::Model], size: PageSize) -> Vec<u8> {
+    // Recipes first, the contents need their pages
+    let mut body = PdfDocument::new(size);
+    let mut starts = BTreeMap::new();
+    for recipe in recipes {
+        starts.insert(recipe.id, body.new_page());
+        recipe_pages(&mut body, recipe);
+    }
+
+    // Page numbers can change how contents lines wrap, lay them out until the count holds
+    let groups = recipes_by_tag(recipes);
+    let mut toc = contents_pages(&groups, &starts, size, 2);
+    for _ in 0..3 {
+        let next = contents_pages(&groups, &starts, size, 1 + toc.page_count());
+        let settled = next.page_count() == toc.page_count();
+        toc = next;
+        if settled {
+            break;
+        }
+    }
+
+    let mut doc = PdfDocument::new(size);
+    doc.new_page();
+    doc.space(size.dimensions().1 / 4.0);
+    doc.centered(title, Font::Bold, 28.0);
+    doc.space(12.0);
+    let count = match recipes.len() {
+        1 => "1 recipe".to_string(),
+        count => format!("{} recipes", count),
+    };
+    doc.centered(&count, Font::Italic, 14.0);
+
+    doc.append(toc);
+    doc.append(body);
+    doc.number_pages(1);
+
+    doc.finish(title)
+}
+
+/// Contents pages, recipe lines link to `offset` + their body page
+fn contents_pages(
+    groups: &[(String, Vec<&recipe::Model>)],
+    starts: &BTreeMap<i64, usize>,
+    size: PageSize,
+    offset: usize,
+) -> PdfDocument {
+    let mut toc = PdfDocument::new(size);
+    toc.new_page();
+    toc.paragraph("Contents", Font::Bold, 20.0, 0.0);
+
+    for (tag, recipes) in groups {
+        toc.space(8.0);
+        toc.paragraph(tag, Font::Bold, 13.0, 0.0);
+        for recipe in recipes {
+            let page = offset + starts.get(&recipe.id).copied().unwrap_or_default();
+            toc.link_line(
+                &recipe.title,
+                &(page + 1).to_string(),
+                Font::Regular,
+                11.0,
+                12.0,
+                page,
+            );
+        }
+    }
+
+    toc
+}
+
+fn recipe_pages(doc: &mut PdfDocument, recipe: &recipe::Model) {
+    doc.paragraph(&recipe.title, Font::Bold, 20.0, 0.0);
+    if let Some(header) = recipe
+        .header
+        .as_deref()
+        .filter(|text| !text.trim().is_empty())
+    {
+        doc.space(4.0);
+        for paragraph in header.lines().filter(|line| !line.trim().is_empty()) {
+            doc.paragraph(paragraph, Font::Italic, 11.0, 0.0);
+        }
+    }
+
+    let facts = recipe_facts(recipe);
+    if !facts.is_empty() {
+        doc.space(4.0);
+        doc.paragraph(&facts, Font::Regular, 10.0, 0.0);
+    }
+    if let Some(tags) = recipe.tags.as_ref().filter(|tags| !tags.is_empty()) {
+        doc.paragraph(
+            &format!("Tags: {}", tags.join(", ")),
+            Font::Regular,
+            10.0,
+            0.0,
+        );
+    }
+
+    if let Some(ingredients) = recipe
+        .ingredients
+        .as_ref()
+        .filter(|items| !items.is_empty())
+    {
+        section(doc, "Ingredients");
+        for ingredient in ingredients {
+            doc.paragraph(
+                &format!("\u{2022} {}", ingredient),
+                Font::Regular,
+                11.0,
+                12.0,
+            );
+        }
+    }
+
+    if let Some(steps) = recipe.steps.as_ref().filter(|items| !items.is_empty()) {
+        section(doc, "Steps");
+        for (index, step) in steps.iter().enumerate() {
+            doc.paragraph(
+                &format!("{}. {}", index + 1, step),
+                Font::Regular,
+                11.0,
+                12.0,
+            );
+            doc.space(3.0);
+        }
+    }
+
+    if let Some(equipment) = recipe.equipment.as_ref().filter(|items| !items.is_empty()) {
+        section(doc, "Equipment");
+        doc.paragraph(&equipment.join(", "), Font::Regular, 11.0, 12.0);
+    }
+
+    let source = [recipe.source_name.as_deref(), recipe.source_url.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    if !source.is_empty() {
+        doc.space(12.0);
+        doc.paragraph(
+            &format!("Source: {}", source.join(" - ")),
+            Font::Italic,
+            9.0,
+            0.0,
+        );
+    }
+}
+
+fn section(doc: &mut PdfDocument, title: &str) {
+    doc.space(10.0);
+    doc.paragraph(title, Font::Bold, 13.0, 0.0);
+    doc.space(2.0);
+}
+
+/// "Prep 15 mins · Cook 1 hr · Serves 4"
+fn recipe_facts(recipe: &recipe::Model) -> String {
+    let mut facts = Vec::new();
+    for (label, time) in [
+        ("Prep", &recipe.prep_time),
+        ("Cook", &recipe.cook_time),
+        ("Total", &recipe.total_time),
+    ] {
+        if let Some(time) = time.as_deref().filter(|time| !time.trim().is_empty()) {
+            facts.push(format!("{} {}", label, time));
+        }
+    }
+    if let Some(servings) = recipe.servings {
+        facts.push(format!("Serves {}", servings));
+    }
+    facts.join(" \u{b7} ")
+}
+
+// endregion: PDF layout
+
+// region:    EPUB layout
+
+fn render_epub(chef: &chef::Model, recipes: &[recipe::Model]) -> Result<Vec<u8>, std::io::Error> {
+    let mut chapters = Vec::new();
+    let mut images = Vec::new();
+    let mut indexes = BTreeMap::new();
+    for recipe in recipes {
+        let image =
+            recipe
+                .image_url
+                .as_deref()
+                .and_then(data_url_image)
+                .map(|(media_type, data)| {
+                    let extension = media_type
+                        .trim_start_matches("image/")
+                        .replace("jpeg", "jpg");
+                    EpubImage {
+                        file: format!("recipe-{}.{}", recipe.id, extension),
+                        media_type,
+                        data,
+                    }
+                });
+
+        indexes.insert(recipe.id, chapters.len());
+        chapters.push(EpubChapter {
+            file: format!("recipe-{}.xhtml", recipe.id),
+            title: recipe.title.clone(),
+            body: recipe_chapter(recipe, image.as_ref()),
+        });
+        images.extend(image);
+    }
+
+    let groups = recipes_by_tag(recipes);
+    let sections = groups
+        .iter()
+        .map(|(tag, recipes)| {
+            let chapters = recipes
+                .iter()
+                .filter_map(|recipe| indexes.get(&recipe.id).copied());
+            (tag.clone(), chapters.collect())
+        })
+        .collect();
+
+    // Same chef and selection, same book: a digest of the chef's uid and the ids, in order
+    // as `indexes` is sorted. Books get shared, so the uid only goes in hashed.
+    let ids: Vec<String> = indexes.keys().map(i64::to_string).collect();
+    let digest = Sha256::digest(format!("{}:{}", chef.firebase_id, ids.join(",")).as_bytes());
+
+    EpubBook {
+        identifier: format!("urn:digital-cookbook:{:x}", digest),
+        title: cookbook_title(chef),
+        author: chef.username.clone().filter(|name| !name.trim().is_empty()),
+        language: "en".to_string(),
+        modified: DateTimeUtc::from(SystemTime::now())
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string(),
+        subjects: groups
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .filter(|tag| tag != UNTAGGED)
+            .collect(),
+        chapters,
+        images,
+        sections,
+    }
+    .write()
+}
+
+/// XHTML of one recipe marked up as a schema.org `Recipe`
+fn recipe_chapter(recipe: &recipe::Model, image: Option<&EpubImage>) -> String {
+    let mut html = vec![
+        r#"<article itemscope="itemscope" itemtype="https://schema.org/Recipe">"#.to_string(),
+        format!(r#"<h1 itemprop="name">{}</h1>"#, xml_escape(&recipe.title)),
+    ];
+    if let Some(image) = image {
+        html.push(format!(
+            r#"<img itemprop="image" src="images/{}" alt="{}"/>"#,
+            xml_escape(&image.file),
+            xml_escape(&recipe.title)
+        ));
+    }
+    if let Some(header) = recipe
+        .header
+        .as_deref()
+        .filter(|text| !text.trim().is_empty())
+    {
+        html.push(format!(
+            r#"<div class="header" itemprop="description">{}</div>"#,
+            paragraphs(header)
+        ));
+    }
+
+    let mut facts = Vec::new();
+    for (label, property, time, minutes) in [
+        ("Prep", "prepTime", &recipe.prep_time, recipe.prep_minutes),
+        ("Cook", "cookTime", &recipe.cook_time, recipe.cook_minutes),
+        (
+            "Total",
+            "totalTime",
+            &recipe.total_time,
+            recipe.total_minutes,
+        ),
+    ] {
+        let Some(time) = time.as_deref().filter(|time| !time.trim().is_empty()) else {
+            continue;
+        };
+        facts.push(match minutes {
+            Some(minutes) => format!(
+                r#"{} <time itemprop="{}" datetime="{}">{}</time>"#,
+                label,
+                property,
+                iso8601_duration(minutes),
+                xml_escape(time)
+            ),
+            None => format!("{} {}", label, xml_escape(time)),
+        });
+    }
+    if let Some(servings) = recipe.servings {
+        facts.push(format!(
+            r#"Serves <span itemprop="recipeYield">{}</span>"#,
+            servings
+        ));
+    }
+    if !facts.is_empty() {
+        html.push(format!(
+            r#"<p class="facts">{}</p>"#,
+            facts.join(" \u{b7} ")
+        ));
+    }
+    if let Some(tags) = recipe.tags.as_ref().filter(|tags| !tags.is_empty()) {
+        html.push(format!(
+            r#"<p class="tags">Tags: <span itemprop="keywords">{}</span></p>"#,
+            xml_escape(&tags.join(", "))
+        ));
+    }
+
+    for (heading, list, property, items) in [
+        ("Ingredients", "ul", "recipeIngredient", &recipe.ingredients),
+        ("Steps", "ol", "recipeInstructions", &recipe.steps),
+        ("Equipment", "ul", "tool", &recipe.equipment),
+    ] {
+        let Some(items) = items.as_ref().filter(|items| !items.is_empty()) else {
+            continue;
+        };
+        html.push(format!("<h2>{}</h2>\n<{}>", heading, list));
+        for item in items {
+            html.push(format!(
+                r#"<li itemprop="{}">{}</li>"#,
+                property,
+                xml_escape(item)
+            ));
+        }
+        html.push(format!("</{}>", list));
+    }
+
+    if let Some(author) = &recipe.original_author {
+        html.push(format!(
+            r#"<p class="source">By <span itemprop="author">{}</span></p>"#,
+            xml_escape(author)
+        ));
+    }
+    match (&recipe.source_url, &recipe.source_name) {
+        (Some(url), name) => html.push(format!(
+            r#"<p class="source">Source: <a itemprop="url" href="{}">{}</a></p>"#,
+            xml_escape(url),
+            xml_escape(name.as_deref().unwrap_or(url))
+        )),
+        (None, Some(name)) => html.push(format!(
+            r#"<p class="source">Source: {}</p>"#,
+            xml_escape(name)
+        )),
+        (None, None) => {}
+    }
+    html.push("</article>\n".to_string());
+
+    html.join("\n")
+}
+
+/// `<p>` per non blank line
+fn paragraphs(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("<p>{}</p>", xml_escape(line)))
+        .collect()
+}
+
+// endregion: EPUB layout
+
+// region:    Option helpers
+
+fn split_list(list: Option<&str>) -> impl Iterator<Item = &str> {
+    list.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn parse_ids(ids: Option<&str>) -> Result<Vec<i64>, Error> {
+    split_list(ids)
+        .map(|id| {
+            id.parse()
+                .map_err(|_| Error::InvalidListOption(format!("ids - '{}' is not a recipe id", id)))
+        })
+        .collect()
+}
+
+// endregion: Option helpers
+
+#[cfg(test)]
+#[path = "../_tests/model_cookbook.rs"]
+mod tests;
@@ -1,29 +1,39 @@
 use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
-use std::{env, fs, path::PathBuf};
+use std::{env, fs};
 
 use dotenvy::dotenv;
 use dotenvy_macro::dotenv;
 use sea_orm::{Database, DatabaseConnection};
 
-const SQL_DIR: &str = "sql/";
+use super::migration::{migrate, MIGRATIONS_DIR};
+
 const SQL_RECREATE: &str = "sql/00-recreate-db.sql";
+const SQL_DEV_SEED: &str = "sql/dev-seed.sql";
+
+/// `DEV_RECREATE_DB=true` drops the database and reseeds it on every init_db. DEV ONLY
+const ENV_DEV_RECREATE_DB: &str = "DEV_RECREATE_DB";
 
 pub async fn init_db() -> Result<DatabaseConnection, super::Error> {
     if dotenv().is_ok() {
         dotenv().unwrap();
     };
 
+    let recreate = env::var(ENV_DEV_RECREATE_DB).is_ok_and(|v| v == "true");
+
     // RESET DB dev only
-    if false {
+    if recreate {
         let root_db_url = match env::var("ROOT_DATABASE_URL") {
             Ok(url) => url,
             Err(e) => "Error".to_owned(),
         };
 
         // let root_db_url = dotenv!("ROOT_DATABASE_URL");
-        let db = PgPoolOptions::new().connect(&root_db_url).await?;
+        let db = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(&root_db_url)
+            .await?;
         pexec(&db, SQL_RECREATE).await?;
-        db.close();
+        db.close().await;
     }
 
     let database_url = match env::var("PRODUCTION_DB_URL") {
@@ -33,45 +43,32 @@ pub async fn init_db() -> Result<DatabaseConnection, super::Error> {
 
     let sqlx_db = PgPoolOptions::new().connect(&database_url).await?;
 
-    // -- Run the app sql files
-    let mut paths: Vec<PathBuf> = fs::read_dir(SQL_DIR)?
-        .into_iter()
-        .filter_map(|e| e.ok().map(|e| e.path()))
-        .collect();
-    paths.sort();
-
-    // Running this code in prod wont cause any real problems, but it will throw errors
-    if false {
-        // Execute each file
-        for path in paths {
-            if let Some(path) = path.to_str() {
-                // only .sql and not the recreate
-                if path.ends_with(".sql") && path != SQL_RECREATE {
-                    pexec(&sqlx_db, path).await?;
-                }
-            }
-        }
+    // -- Bring the schema up to date, refuses to start if an applied migration was edited
+    migrate(&sqlx_db, MIGRATIONS_DIR).await?;
+
+    if recreate {
+        pexec(&sqlx_db, SQL_DEV_SEED).await?;
     }
+    sqlx_db.close().await;
 
     let db: DatabaseConnection = Database::connect(database_url).await?;
 
     Ok(db)
 }
 
-async fn pexec(db: &Pool<Postgres>, file: &str) -> Result<(), sqlx::Error> {
+/// Runs a dev sql file one statement at a time,
+/// as `DROP DATABASE` cannot run in the implicit transaction of a multi statement query
+async fn pexec(db: &Pool<Postgres>, file: &str) -> Result<(), super::Error> {
     // Read the file
     let content = fs::read_to_string(file).map_err(|ex| {
         println!("Error reading {} (cause: {:?}", file, ex);
         ex
     })?;
 
-    let sqls: Vec<&str> = content.split(';').collect();
+    let sqls = content.split(';').filter(|sql| !sql.trim().is_empty());
 
     for sql in sqls {
-        match sqlx::query(sql).execute(db).await {
-            Ok(_) => (),
-            Err(ex) => println!("WARNING - pexec - Sql file '{}' FAILED cause: {}", file, ex),
-        }
+        sqlx::query(sql).execute(db).await?;
     }
 
     Ok(())
@@ -0,0 +1,597 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use reqwest::Url;
+use sea_orm::{prelude::*, DatabaseConnection, Set};
+use serde::{Deserialize, Serialize};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::entities::{import_job, prelude::ImportJob};
+use crate::model::{Error, ImportRequest, RecipeMac, ScrapeCache};
+use crate::security::UserCtx;
+use crate::utils::{
+    discover_site, normalize_source_url, ArchiveEntry, ArchiveFormat, ArchivedRecipe, ScrapeError,
+};
+
+/// Largest batch accepted in one request
+pub const MAX_BATCH_URLS: usize = 100;
+/// Pages scraped at the same time by one batch
+const MAX_CONCURRENT: usize = 8;
+/// Pages scraped at the same time from one host, to stay polite
+const MAX_PER_HOST: usize = 2;
+/// Jobs one chef can have running at once, each one fetches on its own budget
+pub const MAX_RUNNING_JOBS: u64 = 3;
+/// Pages imported by one crawl, and the default when the request gives none
+pub const MAX_CRAWL_PAGES: usize = 200;
+/// Extractors reading schema.org Recipe data, a crawl only imports what they found
+const STRUCTURED_SOURCES: &[&str] = &["json-ld", "microdata"];
+
+/// Archive recipes saved between two saves of the report
+const ARCHIVE_REPORT_EVERY: usize = 25;
+
+const STATUS_RUNNING: &str = "running";
+const STATUS_DONE: &str = "done";
+const STATUS_FAILED: &str = "failed";
+
+/// Body of `POST /api/recipes/import/batch`, `tags` are set on every imported recipe
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct BatchImportRequest {
+    pub urls: Vec<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// Body of `POST /api/recipes/import/crawl`, `url` is any page of the site to crawl
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct CrawlRequest {
+    pub url: String,
+    pub max_pages: Option<usize>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// Query of `POST /api/recipes/import/archive`, the format is guessed when not given
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct ArchiveImportOptions {
+    pub format: Option<ArchiveFormat>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ImportOutcome {
+    Pending,
+    Imported {
+        recipe_id: i64,
+    },
+    /// The url had been imported before, `recipe_id` is the recipe saved then
+    Duplicate {
+        recipe_id: i64,
+    },
+    Failed {
+        reason: String,
+    },
+    /// Left alone on purpose, e.g. disallowed by robots.txt
+    Skipped {
+        reason: String,
+    },
+}
+
+/// Outcome of one url, or of one recipe of an archive where `url` is its file in the archive
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportReportItem {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(flatten)]
+    pub outcome: ImportOutcome,
+}
+
+/// Import job as sent to clients, `status` is "running" until every url has an outcome,
+/// or "failed" with an `error` when the job stopped before
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImportJobView {
+    pub id: i64,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub ctime: Option<DateTimeWithTimeZone>,
+    pub mtime: Option<DateTimeWithTimeZone>,
+    pub report: Vec<ImportReportItem>,
+}
+
+impl From<import_job::Model> for ImportJobView {
+    fn from(job: import_job::Model) -> Self {
+        ImportJobView {
+            id: job.id,
+            status: job.status,
+            error: job.error,
+            ctime: job.ctime,
+            mtime: job.mtime,
+            report: serde_json::from_value(job.report).unwrap_or_default(),
+        }
+    }
+}
+
+impl ImportJobView {
+    pub fn is_done(&self) -> bool {
+        self.status == STATUS_DONE
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.status == STATUS_FAILED
+    }
+}
+
+pub struct ImportJobMac;
+
+impl ImportJobMac {
+    /// Saves a job with every url of `request` pending. Urls repeated
+    /// (once normalized) are only imported once.
+    pub async fn create_batch(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        request: &BatchImportRequest,
+    ) -> Result<ImportJobView, Error> {
+        if request.urls.is_empty() {
+            return Err(Error::InvalidImport("no urls to import".to_string()));
+        }
+
+        let mut seen = HashSet::new();
+        let report: Vec<ImportReportItem> = request
+            .urls
+            .iter()
+            .map(|url| url.trim())
+            .filter(|url| !url.is_empty() && seen.insert(normalize_source_url(url)))
+            .map(|url| ImportReportItem {
+                url: url.to_string(),
+                title: None,
+                outcome: ImportOutcome::Pending,
+            })
+            .collect();
+
+        if report.len() > MAX_BATCH_URLS {
+            return Err(Error::InvalidImport(format!(
+                "{} urls, at most {} can be imported at once",
+                report.len(),
+                MAX_BATCH_URLS
+            )));
+        }
+
+        Self::create(db, utx, report).await
+    }
+
+    /// Saves a running job with `report` as its starting point,
+    /// unless the chef already has `MAX_RUNNING_JOBS` running
+    pub async fn create(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        report: Vec<ImportReportItem>,
+    ) -> Result<ImportJobView, Error> {
+        let running = ImportJob::find()
+            .filter(import_job::Column::Cid.eq(utx.user_id.as_str()))
+            .filter(import_job::Column::Status.eq(STATUS_RUNNING))
+            .count(db)
+            .await?;
+        if running >= MAX_RUNNING_JOBS {
+            return Err(Error::TooManyImportJobs(MAX_RUNNING_JOBS));
+        }
+
+        let job = import_job::ActiveModel {
+            cid: Set(utx.user_id.clone()),
+            status: Set(STATUS_RUNNING.to_string()),
+            report: Set(report_json(&report)),
+            ..Default::default()
+        };
+        let job: import_job::Model = job.insert(db).await?;
+
+        Ok(job.into())
+    }
+
+    pub async fn get(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        id: i64,
+    ) -> Result<ImportJobView, Error> {
+        let job = ImportJob::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| Error::EntityNotFound("import job", id.to_string()))?;
+
+        if job.cid != utx.user_id {
+            return Err(Error::AccessDenied(format!("import job {}", id)));
+        }
+
+        Ok(job.into())
+    }
+
+    /// Saves the report so far, and marks the job done when `done`
+    pub async fn save_report(
+        db: &DatabaseConnection,
+        id: i64,
+        report: &[ImportReportItem],
+        done: bool,
+    ) -> Result<(), Error> {
+        let now: DateTimeWithTimeZone = DateTimeUtc::from(SystemTime::now()).into();
+        let mut job = import_job::ActiveModel {
+            id: Set(id),
+            report: Set(report_json(report)),
+            mtime: Set(Some(now)),
+            ..Default::default()
+        };
+        if done {
+            job.status = Set(STATUS_DONE.to_string());
+        }
+        job.update(db).await?;
+
+        Ok(())
+    }
+
+    /// Marks a job failed because of `ex`, its urls still pending are failed with it
+    pub async fn fail(db: &DatabaseConnection, id: i64, ex: Error) -> Result<(), Error> {
+        let reason = failure_reason(&format!("job {}", id), ex);
+
+        fail_job(db, id, reason).await
+    }
+
+    /// Fails the jobs still running, on startup nothing runs them anymore.
+    /// Returns how many were failed.
+    pub async fn fail_interrupted(db: &DatabaseConnection) -> Result<usize, Error> {
+        let jobs = ImportJob::find()
+            .filter(import_job::Column::Status.eq(STATUS_RUNNING))
+            .all(db)
+            .await?;
+
+        for job in &jobs {
+            fail_job(db, job.id, "Interrupted by a server restart".to_string()).await?;
+        }
+
+        Ok(jobs.len())
+    }
+
+    /// Imports the pending urls of `job`, a few at a time and at most `MAX_PER_HOST`
+    /// per host, saving the report as each one finishes
+    pub async fn run_batch(
+        db: Arc<DatabaseConnection>,
+        utx: UserCtx,
+        job: ImportJobView,
+        tags: Option<Vec<String>>,
+        cache: ScrapeCache,
+    ) -> Result<ImportJobView, Error> {
+        let mut report = job.report;
+        let cache = Arc::new(cache);
+        let all = Arc::new(Semaphore::new(MAX_CONCURRENT));
+        let mut hosts: HashMap<String, Arc<Semaphore>> = HashMap::new();
+        let mut tasks = JoinSet::new();
+
+        for (index, item) in report.iter().enumerate() {
+            if item.outcome != ImportOutcome::Pending {
+                continue;
+            }
+
+            let host = Url::parse(&item.url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default();
+            let host = hosts
+                .entry(host)
+                .or_insert_with(|| Arc::new(Semaphore::new(MAX_PER_HOST)))
+                .clone();
+
+            let (db, utx, cache, all) = (db.clone(), utx.clone(), cache.clone(), all.clone());
+            let (url, tags) = (item.url.clone(), tags.clone());
+            tasks.spawn(async move {
+                // the host permit first, so a slow host doesn't hold up the others
+                let _host = host.acquire_owned().await;
+                let _all = all.acquire_owned().await;
+                let import = async move { import_url(&db, utx, &cache, &url, tags, false).await };
+                // its own task, so a panic is the outcome of this url rather than lost
+                let outcome = match tokio::spawn(import).await {
+                    Ok(outcome) => outcome,
+                    Err(ex) => failed("batch url", Error::IOErr(ex.into())),
+                };
+                (index, outcome)
+            });
+        }
+
+        while let Some(result) = tasks.join_next().await {
+            // only awaiting the permits can fail, which leaves the url to the error below
+            let (index, outcome) = result.map_err(|ex| Error::IOErr(ex.into()))?;
+            report[index].outcome = outcome;
+            Self::save_report(&db, job.id, &report, false).await?;
+        }
+
+        Self::save_report(&db, job.id, &report, true).await?;
+
+        Self::get(&db, &utx, job.id).await
+    }
+
+    /// Saves a job for crawling the site of `request.url`, its pages are only
+    /// known once `run_crawl` has read the sitemaps
+    pub async fn create_crawl(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        request: &CrawlRequest,
+    ) -> Result<ImportJobView, Error> {
+        let is_web_url = Url::parse(&request.url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+        if !is_web_url {
+            return Err(ScrapeError::InvalidUrl(request.url.clone()).into());
+        }
+        if request.max_pages == Some(0) || request.max_pages > Some(MAX_CRAWL_PAGES) {
+            return Err(Error::InvalidImport(format!(
+                "max_pages must be between 1 and {}",
+                MAX_CRAWL_PAGES
+            )));
+        }
+
+        Self::create(db, utx, Vec::new()).await
+    }
+
+    /// Imports the recipes of a site found through its robots.txt and sitemaps,
+    /// one page at a time with at least `delay` (or the site's Crawl-delay) in between
+    pub async fn run_crawl(
+        db: Arc<DatabaseConnection>,
+        utx: UserCtx,
+        job: ImportJobView,
+        request: CrawlRequest,
+        cache: ScrapeCache,
+        delay: Duration,
+    ) -> Result<ImportJobView, Error> {
+        let max_pages = request.max_pages.unwrap_or(MAX_CRAWL_PAGES);
+
+        let discovery = match discover_site(&request.url, &cache.policy, max_pages).await {
+            Ok(discovery) => discovery,
+            Err(ex) => {
+                let report = [ImportReportItem {
+                    url: request.url,
+                    title: None,
+                    outcome: ImportOutcome::Failed {
+                        reason: ex.to_string(),
+                    },
+                }];
+                Self::save_report(&db, job.id, &report, true).await?;
+                return Self::get(&db, &utx, job.id).await;
+            }
+        };
+
+        let pending = discovery.pages.into_iter().map(|url| ImportReportItem {
+            url,
+            title: None,
+            outcome: ImportOutcome::Pending,
+        });
+        let disallowed = discovery
+            .disallowed
+            .into_iter()
+            .map(|url| ImportReportItem {
+                url,
+                title: None,
+                outcome: ImportOutcome::Skipped {
+                    reason: "Disallowed by robots.txt".to_string(),
+                },
+            });
+        // past max_pages the disallowed pages are only counted, under the site url
+        let disallowed_more = (discovery.disallowed_more > 0).then(|| ImportReportItem {
+            url: request.url.clone(),
+            title: None,
+            outcome: ImportOutcome::Skipped {
+                reason: format!(
+                    "{} more pages disallowed by robots.txt",
+                    discovery.disallowed_more
+                ),
+            },
+        });
+        let mut report: Vec<ImportReportItem> =
+            pending.chain(disallowed).chain(disallowed_more).collect();
+        Self::save_report(&db, job.id, &report, false).await?;
+
+        let delay = discovery
+            .crawl_delay
+            .map_or(delay, |crawl| crawl.max(delay));
+        for index in 0..report.len() {
+            if report[index].outcome != ImportOutcome::Pending {
+                continue;
+            }
+            if index > 0 {
+                tokio::time::sleep(delay).await;
+            }
+
+            let url = report[index].url.clone();
+            report[index].outcome =
+                import_url(&db, utx.clone(), &cache, &url, request.tags.clone(), true).await;
+            Self::save_report(&db, job.id, &report, false).await?;
+        }
+
+        Self::save_report(&db, job.id, &report, true).await?;
+
+        Self::get(&db, &utx, job.id).await
+    }
+
+    /// Saves a job for the recipes of an archive, the ones that couldn't be read
+    /// are failed from the start
+    pub async fn create_archive(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        entries: &[ArchiveEntry],
+    ) -> Result<ImportJobView, Error> {
+        let report = entries
+            .iter()
+            .map(|entry| ImportReportItem {
+                url: entry.name.clone(),
+                title: entry
+                    .recipe
+                    .as_ref()
+                    .ok()
+                    .map(|recipe| recipe.title.clone()),
+                outcome: match &entry.recipe {
+                    Ok(_) => ImportOutcome::Pending,
+                    Err(reason) => ImportOutcome::Failed {
+                        reason: reason.clone(),
+                    },
+                },
+            })
+            .collect();
+
+        Self::create(db, utx, report).await
+    }
+
+    /// Saves the readable recipes of `entries` (the ones `job` was created with) in turn
+    pub async fn run_archive(
+        db: Arc<DatabaseConnection>,
+        utx: UserCtx,
+        job: ImportJobView,
+        entries: Vec<ArchiveEntry>,
+    ) -> Result<ImportJobView, Error> {
+        let mut report = job.report;
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            let (Some(item), Ok(recipe)) = (report.get_mut(index), entry.recipe) else {
+                continue;
+            };
+            item.outcome = create_recipe(&db, utx.clone(), &entry.name, recipe).await;
+
+            if (index + 1) % ARCHIVE_REPORT_EVERY == 0 {
+                Self::save_report(&db, job.id, &report, false).await?;
+            }
+        }
+        Self::save_report(&db, job.id, &report, true).await?;
+
+        Self::get(&db, &utx, job.id).await
+    }
+}
+
+/// Saves a job as failed for `reason`, along with its urls still pending
+async fn fail_job(db: &DatabaseConnection, id: i64, reason: String) -> Result<(), Error> {
+    let job = ImportJob::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| Error::EntityNotFound("import job", id.to_string()))?;
+
+    let mut report: Vec<ImportReportItem> =
+        serde_json::from_value(job.report.clone()).unwrap_or_default();
+    for item in report.iter_mut() {
+        if item.outcome == ImportOutcome::Pending {
+            item.outcome = ImportOutcome::Failed {
+                reason: reason.clone(),
+            };
+        }
+    }
+
+    let now: DateTimeWithTimeZone = DateTimeUtc::from(SystemTime::now()).into();
+    let mut job: import_job::ActiveModel = job.into();
+    job.status = Set(STATUS_FAILED.to_string());
+    job.error = Set(Some(reason));
+    job.report = Set(report_json(&report));
+    job.mtime = Set(Some(now));
+    job.update(db).await?;
+
+    Ok(())
+}
+
+/// Scrapes and saves one url of a job, failures end up in the report.
+/// With `structured_only`, pages without schema.org Recipe data are skipped.
+async fn import_url(
+    db: &DatabaseConnection,
+    utx: UserCtx,
+    cache: &ScrapeCache,
+    url: &str,
+    tags: Option<Vec<String>>,
+    structured_only: bool,
+) -> ImportOutcome {
+    let imported = async {
+        if let Some(recipe) = RecipeMac::find_by_source_url(db, &utx, url).await? {
+            return Ok(ImportOutcome::Duplicate {
+                recipe_id: recipe.id,
+            });
+        }
+
+        let scraped = cache.scrape(db, url, None).await?;
+        if structured_only && !STRUCTURED_SOURCES.contains(&scraped.source.as_str()) {
+            return Ok(skipped_no_recipe());
+        }
+
+        let request = ImportRequest {
+            url: url.to_string(),
+            title: None,
+            tags,
+        };
+        let imported = RecipeMac::import(db, utx, scraped, request).await?;
+
+        Ok::<_, Error>(if imported.existing {
+            ImportOutcome::Duplicate {
+                recipe_id: imported.recipe.id,
+            }
+        } else {
+            ImportOutcome::Imported {
+                recipe_id: imported.recipe.id,
+            }
+        })
+    };
+
+    match imported.await {
+        Ok(outcome) => outcome,
+        Err(Error::ScrapeErr(ScrapeError::NoRecipeFound(_))) if structured_only => {
+            skipped_no_recipe()
+        }
+        Err(ex) => failed(url, ex),
+    }
+}
+
+/// Saves one recipe of an archive, unless its source url was imported before
+async fn create_recipe(
+    db: &DatabaseConnection,
+    utx: UserCtx,
+    name: &str,
+    recipe: ArchivedRecipe,
+) -> ImportOutcome {
+    let created = async {
+        if let Some(source_url) = &recipe.source_url {
+            if let Some(existing) = RecipeMac::find_by_source_url(db, &utx, source_url).await? {
+                return Ok(ImportOutcome::Duplicate {
+                    recipe_id: existing.id,
+                });
+            }
+        }
+
+        let recipe = RecipeMac::create(db, recipe.into(), utx).await?;
+
+        Ok::<_, Error>(ImportOutcome::Imported {
+            recipe_id: recipe.id,
+        })
+    };
+
+    created.await.unwrap_or_else(|ex| failed(name, ex))
+}
+
+fn failed(what: &str, ex: Error) -> ImportOutcome {
+    ImportOutcome::Failed {
+        reason: failure_reason(what, ex),
+    }
+}
+
+/// Reason shown in reports, database and io details stay server side as in the web layer
+pub(super) fn failure_reason(what: &str, ex: Error) -> String {
+    match ex {
+        Error::SeaOrmErr(_) | Error::SqlxErr(_) => {
+            println!("ERROR - import {} - {:?}", what, ex);
+            "Database error".to_string()
+        }
+        Error::IOErr(_) => {
+            println!("ERROR - import {} - {:?}", what, ex);
+            "Internal error".to_string()
+        }
+        ex => ex.to_string(),
+    }
+}
+
+fn skipped_no_recipe() -> ImportOutcome {
+    ImportOutcome::Skipped {
+        reason: "No schema.org Recipe data".to_string(),
+    }
+}
+
+fn report_json(report: &[ImportReportItem]) -> Json {
+    serde_json::to_value(report).unwrap_or_default()
+}
+
+#[cfg(test)]
+#[path = "../_tests/model_import_job.rs"]
+mod tests;
@@ -0,0 +1,153 @@
+use std::{fs, path::Path};
+
+use sha2::{Digest, Sha256};
+use sqlx::{Executor, Pool, Postgres, Row};
+
+pub const MIGRATIONS_DIR: &str = "sql/migrations/";
+
+/// Arbitrary key so that two instances starting together don't apply the same migration
+const MIGRATION_LOCK_KEY: i64 = 0x636f_6f6b_626f_6f6b;
+
+const SQL_CREATE_MIGRATIONS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version bigint primary key,
+    name text NOT NULL,
+    checksum text NOT NULL,
+    applied_at timestamp with time zone DEFAULT now()
+)";
+
+/// A `sql/migrations/NNNN-name.sql` file
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub name: String,
+    pub sql: String,
+    pub checksum: String,
+}
+
+/// Reads the migrations of `dir`, ordered by version
+pub fn read_migrations(dir: &str) -> Result<Vec<Migration>, super::Error> {
+    let mut migrations: Vec<Migration> = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("sql") {
+            continue;
+        }
+
+        let name = file_name(&path);
+        let version = parse_version(&name)?;
+        let sql = fs::read_to_string(&path)?;
+        let checksum = format!("{:x}", Sha256::digest(sql.as_bytes()));
+
+        migrations.push(Migration {
+            version,
+            name,
+            sql,
+            checksum,
+        });
+    }
+
+    migrations.sort_by_key(|m| m.version);
+
+    if let Some(pair) = migrations.windows(2).find(|w| w[0].version == w[1].version) {
+        return Err(super::Error::InvalidMigration(format!(
+            "{} and {} share version {}",
+            pair[0].name, pair[1].name, pair[0].version
+        )));
+    }
+
+    Ok(migrations)
+}
+
+/// Applies the pending migrations of `dir`, each in its own transaction.
+/// Refuses to run when an applied migration was edited or removed since.
+pub async fn migrate(db: &Pool<Postgres>, dir: &str) -> Result<Vec<String>, super::Error> {
+    let migrations = read_migrations(dir)?;
+
+    // Advisory locks belong to a session, so keep one connection for the whole run
+    let mut conn = db.acquire().await?;
+    conn.execute(SQL_CREATE_MIGRATIONS_TABLE).await?;
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    let result = apply_pending(&mut conn, &migrations).await;
+
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    result
+}
+
+async fn apply_pending(
+    conn: &mut sqlx::PgConnection,
+    migrations: &[Migration],
+) -> Result<Vec<String>, super::Error> {
+    let applied = sqlx::query("SELECT version, name, checksum FROM schema_migrations")
+        .fetch_all(&mut *conn)
+        .await?;
+
+    for row in applied.iter() {
+        let version: i64 = row.try_get("version")?;
+        let name: String = row.try_get("name")?;
+        let checksum: String = row.try_get("checksum")?;
+
+        match migrations.iter().find(|m| m.version == version) {
+            Some(migration) if migration.checksum != checksum => {
+                return Err(super::Error::MigrationChecksumMismatch(name));
+            }
+            Some(_) => (),
+            None => return Err(super::Error::MigrationMissing(name)),
+        }
+    }
+
+    let mut newly_applied = Vec::new();
+
+    for migration in migrations {
+        let is_applied = applied
+            .iter()
+            .any(|row| row.try_get::<i64, _>("version").ok() == Some(migration.version));
+        if is_applied {
+            continue;
+        }
+
+        // No bind parameters, so sqlx sends the whole file as one multi statement query
+        let mut tx = sqlx::Connection::begin(&mut *conn).await?;
+        tx.execute(migration.sql.as_str()).await?;
+        sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES ($1, $2, $3)")
+            .bind(migration.version)
+            .bind(&migration.name)
+            .bind(&migration.checksum)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        println!("Applied migration {}", migration.name);
+        newly_applied.push(migration.name.clone());
+    }
+
+    Ok(newly_applied)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// `0002-recipe-search.sql` -> 2
+fn parse_version(name: &str) -> Result<i64, super::Error> {
+    name.split(['-', '_'])
+        .next()
+        .and_then(|prefix| prefix.parse::<i64>().ok())
+        .ok_or_else(|| super::Error::InvalidMigration(format!("{} has no version prefix", name)))
+}
+
+#[cfg(test)]
+#[path = "../_tests/model_migration.rs"]
+mod tests;
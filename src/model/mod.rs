@@ -1,20 +1,74 @@
 use thiserror::Error as ThisError;
 
 mod chef;
+mod cookbook;
 mod db;
+mod import_job;
+mod migration;
+mod obsidian;
+mod page;
 mod recipe;
+mod scrape_cache;
+mod search;
 
 pub use chef::{ChefMac, ChefPatch};
+pub use cookbook::{CookbookMac, CookbookOptions, MAX_COOKBOOK_RECIPES};
 pub use db::init_db;
-pub use recipe::{RecipeMac, RecipePatch};
+pub use import_job::{
+    ArchiveImportOptions, BatchImportRequest, CrawlRequest, ImportJobMac, ImportJobView,
+    ImportOutcome, ImportReportItem, MAX_RUNNING_JOBS,
+};
+pub use obsidian::{ObsidianMac, VaultOutcome, VaultReportItem};
+pub use page::{ListOptions, Page};
+pub use recipe::{
+    CooklangImportOptions, ImportRequest, RecipeMac, RecipePatch, RecipeView, ScaleOptions,
+    ScaledRecipe, ViewOptions,
+};
+pub use scrape_cache::ScrapeCache;
+pub use search::{RecipeSearchHit, SearchOptions};
 
 #[derive(ThisError, Debug)]
 pub enum Error {
     #[error("Entity with same firebase id already exists")]
     EntityAlreadyExists,
 
-    #[error("Entity Not Found - {0}")]
-    EntityNotFound(String),
+    /// (entity kind, id)
+    #[error("Entity Not Found - {0} {1}")]
+    EntityNotFound(&'static str, String),
+
+    #[error("Access Denied - {0}")]
+    AccessDenied(String),
+
+    #[error("Invalid list option - {0}")]
+    InvalidListOption(String),
+
+    #[error("Invalid scale - {0}")]
+    InvalidScale(String),
+
+    #[error("Invalid recipe - {0}")]
+    InvalidRecipe(String),
+
+    #[error("Invalid import - {0}")]
+    InvalidImport(String),
+
+    /// the most import jobs a chef can have running
+    #[error("Too many import jobs - at most {0} can run at once")]
+    TooManyImportJobs(u64),
+
+    #[error("Invalid migration - {0}")]
+    InvalidMigration(String),
+
+    #[error("Applied migration {0} was modified, refusing to start")]
+    MigrationChecksumMismatch(String),
+
+    #[error("Applied migration {0} is missing from the migrations folder, refusing to start")]
+    MigrationMissing(String),
+
+    #[error(transparent)]
+    ScrapeErr(#[from] crate::utils::ScrapeError),
+
+    #[error(transparent)]
+    ArchiveErr(#[from] crate::utils::ArchiveError),
 
     #[error(transparent)]
     SeaOrmErr(#[from] sea_orm::DbErr),
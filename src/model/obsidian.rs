@@ -0,0 +1,280 @@
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::recipe;
+use crate::model::import_job::failure_reason;
+use crate::model::{CookbookMac, CookbookOptions, Error, RecipeMac, RecipePatch};
+use crate::security::UserCtx;
+use crate::utils::{write_vault, RecipeNote, VaultEntry};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum VaultOutcome {
+    Created {
+        recipe_id: i64,
+    },
+    Updated {
+        recipe_id: i64,
+    },
+    /// The note matches the recipe
+    Unchanged {
+        recipe_id: i64,
+    },
+    /// Both the note and the recipe were edited since the export, the recipe is left alone
+    Conflict {
+        recipe_id: i64,
+        reason: String,
+    },
+    Failed {
+        reason: String,
+    },
+}
+
+/// Outcome of one note, `file` is its path in the vault
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VaultReportItem {
+    pub file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(flatten)]
+    pub outcome: VaultOutcome,
+}
+
+pub struct ObsidianMac;
+
+impl ObsidianMac {
+    /// Zipped vault folder of the recipes picked by `options`, one note per recipe
+    pub async fn export(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        options: &CookbookOptions,
+    ) -> Result<Vec<u8>, Error> {
+        let (_, recipes) = CookbookMac::recipes(db, utx, options).await?;
+        let notes: Vec<RecipeNote> = recipes.iter().map(RecipeNote::from).collect();
+
+        Ok(write_vault(&notes)?)
+    }
+
+    /// Saves the notes of a vault: notes with the id of one of the caller's recipes update it,
+    /// the others are created. A recipe saved in the app after the note's `updated` time is
+    /// reported as a conflict rather than overwritten, a note that can't be saved as failed.
+    pub async fn import(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        entries: Vec<VaultEntry>,
+    ) -> Vec<VaultReportItem> {
+        let mut report = Vec::with_capacity(entries.len());
+
+        for entry in entries {
+            let (title, outcome) = match entry.note {
+                Ok(note) => {
+                    let title = Some(note.title.clone());
+                    match import_note(db, utx, note).await {
+                        Ok(outcome) => (title, outcome),
+                        Err(ex) => (title, failed(&entry.name, ex)),
+                    }
+                }
+                Err(reason) => (None, VaultOutcome::Failed { reason }),
+            };
+            report.push(VaultReportItem {
+                file: entry.name,
+                title,
+                outcome,
+            });
+        }
+
+        report
+    }
+}
+
+async fn import_note(
+    db: &DatabaseConnection,
+    utx: &UserCtx,
+    note: RecipeNote,
+) -> Result<VaultOutcome, Error> {
+    let existing = match note.id {
+        Some(id) => match RecipeMac::get(db, utx.clone(), id).await {
+            Ok(recipe) => Some(recipe),
+            // Deleted since the export, or another chef's vault: saved as a new recipe
+            Err(Error::EntityNotFound(..) | Error::AccessDenied(_)) => None,
+            Err(ex) => return Err(ex),
+        },
+        None => None,
+    };
+    let updated = note.updated.clone();
+    let data = RecipePatch::from(note);
+
+    let Some(recipe) = existing else {
+        let recipe = RecipeMac::create(db, data, utx.clone()).await?;
+        return Ok(VaultOutcome::Created {
+            recipe_id: recipe.id,
+        });
+    };
+
+    let changes = changed_fields(&data, &recipe);
+    if changes.is_empty() {
+        return Ok(VaultOutcome::Unchanged {
+            recipe_id: recipe.id,
+        });
+    }
+
+    let exported = updated
+        .as_deref()
+        .and_then(|updated| DateTimeWithTimeZone::parse_from_rfc3339(updated).ok());
+    let saved = recipe.mtime.or(recipe.ctime);
+    let conflict = match (exported, saved) {
+        (None, _) => Some("the note has no valid `updated` time, export the recipe again"),
+        (Some(exported), Some(saved)) if saved > exported => {
+            Some("the recipe was edited in the app after the note was exported")
+        }
+        _ => None,
+    };
+    if let Some(reason) = conflict {
+        return Ok(VaultOutcome::Conflict {
+            recipe_id: recipe.id,
+            reason: format!("{}, the note changes {}", reason, changes.join(", ")),
+        });
+    }
+
+    let recipe = RecipeMac::update(db, data, utx.clone(), recipe.id).await?;
+    Ok(VaultOutcome::Updated {
+        recipe_id: recipe.id,
+    })
+}
+
+fn failed(file: &str, ex: Error) -> VaultOutcome {
+    VaultOutcome::Failed {
+        reason: failure_reason(file, ex),
+    }
+}
+
+/// Fields the patch would change, what the note leaves out doesn't count
+fn changed_fields(data: &RecipePatch, recipe: &recipe::Model) -> Vec<&'static str> {
+    fn differs<T: PartialEq>(new: &Option<T>, old: T) -> bool {
+        new.as_ref().is_some_and(|new| *new != old)
+    }
+    fn text(value: &Option<String>) -> String {
+        value.as_deref().unwrap_or_default().trim().to_string()
+    }
+
+    let checks = [
+        ("title", differs(&data.title, recipe.title.clone())),
+        ("header", differs(&data.header, text(&recipe.header))),
+        (
+            "tags",
+            differs(&data.tags, recipe.tags.clone().unwrap_or_default()),
+        ),
+        (
+            "prep_time",
+            differs(&data.prep_time, text(&recipe.prep_time)),
+        ),
+        (
+            "cook_time",
+            differs(&data.cook_time, text(&recipe.cook_time)),
+        ),
+        (
+            "total_time",
+            differs(&data.total_time, text(&recipe.total_time)),
+        ),
+        (
+            "servings",
+            data.servings.is_some() && data.servings != recipe.servings,
+        ),
+        ("image", differs(&data.image_url, text(&recipe.image_url))),
+        (
+            "source",
+            differs(&data.source_url, text(&recipe.source_url)),
+        ),
+        (
+            "source_name",
+            differs(&data.source_name, text(&recipe.source_name)),
+        ),
+        (
+            "author",
+            differs(&data.original_author, text(&recipe.original_author)),
+        ),
+        (
+            "ingredients",
+            differs(
+                &data.ingredients,
+                recipe.ingredients.clone().unwrap_or_default(),
+            ),
+        ),
+        (
+            "steps",
+            differs(&data.steps, recipe.steps.clone().unwrap_or_default()),
+        ),
+        (
+            "equipment",
+            differs(
+                &data.equipment,
+                recipe.equipment.clone().unwrap_or_default(),
+            ),
+        ),
+    ];
+
+    checks
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(field, _)| field)
+        .collect()
+}
+
+/// `Option` of a non blank text
+fn filled(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+impl From<&recipe::Model> for RecipeNote {
+    fn from(recipe: &recipe::Model) -> Self {
+        RecipeNote {
+            id: Some(recipe.id),
+            title: recipe.title.clone(),
+            header: filled(&recipe.header),
+            tags: Some(recipe.tags.clone().unwrap_or_default()),
+            prep_time: filled(&recipe.prep_time),
+            cook_time: filled(&recipe.cook_time),
+            total_time: filled(&recipe.total_time),
+            servings: recipe.servings,
+            // Photos stored as data urls would bury the note, they stay in the app
+            image_url: filled(&recipe.image_url).filter(|url| !url.starts_with("data:")),
+            source_url: filled(&recipe.source_url),
+            source_name: filled(&recipe.source_name),
+            original_author: filled(&recipe.original_author),
+            updated: recipe.mtime.or(recipe.ctime).map(|time| time.to_rfc3339()),
+            ingredients: recipe.ingredients.clone(),
+            steps: recipe.steps.clone(),
+            equipment: recipe.equipment.clone(),
+        }
+    }
+}
+
+impl From<RecipeNote> for RecipePatch {
+    fn from(note: RecipeNote) -> Self {
+        RecipePatch {
+            title: Some(note.title),
+            header: note.header,
+            steps: note.steps,
+            ingredients: note.ingredients,
+            tags: note.tags,
+            image_url: note.image_url,
+            cook_time: note.cook_time,
+            prep_time: note.prep_time,
+            total_time: note.total_time,
+            servings: note.servings,
+            source_url: note.source_url,
+            source_name: note.source_name,
+            original_author: note.original_author,
+            equipment: note.equipment,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "../_tests/model_obsidian.rs"]
+mod tests;
@@ -0,0 +1,107 @@
+use sea_orm::{
+    prelude::*, DatabaseConnection, Order, PaginatorTrait, QueryOrder, QuerySelect, Select,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::recipe;
+
+const DEFAULT_LIMIT: u64 = 50;
+const MAX_LIMIT: u64 = 200;
+
+/// Query options shared by the recipe list endpoints
+/// e.g. `?limit=20&offset=40&sort=-mtime`
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct ListOptions {
+    pub limit: Option<u64>,
+    /// `next_cursor` of the previous page can be sent back as `cursor`
+    #[serde(alias = "cursor")]
+    pub offset: Option<u64>,
+    /// One of `title`, `ctime`, `mtime`, `total_minutes`, prefixed with `-` for descending
+    pub sort: Option<String>,
+    /// Only recipes ready in this many minutes, recipes without a known time are left out
+    pub max_total_minutes: Option<i32>,
+}
+
+/// One page of results with the total count across all pages
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    /// Offset of the following page, `None` on the last page
+    pub next_cursor: Option<u64>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+pub async fn paginate_recipes(
+    db: &DatabaseConnection,
+    select: Select<recipe::Entity>,
+    options: ListOptions,
+) -> Result<Page<recipe::Model>, super::Error> {
+    let (limit, offset) = page_bounds(options.limit, options.offset);
+
+    let mut select = select;
+    if let Some(max) = options.max_total_minutes {
+        select = select.filter(recipe::Column::TotalMinutes.lte(max));
+    }
+
+    let total = select.clone().count(db).await?;
+
+    if let Some(sort) = options.sort.as_deref() {
+        let (column, order) = recipe_sort(sort)?;
+        select = select.order_by(column, order);
+    }
+
+    // id last so pages are stable when the sort column has ties
+    let items: Vec<recipe::Model> = select
+        .order_by(recipe::Column::Id, Order::Asc)
+        .offset(offset)
+        .limit(limit)
+        .all(db)
+        .await?;
+
+    Ok(Page {
+        items,
+        total,
+        next_cursor: next_cursor(limit, offset, total),
+    })
+}
+
+/// Applies the default and maximum page size, returns (limit, offset)
+/// The offset is kept within a Postgres `bigint`, past that it would bind as a negative OFFSET
+pub(super) fn page_bounds(limit: Option<u64>, offset: Option<u64>) -> (u64, u64) {
+    (
+        limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+        offset.unwrap_or(0).min(i64::MAX as u64),
+    )
+}
+
+/// Offset of the page after the one starting at `offset`, `None` when it is the last
+pub(super) fn next_cursor(limit: u64, offset: u64, total: u64) -> Option<u64> {
+    offset.checked_add(limit).filter(|next| *next < total)
+}
+
+fn recipe_sort(sort: &str) -> Result<(recipe::Column, Order), super::Error> {
+    let (name, order) = match sort.strip_prefix('-') {
+        Some(name) => (name, Order::Desc),
+        None => (sort, Order::Asc),
+    };
+
+    let column = match name {
+        "title" => recipe::Column::Title,
+        "ctime" => recipe::Column::Ctime,
+        "mtime" => recipe::Column::Mtime,
+        "total_minutes" => recipe::Column::TotalMinutes,
+        _ => return Err(super::Error::InvalidListOption(format!("sort '{}'", sort))),
+    };
+
+    Ok((column, order))
+}
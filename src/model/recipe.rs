@@ -1,11 +1,23 @@
+use std::time::SystemTime;
+
 use sea_orm::sea_query::{any, Expr, PgFunc};
-use sea_orm::{prelude::*, DatabaseConnection, Set};
+use sea_orm::{prelude::*, DatabaseConnection, QueryOrder, Set, TransactionTrait};
 use serde::{Deserialize, Serialize};
 
 use crate::entities::{prelude::Recipe, recipe};
 
-use crate::model::Error;
+use crate::model::page::paginate_recipes;
+use crate::model::search::search_recipes;
+use crate::model::{Error, ListOptions, Page, RecipeSearchHit, SearchOptions};
 use crate::security::UserCtx;
+use crate::utils::{
+    convert_ingredient, convert_temperatures, format_minutes, iso8601_duration,
+    normalize_source_url, parse_ingredient, parse_minutes, scale_ingredient, ArchivedRecipe,
+    CooklangIngredient, CooklangRecipe, Ingredient, ScrapedRecipe, UnitSystem,
+};
+
+/// Largest `factor` accepted when scaling, past this the rounding is meaningless anyway
+const MAX_SCALE_FACTOR: f64 = 100.0;
 
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct RecipePatch {
@@ -18,6 +30,333 @@ pub struct RecipePatch {
     pub cook_time: Option<String>,
     pub prep_time: Option<String>,
     pub total_time: Option<String>,
+    pub servings: Option<i32>,
+    pub source_url: Option<String>,
+    pub source_name: Option<String>,
+    pub original_author: Option<String>,
+    pub equipment: Option<Vec<String>>,
+}
+
+impl From<ScrapedRecipe> for RecipePatch {
+    fn from(scraped: ScrapedRecipe) -> Self {
+        RecipePatch {
+            title: scraped.title,
+            header: scraped.header,
+            steps: Some(scraped.steps),
+            ingredients: Some(scraped.ingredients),
+            tags: Some(scraped.tags),
+            image_url: scraped.image_url,
+            cook_time: scraped.cook_time,
+            prep_time: scraped.prep_time,
+            total_time: scraped.total_time,
+            servings: scraped.servings,
+            source_url: Some(scraped.source_url),
+            source_name: scraped.source_name,
+            original_author: scraped.author,
+            equipment: None,
+        }
+    }
+}
+
+impl From<ArchivedRecipe> for RecipePatch {
+    fn from(archived: ArchivedRecipe) -> Self {
+        RecipePatch {
+            title: Some(archived.title),
+            header: archived.header,
+            steps: Some(archived.steps),
+            ingredients: Some(archived.ingredients),
+            tags: Some(archived.tags),
+            image_url: archived.image_url,
+            cook_time: archived.cook_time,
+            prep_time: archived.prep_time,
+            total_time: archived.total_time,
+            servings: archived.servings,
+            source_url: archived.source_url.as_deref().map(normalize_source_url),
+            source_name: archived.source_name,
+            original_author: archived.original_author,
+            equipment: None,
+        }
+    }
+}
+
+/// Metadata keys read for each field, the first one given wins
+const COOKLANG_PREP_TIME: &[&str] = &["prep time", "prep_time", "time.prep"];
+const COOKLANG_COOK_TIME: &[&str] = &["cook time", "cook_time", "time.cook"];
+const COOKLANG_TOTAL_TIME: &[&str] = &["time", "total time", "total_time", "duration"];
+
+/// Metadata maps to title, servings, tags, source, author, times and image,
+/// the description and notes to the header. Timers add up to the cook time
+/// when the metadata gives none.
+impl From<CooklangRecipe> for RecipePatch {
+    fn from(cook: CooklangRecipe) -> Self {
+        let meta = |keys: &[&str]| cook.meta(keys).map(str::to_string);
+        let is_url = |text: &String| text.starts_with("http://") || text.starts_with("https://");
+
+        let header = meta(&["description"])
+            .into_iter()
+            .chain(cook.notes.iter().cloned())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let tags = meta(&["tags", "tag"])
+            .map(|tags| {
+                tags.trim_matches(|c| c == '[' || c == ']')
+                    .split(',')
+                    .map(|tag| {
+                        tag.trim()
+                            .trim_matches(|c| c == '"' || c == '\'')
+                            .to_string()
+                    })
+                    .filter(|tag| !tag.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let servings = meta(&["servings", "serves", "yield"]).and_then(|servings| {
+            let digits: String = servings
+                .chars()
+                .skip_while(|c| !c.is_ascii_digit())
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            digits.parse().ok()
+        });
+        let timer_minutes: i32 = cook
+            .timers
+            .iter()
+            .filter_map(|timer| match (&timer.quantity, &timer.unit) {
+                (Some(quantity), Some(unit)) => parse_minutes(&format!("{} {}", quantity, unit)),
+                _ => None,
+            })
+            .fold(0, i32::saturating_add);
+        let source = meta(&["source", "source.url", "url"]);
+
+        RecipePatch {
+            title: meta(&["title"]),
+            header: (!header.is_empty()).then_some(header),
+            steps: Some(cook.steps.clone()),
+            ingredients: Some(
+                cook.ingredients
+                    .iter()
+                    .map(CooklangIngredient::line)
+                    .collect(),
+            ),
+            tags: Some(tags),
+            image_url: meta(&["image"]).filter(is_url),
+            prep_time: meta(COOKLANG_PREP_TIME),
+            cook_time: meta(COOKLANG_COOK_TIME)
+                .or_else(|| (timer_minutes > 0).then(|| format_minutes(timer_minutes))),
+            total_time: meta(COOKLANG_TOTAL_TIME),
+            servings,
+            source_url: source
+                .as_ref()
+                .filter(|source| is_url(source))
+                .map(|url| normalize_source_url(url)),
+            source_name: meta(&["source.name"]).or(source.filter(|source| !is_url(source))),
+            original_author: meta(&["author", "source.author"]),
+            equipment: Some(cook.cookware),
+        }
+    }
+}
+
+/// The recipe as Cooklang, the header becomes notes and each ingredient line
+/// is marked up where a step first mentions it
+impl From<&recipe::Model> for CooklangRecipe {
+    fn from(recipe: &recipe::Model) -> Self {
+        let mut metadata = vec![("title".to_string(), recipe.title.clone())];
+        let mut push = |key: &str, value: Option<String>| {
+            if let Some(value) = value.filter(|value| !value.trim().is_empty()) {
+                metadata.push((key.to_string(), value));
+            }
+        };
+        push(
+            "servings",
+            recipe.servings.map(|servings| servings.to_string()),
+        );
+        push("tags", recipe.tags.as_ref().map(|tags| tags.join(", ")));
+        push(
+            "source",
+            recipe.source_url.clone().or(recipe.source_name.clone()),
+        );
+        if recipe.source_url.is_some() {
+            push("source.name", recipe.source_name.clone());
+        }
+        push("author", recipe.original_author.clone());
+        push(COOKLANG_PREP_TIME[0], recipe.prep_time.clone());
+        push(COOKLANG_COOK_TIME[0], recipe.cook_time.clone());
+        push(COOKLANG_TOTAL_TIME[0], recipe.total_time.clone());
+        push(
+            "image",
+            recipe
+                .image_url
+                .clone()
+                .filter(|url| url.starts_with("http")),
+        );
+
+        let notes = recipe
+            .header
+            .iter()
+            .flat_map(|header| header.split("\n\n"))
+            .map(|note| note.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|note| !note.is_empty())
+            .collect();
+        let ingredients = recipe
+            .ingredients
+            .iter()
+            .flatten()
+            .map(|line| CooklangIngredient::from(&parse_ingredient(line)))
+            .collect();
+
+        CooklangRecipe {
+            metadata,
+            notes,
+            ingredients,
+            cookware: recipe.equipment.clone().unwrap_or_default(),
+            timers: Vec::new(),
+            steps: recipe.steps.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Query of `POST /api/recipes/import/cooklang`, `title` for files without one
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct CooklangImportOptions {
+    pub title: Option<String>,
+}
+
+/// Recipe saved by an import, `existing` when it had been imported before
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Imported {
+    pub recipe: recipe::Model,
+    pub existing: bool,
+}
+
+/// Body of `POST /api/recipes/import`, `title` and `tags` replace the scraped ones when given
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct ImportRequest {
+    pub url: String,
+    pub title: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+/// Recipe as sent to clients, with the parsed ingredients next to the raw text
+/// and the times as ISO 8601 durations.
+/// Photos stored inline as `data:` urls are sent as the url of `GET /api/recipes/{id}/image`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecipeView {
+    #[serde(flatten)]
+    pub recipe: recipe::Model,
+    pub parsed_ingredients: Vec<Ingredient>,
+    pub prep_duration: Option<String>,
+    pub cook_duration: Option<String>,
+    pub total_duration: Option<String>,
+}
+
+impl From<recipe::Model> for RecipeView {
+    fn from(mut recipe: recipe::Model) -> Self {
+        if recipe
+            .image_url
+            .as_deref()
+            .is_some_and(|url| url.starts_with("data:"))
+        {
+            recipe.image_url = Some(format!("/api/recipes/{}/image", recipe.id));
+        }
+        let parsed_ingredients = recipe
+            .ingredients
+            .iter()
+            .flatten()
+            .map(|raw| parse_ingredient(raw))
+            .collect();
+
+        RecipeView {
+            prep_duration: recipe.prep_minutes.map(iso8601_duration),
+            cook_duration: recipe.cook_minutes.map(iso8601_duration),
+            total_duration: recipe.total_minutes.map(iso8601_duration),
+            recipe,
+            parsed_ingredients,
+        }
+    }
+}
+
+impl RecipeView {
+    /// The recipe with every ingredient amount multiplied by `factor`,
+    /// the ingredient lines are rewritten to match. Nothing is stored.
+    pub fn scaled(recipe: recipe::Model, factor: f64) -> Self {
+        let view = RecipeView::from(recipe);
+
+        let parsed_ingredients: Vec<Ingredient> = view
+            .parsed_ingredients
+            .iter()
+            .map(|ingredient| scale_ingredient(ingredient, factor))
+            .collect();
+
+        let mut recipe = view.recipe;
+        recipe.ingredients = recipe
+            .ingredients
+            .map(|_| parsed_ingredients.iter().map(|i| i.raw.clone()).collect());
+        recipe.servings = recipe
+            .servings
+            .map(|servings| ((servings as f64 * factor).round() as i32).max(1));
+
+        RecipeView {
+            recipe,
+            parsed_ingredients,
+            ..view
+        }
+    }
+
+    /// The recipe with ingredient amounts and step temperatures rewritten in `system`.
+    /// Nothing is stored.
+    pub fn in_units(self, system: UnitSystem) -> Self {
+        let parsed_ingredients: Vec<Ingredient> = self
+            .parsed_ingredients
+            .iter()
+            .map(|ingredient| convert_ingredient(ingredient, system))
+            .collect();
+
+        let mut recipe = self.recipe;
+        recipe.ingredients = recipe
+            .ingredients
+            .map(|_| parsed_ingredients.iter().map(|i| i.raw.clone()).collect());
+        recipe.steps = recipe.steps.map(|steps| {
+            steps
+                .iter()
+                .map(|step| convert_temperatures(step, system))
+                .collect()
+        });
+
+        RecipeView {
+            recipe,
+            parsed_ingredients,
+            ..self
+        }
+    }
+
+    /// Applies `?units=`, when given
+    pub fn with_options(self, options: &ViewOptions) -> Self {
+        match options.units {
+            Some(system) => self.in_units(system),
+            None => self,
+        }
+    }
+}
+
+/// Query of the endpoints returning a single recipe, e.g. `?units=metric`
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct ViewOptions {
+    pub units: Option<UnitSystem>,
+}
+
+/// Query of `GET /api/recipes/{id}/scaled`, either a target `servings` or a `factor`
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct ScaleOptions {
+    pub servings: Option<i32>,
+    pub factor: Option<f64>,
+    pub units: Option<UnitSystem>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScaledRecipe {
+    #[serde(flatten)]
+    pub recipe: RecipeView,
+    pub factor: f64,
 }
 
 pub struct RecipeMac;
@@ -28,29 +367,95 @@ impl RecipeMac {
         data: RecipePatch,
         utx: UserCtx,
     ) -> Result<recipe::Model, super::Error> {
-        let recipe = recipe::ActiveModel {
-            cid: Set(utx.user_id),
-            title: Set(data.title.unwrap()),
-            header: Set(Some(data.header.unwrap_or_default())),
-            steps: Set(Some(data.steps.unwrap_or_default())),
-            ingredients: Set(Some(data.ingredients.unwrap_or_default())),
-            tags: Set(Some(data.tags.unwrap_or_default())),
-            image_url: Set(Some(data.image_url.unwrap_or_default())),
-            cook_time: Set(Some(data.cook_time.unwrap_or_default())),
-            prep_time: Set(Some(data.prep_time.unwrap_or_default())),
-            total_time: Set(Some(data.total_time.unwrap_or_default())),
-            ..Default::default()
-        };
+        insert_recipe(db, data, utx).await
+    }
 
-        let recipe: recipe::Model = recipe.insert(db).await?;
+    /// The caller's recipe imported from `url`, if any. Urls are compared normalized,
+    /// see `normalize_source_url`
+    pub async fn find_by_source_url(
+        db: &DatabaseConnection,
+        utx: &UserCtx,
+        url: &str,
+    ) -> Result<Option<recipe::Model>, super::Error> {
+        find_by_source_urls(db, utx, &[normalize_source_url(url)]).await
+    }
 
-        Ok(recipe)
+    /// Saves the recipe of a `.cook` file, `options.title` wins over its metadata
+    pub async fn import_cooklang(
+        db: &DatabaseConnection,
+        utx: UserCtx,
+        text: &str,
+        options: CooklangImportOptions,
+    ) -> Result<recipe::Model, super::Error> {
+        let mut data = RecipePatch::from(CooklangRecipe::parse(text));
+        data.title = options.title.or(data.title);
+
+        if data.title.as_deref().unwrap_or_default().trim().is_empty() {
+            return Err(Error::InvalidImport(
+                "the recipe has no title, add `>> title:` or ?title=".to_string(),
+            ));
+        }
+        if data.steps.as_ref().is_none_or(Vec::is_empty) {
+            return Err(Error::InvalidImport("the recipe has no steps".to_string()));
+        }
+
+        insert_recipe(db, data, utx).await
+    }
+
+    /// Saves a scraped recipe for the caller, with the overrides of `request` applied.
+    /// A recipe already imported from the same url is returned instead of saving it twice.
+    pub async fn import(
+        db: &DatabaseConnection,
+        utx: UserCtx,
+        scraped: ScrapedRecipe,
+        request: ImportRequest,
+    ) -> Result<Imported, super::Error> {
+        let mut data = RecipePatch::from(scraped);
+        data.title = request.title.or(data.title);
+        data.tags = request.tags.or(data.tags);
+
+        if data
+            .title
+            .as_deref()
+            .is_none_or(|title| title.trim().is_empty())
+        {
+            return Err(Error::InvalidImport(format!(
+                "no title found at {}, send one with the request",
+                request.url
+            )));
+        }
+
+        let urls: Vec<String> = data
+            .source_url
+            .iter()
+            .cloned()
+            .chain([normalize_source_url(&request.url)])
+            .collect();
+
+        let txn = db.begin().await?;
+        let imported = match find_by_source_urls(&txn, &utx, &urls).await? {
+            Some(recipe) => Imported {
+                recipe,
+                existing: true,
+            },
+            None => Imported {
+                recipe: insert_recipe(&txn, data, utx).await?,
+                existing: false,
+            },
+        };
+        txn.commit().await?;
+
+        Ok(imported)
     }
 
-    pub async fn list(db: &DatabaseConnection) -> Result<Vec<recipe::Model>, super::Error> {
-        let recipes: Vec<recipe::Model> = Recipe::find().all(db).await?;
+    pub async fn list(
+        db: &DatabaseConnection,
+        utx: UserCtx,
+        options: ListOptions,
+    ) -> Result<Page<recipe::Model>, super::Error> {
+        let select = Recipe::find().filter(recipe::Column::Cid.eq(utx.user_id));
 
-        Ok(recipes)
+        paginate_recipes(db, select, options).await
     }
 
     pub async fn update(
@@ -59,38 +464,30 @@ impl RecipeMac {
         utx: UserCtx,
         id: i64,
     ) -> Result<recipe::Model, super::Error> {
-        let recipe: Option<recipe::Model> = Recipe::find_by_id(id).one(db).await?;
+        let old: recipe::Model = fetch_owned(db, &utx, id).await?;
 
-        let mut recipe: recipe::ActiveModel = recipe.unwrap().into();
+        let mut recipe: recipe::ActiveModel = old.clone().into();
 
-        recipe.title = Set(data.title.unwrap_or_else(|| recipe.title.unwrap()));
+        recipe.title = Set(data.title.unwrap_or(old.title));
         recipe.mid = Set(Some(utx.user_id));
-        recipe.header = Set(Some(
-            data.header
-                .unwrap_or_else(|| recipe.header.unwrap().unwrap()),
-        ));
+        recipe.mtime = Set(Some(DateTimeUtc::from(SystemTime::now()).into()));
+        recipe.header = Set(Some(data.header.or(old.header).unwrap_or_default()));
         recipe.ingredients = Set(Some(
-            data.ingredients
-                .unwrap_or_else(|| recipe.ingredients.unwrap().unwrap()),
-        ));
-        recipe.steps = Set(Some(
-            data.steps.unwrap_or_else(|| recipe.steps.unwrap().unwrap()),
-        ));
-        recipe.tags = Set(Some(
-            data.tags.unwrap_or_else(|| recipe.tags.unwrap().unwrap()),
-        ));
-        recipe.cook_time = Set(Some(
-            data.cook_time
-                .unwrap_or_else(|| recipe.cook_time.unwrap().unwrap()),
-        ));
-        recipe.prep_time = Set(Some(
-            data.prep_time
-                .unwrap_or_else(|| recipe.prep_time.unwrap().unwrap()),
-        ));
-        recipe.total_time = Set(Some(
-            data.total_time
-                .unwrap_or_else(|| recipe.total_time.unwrap().unwrap()),
+            data.ingredients.or(old.ingredients).unwrap_or_default(),
         ));
+        recipe.steps = Set(Some(data.steps.or(old.steps).unwrap_or_default()));
+        recipe.tags = Set(Some(data.tags.or(old.tags).unwrap_or_default()));
+        set_times(
+            &mut recipe,
+            data.prep_time.or(old.prep_time).unwrap_or_default(),
+            data.cook_time.or(old.cook_time).unwrap_or_default(),
+            data.total_time.or(old.total_time).unwrap_or_default(),
+        )?;
+        recipe.servings = Set(data.servings.or(old.servings));
+        recipe.source_url = Set(data.source_url.or(old.source_url));
+        recipe.source_name = Set(data.source_name.or(old.source_name));
+        recipe.original_author = Set(data.original_author.or(old.original_author));
+        recipe.equipment = Set(data.equipment.or(old.equipment));
 
         let recipe: recipe::Model = recipe.update(db).await?;
 
@@ -99,11 +496,10 @@ impl RecipeMac {
 
     pub async fn delete(
         db: &DatabaseConnection,
-        _utx: UserCtx,
+        utx: UserCtx,
         id: i64,
     ) -> Result<i64, super::Error> {
-        let recipe: Option<recipe::Model> = Recipe::find_by_id(id).one(db).await?;
-        let recipe: recipe::Model = recipe.unwrap();
+        let recipe: recipe::Model = fetch_owned(db, &utx, id).await?;
 
         let id = recipe.id;
         recipe.delete(db).await?;
@@ -111,42 +507,208 @@ impl RecipeMac {
         Ok(id)
     }
 
-    pub async fn get(db: &DatabaseConnection, id: i64) -> Result<recipe::Model, super::Error> {
-        let recipe: Option<recipe::Model> = Recipe::find_by_id(id).one(db).await?;
-        let recipe: recipe::Model = recipe.unwrap();
+    pub async fn get(
+        db: &DatabaseConnection,
+        utx: UserCtx,
+        id: i64,
+    ) -> Result<recipe::Model, super::Error> {
+        let recipe: recipe::Model = fetch_owned(db, &utx, id).await?;
 
         Ok(recipe)
     }
 
+    pub async fn scaled(
+        db: &DatabaseConnection,
+        utx: UserCtx,
+        id: i64,
+        options: ScaleOptions,
+    ) -> Result<ScaledRecipe, super::Error> {
+        let recipe: recipe::Model = fetch_owned(db, &utx, id).await?;
+        let factor = scale_factor(&recipe, &options)?;
+
+        let mut view = RecipeView::scaled(recipe, factor);
+        if let Some(system) = options.units {
+            view = view.in_units(system);
+        }
+
+        Ok(ScaledRecipe {
+            recipe: view,
+            factor,
+        })
+    }
+
     pub async fn get_by_tag(
         db: &DatabaseConnection,
         utx: UserCtx,
         tag: &str,
-    ) -> Result<Vec<recipe::Model>, super::Error> {
-        let recipes: Vec<recipe::Model> = Recipe::find()
+        options: ListOptions,
+    ) -> Result<Page<recipe::Model>, super::Error> {
+        let select = Recipe::find()
             .filter(recipe::Column::Cid.eq(utx.user_id))
             .filter(Expr::eq(
                 Expr::val(tag),
                 Expr::expr(PgFunc::any(Expr::col(recipe::Column::Tags))),
-            ))
-            .all(db)
-            .await?;
+            ));
+
+        paginate_recipes(db, select, options).await
+    }
 
-        Ok(recipes)
+    pub async fn search(
+        db: &DatabaseConnection,
+        utx: UserCtx,
+        options: SearchOptions,
+    ) -> Result<Page<RecipeSearchHit>, super::Error> {
+        search_recipes(db, utx.user_id, options).await
     }
 }
 
 // region: Utils
-fn handle_fetch_one_result(
-    result: Result<recipe::Model, sea_orm::DbErr>,
+/// Fetches a recipe and makes sure it belongs to the calling chef
+async fn fetch_owned(
+    db: &DatabaseConnection,
+    utx: &UserCtx,
     id: i64,
 ) -> Result<recipe::Model, super::Error> {
-    result.map_err(|sea_orm_err| match sea_orm_err {
-        sea_orm::DbErr::Query(RuntimeErr::SqlxError(sea_orm_err)) => {
-            super::Error::EntityNotFound(id.to_string())
+    let recipe = Recipe::find_by_id(id).one(db).await;
+    let recipe: recipe::Model = handle_fetch_one_result(recipe, id)?;
+
+    ensure_owner(&recipe, utx)?;
+
+    Ok(recipe)
+}
+
+async fn find_by_source_urls<C: ConnectionTrait>(
+    db: &C,
+    utx: &UserCtx,
+    urls: &[String],
+) -> Result<Option<recipe::Model>, super::Error> {
+    let recipe = Recipe::find()
+        .filter(recipe::Column::Cid.eq(utx.user_id.as_str()))
+        .filter(recipe::Column::SourceUrl.is_in(urls.iter().map(String::as_str)))
+        .order_by_asc(recipe::Column::Id)
+        .one(db)
+        .await?;
+
+    Ok(recipe)
+}
+
+fn ensure_owner(recipe: &recipe::Model, utx: &UserCtx) -> Result<(), super::Error> {
+    if recipe.cid != utx.user_id {
+        return Err(super::Error::AccessDenied(format!("recipe {}", recipe.id)));
+    }
+
+    Ok(())
+}
+
+/// Factor to go from the recipe's servings to the requested ones, or the requested factor
+fn scale_factor(recipe: &recipe::Model, options: &ScaleOptions) -> Result<f64, super::Error> {
+    let factor = match (options.servings, options.factor) {
+        (Some(servings), None) => match recipe.servings {
+            Some(base) if base > 0 => servings as f64 / base as f64,
+            _ => {
+                return Err(super::Error::InvalidScale(format!(
+                    "recipe {} has no servings to scale from, use factor",
+                    recipe.id
+                )))
+            }
+        },
+        (None, Some(factor)) => factor,
+        _ => {
+            return Err(super::Error::InvalidScale(
+                "expected one of servings or factor".to_string(),
+            ))
+        }
+    };
+
+    if !factor.is_finite() || factor <= 0.0 || factor > MAX_SCALE_FACTOR {
+        return Err(super::Error::InvalidScale(format!("factor {}", factor)));
+    }
+
+    Ok(factor)
+}
+
+/// Inserts a new recipe owned by the caller, on a connection or inside a transaction
+async fn insert_recipe<C: ConnectionTrait>(
+    db: &C,
+    data: RecipePatch,
+    utx: UserCtx,
+) -> Result<recipe::Model, Error> {
+    let title = data
+        .title
+        .filter(|title| !title.trim().is_empty())
+        .ok_or_else(|| Error::InvalidRecipe("a recipe needs a title".to_string()))?;
+
+    let recipe = recipe::ActiveModel {
+        cid: Set(utx.user_id),
+        title: Set(title),
+        header: Set(Some(data.header.unwrap_or_default())),
+        steps: Set(Some(data.steps.unwrap_or_default())),
+        ingredients: Set(Some(data.ingredients.unwrap_or_default())),
+        tags: Set(Some(data.tags.unwrap_or_default())),
+        image_url: Set(Some(data.image_url.unwrap_or_default())),
+        servings: Set(data.servings),
+        source_url: Set(data.source_url),
+        source_name: Set(data.source_name),
+        original_author: Set(data.original_author),
+        equipment: Set(Some(data.equipment.unwrap_or_default())),
+        ..Default::default()
+    };
+    let mut recipe = recipe;
+    set_times(
+        &mut recipe,
+        data.prep_time.unwrap_or_default(),
+        data.cook_time.unwrap_or_default(),
+        data.total_time.unwrap_or_default(),
+    )?;
+
+    let recipe: recipe::Model = recipe.insert(db).await?;
+
+    Ok(recipe)
+}
+
+/// Sets the time texts and the minutes parsed from them,
+/// total minutes fall back to prep + cook when the total is missing
+fn set_times(
+    recipe: &mut recipe::ActiveModel,
+    prep_time: String,
+    cook_time: String,
+    total_time: String,
+) -> Result<(), super::Error> {
+    for (name, time) in [
+        ("prep", &prep_time),
+        ("cook", &cook_time),
+        ("total", &total_time),
+    ] {
+        if time.trim_start().starts_with('-') {
+            return Err(super::Error::InvalidRecipe(format!(
+                "{} time cannot be negative",
+                name
+            )));
         }
-        other => super::Error::SeaOrmErr(other),
-    })
+    }
+
+    let prep_minutes = parse_minutes(&prep_time);
+    let cook_minutes = parse_minutes(&cook_time);
+    let total_minutes = parse_minutes(&total_time).or(match (prep_minutes, cook_minutes) {
+        (None, None) => None,
+        (prep, cook) => Some(prep.unwrap_or(0).saturating_add(cook.unwrap_or(0))),
+    });
+
+    recipe.prep_time = Set(Some(prep_time));
+    recipe.cook_time = Set(Some(cook_time));
+    recipe.total_time = Set(Some(total_time));
+    recipe.prep_minutes = Set(prep_minutes);
+    recipe.cook_minutes = Set(cook_minutes);
+    recipe.total_minutes = Set(total_minutes);
+
+    Ok(())
+}
+
+fn handle_fetch_one_result(
+    result: Result<Option<recipe::Model>, sea_orm::DbErr>,
+    id: i64,
+) -> Result<recipe::Model, super::Error> {
+    result?.ok_or_else(|| super::Error::EntityNotFound("recipe", id.to_string()))
 }
 
 // endregion: Utils
@@ -0,0 +1,123 @@
+use std::{env, time::Duration, time::SystemTime};
+
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{prelude::*, DatabaseConnection, Set};
+
+use crate::entities::{prelude::ScrapeCache as ScrapeCacheEntity, scrape_cache};
+use crate::utils::{
+    fetch_html_if_modified, normalize_source_url, scrape_recipe_html, FetchPolicy, Fetched,
+    ScrapeError, ScrapedRecipe, UnitSystem, Validators,
+};
+
+/// Seconds a scraped page is served from the cache before it gets revalidated
+const ENV_SCRAPE_CACHE_TTL: &str = "SCRAPE_CACHE_TTL";
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Scrape results kept by normalized url. Fresh entries skip the fetch entirely,
+/// stale ones are revalidated with a conditional request.
+#[derive(Clone, Debug)]
+pub struct ScrapeCache {
+    pub policy: FetchPolicy,
+    pub ttl: Duration,
+}
+
+impl ScrapeCache {
+    /// `FetchPolicy::from_env`, with the ttl from `SCRAPE_CACHE_TTL` when set
+    pub fn from_env() -> Self {
+        let ttl = env::var(ENV_SCRAPE_CACHE_TTL)
+            .ok()
+            .and_then(|secs| secs.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TTL);
+
+        ScrapeCache {
+            policy: FetchPolicy::from_env(),
+            ttl,
+        }
+    }
+
+    /// Recipe preview of the page at `url`, from the cache when it is still good
+    pub async fn scrape(
+        &self,
+        db: &DatabaseConnection,
+        url: &str,
+        units: Option<UnitSystem>,
+    ) -> Result<ScrapedRecipe, super::Error> {
+        let key = normalize_source_url(url);
+        let now: DateTimeWithTimeZone = DateTimeUtc::from(SystemTime::now()).into();
+
+        // entries written by an older ScrapedRecipe are refetched rather than failed on
+        let cached = ScrapeCacheEntity::find_by_id(key.clone())
+            .one(db)
+            .await?
+            .and_then(|entry| {
+                let recipe = serde_json::from_value::<ScrapedRecipe>(entry.result.clone()).ok()?;
+                Some((entry, recipe))
+            });
+
+        if let Some((entry, recipe)) = &cached {
+            let age = now.signed_duration_since(entry.fetched_at).to_std();
+            if age.is_ok_and(|age| age < self.ttl) {
+                return Ok(recipe.clone().with_units(units));
+            }
+        }
+
+        let validators = cached.as_ref().map(|(entry, _)| Validators {
+            etag: entry.etag.clone(),
+            last_modified: entry.last_modified.clone(),
+        });
+        let fetched = fetch_html_if_modified(url, &self.policy, validators.as_ref()).await?;
+
+        let (recipe, validators) = match (fetched, cached) {
+            (Fetched::NotModified, Some((entry, recipe))) => {
+                let validators = Validators {
+                    etag: entry.etag,
+                    last_modified: entry.last_modified,
+                };
+                (recipe, validators)
+            }
+            (
+                Fetched::Page {
+                    url: page_url,
+                    body,
+                    validators,
+                },
+                _,
+            ) => (
+                scrape_recipe_html(page_url.as_str(), &body, None)?,
+                validators,
+            ),
+            // only asked for with a cached copy, a server answering 304 anyway is broken
+            (Fetched::NotModified, None) => {
+                return Err(ScrapeError::FetchFailed(url.to_string(), "HTTP 304".into()).into())
+            }
+        };
+
+        let entry = scrape_cache::ActiveModel {
+            url: Set(key),
+            result: Set(serde_json::to_value(&recipe).unwrap_or_default()),
+            etag: Set(validators.etag),
+            last_modified: Set(validators.last_modified),
+            fetched_at: Set(now),
+        };
+        ScrapeCacheEntity::insert(entry)
+            .on_conflict(
+                OnConflict::column(scrape_cache::Column::Url)
+                    .update_columns([
+                        scrape_cache::Column::Result,
+                        scrape_cache::Column::Etag,
+                        scrape_cache::Column::LastModified,
+                        scrape_cache::Column::FetchedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(db)
+            .await?;
+
+        Ok(recipe.with_units(units))
+    }
+}
+
+#[cfg(test)]
+#[path = "../_tests/model_scrape_cache.rs"]
+mod tests;
@@ -0,0 +1,192 @@
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, FromQueryResult, Statement, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::recipe;
+
+use super::page::{next_cursor, page_bounds};
+use super::{Page, RecipeView};
+
+const TS_CONFIG: &str = "english";
+const HIGHLIGHT_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>";
+/// Recipe text is scraped or imported, it is escaped before `ts_headline` adds the `<mark>` tags
+const HTML_ESCAPES: [(&str, &str); 5] = [
+    ("&", "&amp;"),
+    ("<", "&lt;"),
+    (">", "&gt;"),
+    ("\"", "&quot;"),
+    // a single quote, doubled inside the SQL string literal
+    ("''", "&#39;"),
+];
+
+/// Query of `GET /api/recipes/search`
+/// `q` takes plain words, `"quoted phrases"` and `prefix*` terms, all of which must match
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct SearchOptions {
+    pub q: String,
+    pub limit: Option<u64>,
+    #[serde(alias = "cursor")]
+    pub offset: Option<u64>,
+}
+
+/// A matching recipe with its rank and `<mark>` highlighted title and snippet, both HTML escaped
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecipeSearchHit {
+    pub recipe: RecipeView,
+    pub rank: f32,
+    pub title: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum SearchTerm {
+    Word(String),
+    Phrase(String),
+    Prefix(String),
+}
+
+/// Splits a user query into words, `"phrases"` and `prefix*` terms
+pub(super) fn parse_search_query(q: &str) -> Vec<SearchTerm> {
+    let mut terms = Vec::new();
+
+    for (i, part) in q.split('"').enumerate() {
+        // odd parts sit between quotes
+        if i % 2 == 1 {
+            let phrase = part.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                terms.push(SearchTerm::Phrase(phrase));
+            }
+            continue;
+        }
+
+        for word in part.split_whitespace() {
+            match word.strip_suffix('*') {
+                Some(prefix) => {
+                    // goes through to_tsquery, so only keep plain word characters
+                    let prefix: String = prefix.chars().filter(|c| c.is_alphanumeric()).collect();
+                    if !prefix.is_empty() {
+                        terms.push(SearchTerm::Prefix(prefix));
+                    }
+                }
+                None => terms.push(SearchTerm::Word(word.to_string())),
+            }
+        }
+    }
+
+    terms
+}
+
+/// Builds a tsquery expression for `terms`, binding each one as the next `$n` in `values`
+fn tsquery_sql(terms: &[SearchTerm], values: &mut Vec<Value>) -> String {
+    terms
+        .iter()
+        .map(|term| {
+            let (sql, value) = match term {
+                SearchTerm::Word(word) => ("plainto_tsquery", word.clone()),
+                SearchTerm::Phrase(phrase) => ("phraseto_tsquery", phrase.clone()),
+                SearchTerm::Prefix(prefix) => ("to_tsquery", format!("{}:*", prefix)),
+            };
+            values.push(value.into());
+            format!("{}('{}', ${})", sql, TS_CONFIG, values.len())
+        })
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
+/// Wraps the SQL expression `text` in `replace` calls that HTML escape it
+fn html_escape_sql(text: &str) -> String {
+    HTML_ESCAPES
+        .iter()
+        .fold(text.to_string(), |sql, (from, to)| {
+            format!("replace({}, '{}', '{}')", sql, from, to)
+        })
+}
+
+/// Ranked full text search over the title, header, ingredients and steps of the chef's recipes
+pub(super) async fn search_recipes(
+    db: &DatabaseConnection,
+    cid: String,
+    options: SearchOptions,
+) -> Result<Page<RecipeSearchHit>, super::Error> {
+    let terms = parse_search_query(&options.q);
+    if terms.is_empty() {
+        return Err(super::Error::InvalidListOption(
+            "empty search query".to_string(),
+        ));
+    }
+
+    let (limit, offset) = page_bounds(options.limit, options.offset);
+
+    let mut values: Vec<Value> = vec![cid.into()];
+    let tsquery = tsquery_sql(&terms, &mut values);
+
+    let count_sql = format!(
+        "SELECT count(*) AS total FROM recipe WHERE cid = $1 AND search @@ ({})",
+        tsquery
+    );
+    let total: i64 = match db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &count_sql,
+            values.clone(),
+        ))
+        .await?
+    {
+        Some(row) => row.try_get("", "total")?,
+        None => 0,
+    };
+
+    values.push((limit as i64).into());
+    values.push((offset as i64).into());
+
+    let sql = format!(
+        r#"SELECT recipe.*,
+    ts_rank_cd(recipe.search, q.query) AS rank,
+    ts_headline('{config}', {title}, q.query, '{highlight}') AS title_highlight,
+    ts_headline('{config}', {body}, q.query, '{highlight}, MaxFragments=2') AS snippet
+FROM recipe, (SELECT {tsquery} AS query) AS q
+WHERE recipe.cid = $1 AND recipe.search @@ q.query
+ORDER BY rank DESC, recipe.id
+LIMIT ${limit_n} OFFSET ${offset_n}"#,
+        config = TS_CONFIG,
+        highlight = HIGHLIGHT_OPTIONS,
+        title = html_escape_sql("recipe.title"),
+        body = html_escape_sql(
+            "concat_ws(' ', recipe.header, immutable_array_to_string(recipe.ingredients), immutable_array_to_string(recipe.steps))"
+        ),
+        tsquery = tsquery,
+        limit_n = values.len() - 1,
+        offset_n = values.len(),
+    );
+
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &sql,
+            values,
+        ))
+        .await?;
+
+    let items = rows
+        .iter()
+        .map(|row| {
+            Ok(RecipeSearchHit {
+                recipe: recipe::Model::from_query_result(row, "")?.into(),
+                rank: row.try_get("", "rank")?,
+                title: row.try_get("", "title_highlight")?,
+                snippet: row.try_get("", "snippet")?,
+            })
+        })
+        .collect::<Result<Vec<_>, sea_orm::DbErr>>()?;
+
+    let total = total as u64;
+
+    Ok(Page {
+        items,
+        total,
+        next_cursor: next_cursor(limit, offset, total),
+    })
+}
+
+#[cfg(test)]
+#[path = "../_tests/model_search.rs"]
+mod tests;
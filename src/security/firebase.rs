@@ -0,0 +1,162 @@
+use std::{
+    env,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use jsonwebtoken::{
+    decode, decode_header, get_current_timestamp, jwk::JwkSet, Algorithm, DecodingKey, Validation,
+};
+use serde::Deserialize;
+use tokio::sync::{OnceCell, RwLock};
+
+use super::Error;
+
+const ENV_PROJECT_ID: &str = "FIREBASE_PROJECT_ID";
+const ENV_JWKS_PATH: &str = "FIREBASE_JWKS_PATH";
+const DEFAULT_JWKS_PATH: &str = "firebase-jwks.json";
+const ISSUER_PREFIX: &str = "https://securetoken.google.com/";
+
+/// Clock skew tolerated on `exp`, `iat` and `auth_time`, in seconds
+const LEEWAY_SECS: u64 = 60;
+
+/// Least time between two JWKS reloads, so unknown `kid`s can't make every request hit the file
+const MIN_RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+static KEY_STORE: OnceCell<KeyStore> = OnceCell::const_new();
+
+/// Claims of a Firebase ID token that the api relies on
+#[derive(Debug, Deserialize)]
+pub struct FirebaseClaims {
+    pub sub: String,
+    pub aud: String,
+    pub iss: String,
+    pub exp: u64,
+    pub iat: u64,
+    pub auth_time: Option<u64>,
+    pub email: Option<String>,
+}
+
+/// Firebase signing keys, loaded from a local JWKS file.
+/// The file is expected to be kept fresh by whatever fetches Google's published keys,
+/// so an unknown `kid` triggers a reload before the token is rejected,
+/// at most once per `MIN_RELOAD_INTERVAL`.
+struct KeyStore {
+    project_id: String,
+    jwks_path: PathBuf,
+    keys: RwLock<JwkSet>,
+    last_reload: Mutex<Instant>,
+}
+
+impl KeyStore {
+    async fn from_env() -> Result<KeyStore, Error> {
+        let project_id =
+            env::var(ENV_PROJECT_ID).map_err(|_| Error::FailMissingEnv(ENV_PROJECT_ID))?;
+        let jwks_path: PathBuf = env::var(ENV_JWKS_PATH)
+            .unwrap_or_else(|_| DEFAULT_JWKS_PATH.to_string())
+            .into();
+
+        let keys = load_jwks(&jwks_path).await?;
+
+        Ok(KeyStore {
+            project_id,
+            jwks_path,
+            keys: RwLock::new(keys),
+            last_reload: Mutex::new(Instant::now()),
+        })
+    }
+
+    async fn decoding_key(&self, kid: &str) -> Result<DecodingKey, Error> {
+        if let Some(jwk) = self.keys.read().await.find(kid) {
+            return DecodingKey::from_jwk(jwk).map_err(|ex| Error::FailLoadKeySet(ex.to_string()));
+        }
+
+        let unknown = || Error::InvalidToken(format!("Unknown key id '{}'", kid));
+
+        // Keys rotate, so give the file one chance to have picked up the new one
+        if !self.claim_reload() {
+            return Err(unknown());
+        }
+        let mut keys = self.keys.write().await;
+        *keys = load_jwks(&self.jwks_path).await?;
+
+        match keys.find(kid) {
+            Some(jwk) => {
+                DecodingKey::from_jwk(jwk).map_err(|ex| Error::FailLoadKeySet(ex.to_string()))
+            }
+            None => Err(unknown()),
+        }
+    }
+
+    /// true when the last reload is old enough for another one, which is then counted as done
+    fn claim_reload(&self) -> bool {
+        let mut last_reload = self.last_reload.lock().unwrap();
+        if last_reload.elapsed() < MIN_RELOAD_INTERVAL {
+            return false;
+        }
+        *last_reload = Instant::now();
+        true
+    }
+
+    fn validation(&self) -> Validation {
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.leeway = LEEWAY_SECS;
+        validation.set_audience(&[&self.project_id]);
+        validation.set_issuer(&[format!("{}{}", ISSUER_PREFIX, self.project_id)]);
+        validation.set_required_spec_claims(&["exp", "sub", "aud", "iss"]);
+
+        validation
+    }
+}
+
+/// Verifies a Firebase ID token (RS256 signature, `kid`, issuer, audience and expiry)
+/// and returns its claims.
+pub async fn verify_id_token(token: &str) -> Result<FirebaseClaims, Error> {
+    let header = decode_header(token).map_err(|ex| Error::InvalidToken(ex.to_string()))?;
+
+    if header.alg != Algorithm::RS256 {
+        return Err(Error::InvalidToken(format!(
+            "Unexpected algorithm {:?}",
+            header.alg
+        )));
+    }
+
+    let kid = header
+        .kid
+        .ok_or_else(|| Error::InvalidToken("Missing key id".to_string()))?;
+
+    let store = KEY_STORE.get_or_try_init(KeyStore::from_env).await?;
+    let key = store.decoding_key(&kid).await?;
+
+    let claims = decode::<FirebaseClaims>(token, &key, &store.validation())
+        .map_err(|ex| Error::InvalidToken(ex.to_string()))?
+        .claims;
+
+    // jsonwebtoken does not check these, Firebase requires both to be in the past
+    let now = get_current_timestamp();
+    if claims.iat > now + LEEWAY_SECS {
+        return Err(Error::InvalidToken("Token issued in the future".to_string()));
+    }
+    if claims.auth_time.is_some_and(|t| t > now + LEEWAY_SECS) {
+        return Err(Error::InvalidToken("Authenticated in the future".to_string()));
+    }
+    if claims.sub.is_empty() {
+        return Err(Error::InvalidToken("Empty subject".to_string()));
+    }
+
+    Ok(claims)
+}
+
+async fn load_jwks(path: &PathBuf) -> Result<JwkSet, Error> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|ex| Error::FailLoadKeySet(format!("{} (cause: {})", path.display(), ex)))?;
+
+    serde_json::from_str(&content)
+        .map_err(|ex| Error::FailLoadKeySet(format!("{} (cause: {})", path.display(), ex)))
+}
+
+#[cfg(test)]
+#[path = "../_tests/security_firebase.rs"]
+mod tests;
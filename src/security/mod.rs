@@ -2,19 +2,30 @@ use thiserror::Error as ThisError;
 
 use sea_orm::DatabaseConnection;
 
+mod firebase;
+
+#[derive(Clone, Debug)]
 pub struct UserCtx {
     pub user_id: String,
 }
 
+/// Verifies the Firebase ID token sent in `X-Auth-Token` and maps its `sub` (the firebase id) to the user
 pub async fn utx_from_token(_db: &DatabaseConnection, token: &str) -> Result<UserCtx, Error> {
-    match token.parse::<String>() {
-        Ok(user_id) => Ok(UserCtx { user_id }),
-        Err(_) => Err(Error::InvalidToken(token.to_string()))
-    }
+    let claims = firebase::verify_id_token(token).await?;
+
+    Ok(UserCtx {
+        user_id: claims.sub,
+    })
 }
 
 #[derive(ThisError, Debug)]
 pub enum Error {
     #[error("Invalid Token {0}")]
-    InvalidToken(String)
-}
\ No newline at end of file
+    InvalidToken(String),
+
+    #[error("Fail loading token signing keys - {0}")]
+    FailLoadKeySet(String),
+
+    #[error("Missing environment variable {0}")]
+    FailMissingEnv(&'static str),
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::{
+    image_data_url, is_zip, names, require_title, servings, text, time_text, web_url, zip_files,
+    ArchiveEntry, ArchiveError, ArchivedRecipe, UnpackBudget,
+};
+
+/// Recipes of Mealie json, one recipe, an array or `{"items": [...]}` page of them,
+/// or a zip with a json file (and `images/original.*` photo) per recipe
+pub(super) fn read(
+    bytes: &[u8],
+    budget: &mut UnpackBudget,
+) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    if !is_zip(bytes) {
+        let json: Value = serde_json::from_slice(bytes)
+            .map_err(|ex| ArchiveError::InvalidArchive(format!("Invalid json - {}", ex)))?;
+        return Ok(entries(&json, "", None));
+    }
+
+    let files = zip_files(bytes, budget, |name| {
+        name.ends_with(".json") || photo_file(name).is_some()
+    })?;
+    let photos: HashMap<&str, String> = files
+        .iter()
+        .filter_map(|(name, content)| {
+            let (folder, file) = photo_file(name)?;
+            Some((folder, image_data_url(file, content)?))
+        })
+        .collect();
+
+    let mut entries = Vec::new();
+    for (name, content) in files.iter().filter(|(name, _)| name.ends_with(".json")) {
+        let folder = name.rsplit_once('/').map_or("", |(folder, _)| folder);
+        match serde_json::from_slice::<Value>(content) {
+            Ok(json) => entries.extend(self::entries(&json, name, photos.get(folder))),
+            Err(ex) => entries.push(ArchiveEntry {
+                name: name.clone(),
+                recipe: Err(format!("Invalid recipe json - {}", ex)),
+            }),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// (recipe folder, file) of a recipe photo,
+/// "recipes/cornbread/images/original.webp" -> ("recipes/cornbread", "original.webp")
+fn photo_file(name: &str) -> Option<(&str, &str)> {
+    name.rsplit_once("/images/")
+        .filter(|(_, file)| file.starts_with("original."))
+}
+
+/// Entries of one json document, `photo` is the embedded photo of its folder
+fn entries(json: &Value, file: &str, photo: Option<&String>) -> Vec<ArchiveEntry> {
+    let recipes: Vec<&Value> = match json {
+        Value::Array(recipes) => recipes.iter().collect(),
+        Value::Object(object) => match object.get("items").or_else(|| object.get("recipes")) {
+            Some(Value::Array(recipes)) => recipes.iter().collect(),
+            _ => vec![json],
+        },
+        _ => Vec::new(),
+    };
+    let single = recipes.len() == 1;
+
+    recipes
+        .into_iter()
+        .enumerate()
+        .map(|(index, json)| {
+            let name = match (file, single) {
+                ("", _) => text(&json["slug"]).unwrap_or_else(|| format!("#{}", index + 1)),
+                (file, true) => file.to_string(),
+                (file, false) => format!("{}#{}", file, index + 1),
+            };
+            let recipe = match json {
+                Value::Object(_) => require_title(recipe(json, photo.filter(|_| single))),
+                _ => Err("Not a recipe object".to_string()),
+            };
+            ArchiveEntry { name, recipe }
+        })
+        .collect()
+}
+
+fn recipe(json: &Value, photo: Option<&String>) -> ArchivedRecipe {
+    let mut tags = names(&json["tags"]);
+    for category in names(&json["recipeCategory"]) {
+        if !tags.contains(&category) {
+            tags.push(category);
+        }
+    }
+
+    ArchivedRecipe {
+        title: text(&json["name"]).unwrap_or_default(),
+        header: text(&json["description"]),
+        steps: items(&json["recipeInstructions"], instruction),
+        ingredients: items(&json["recipeIngredient"], ingredient),
+        tags,
+        image_url: web_url(&json["image"]).or_else(|| photo.cloned()),
+        prep_time: time_text(&json["prepTime"]),
+        cook_time: time_text(&json["performTime"]).or_else(|| time_text(&json["cookTime"])),
+        total_time: time_text(&json["totalTime"]),
+        servings: servings(&json["recipeServings"]).or_else(|| servings(&json["recipeYield"])),
+        source_url: web_url(&json["orgURL"]).or_else(|| web_url(&json["org_url"])),
+        source_name: None,
+        original_author: None,
+    }
+}
+
+fn items(value: &Value, read: fn(&Value) -> Option<String>) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(read).collect())
+        .unwrap_or_default()
+}
+
+/// Plain string, or `{"text": ...}` for newer exports
+fn instruction(value: &Value) -> Option<String> {
+    text(value).or_else(|| text(&value["text"]))
+}
+
+/// Plain string for older exports, else the original text, the display text,
+/// or "quantity unit food note" put back together
+fn ingredient(value: &Value) -> Option<String> {
+    if let Some(line) = text(value)
+        .or_else(|| text(&value["originalText"]))
+        .or_else(|| text(&value["display"]))
+    {
+        return Some(line);
+    }
+
+    let quantity = value["quantity"]
+        .as_f64()
+        .filter(|quantity| *quantity > 0.0);
+    let line = [
+        quantity.map(|quantity| quantity.to_string()),
+        text(&value["unit"]["name"]),
+        text(&value["food"]["name"]),
+        text(&value["note"]),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    (!line.is_empty()).then_some(line)
+}
@@ -0,0 +1,311 @@
+mod mealie;
+mod paprika;
+mod tandoor;
+
+use std::io::{Cursor, Read};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error as ThisError;
+use zip::ZipArchive;
+
+use super::duration::{format_minutes, parse_minutes};
+
+/// Recipes read from one archive
+pub const MAX_ARCHIVE_RECIPES: usize = 5000;
+/// Files in one zip, a nested zip counts its own
+const MAX_ZIP_FILES: usize = 4 * MAX_ARCHIVE_RECIPES;
+/// Uncompressed size of one file in an archive
+const MAX_ENTRY_BYTES: u64 = 16 * 1024 * 1024;
+/// Bytes decompressed from one upload, nested zips and gzipped recipes included
+const MAX_UNPACKED_BYTES: u64 = 128 * 1024 * 1024;
+/// Embedded photos larger than this are dropped rather than stored as data urls
+const MAX_IMAGE_BYTES: usize = 512 * 1024;
+
+#[derive(ThisError, Debug)]
+pub enum ArchiveError {
+    #[error("Invalid archive - {0}")]
+    InvalidArchive(String),
+
+    #[error("Unknown archive format, expected a Paprika, Mealie or Tandoor export")]
+    UnknownFormat,
+
+    #[error("File {0} in the archive is larger than {1} bytes")]
+    TooLarge(String, u64),
+
+    #[error("Archive unpacks to more than {0} bytes")]
+    TooLargeUnpacked(u64),
+
+    #[error("Archive has more than {0} files")]
+    TooManyFiles(usize),
+
+    #[error("Archive has more than {0} recipes")]
+    TooManyRecipes(usize),
+}
+
+/// Recipe managers we import exports from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    /// `.paprikarecipes`, a zip of gzipped json recipes
+    Paprika,
+    /// Recipe json, alone, in an array or zipped per recipe
+    Mealie,
+    /// Zip of per recipe zips holding `recipe.json` and the photo
+    Tandoor,
+}
+
+/// A recipe read from an export, converts into a `RecipePatch`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedRecipe {
+    pub title: String,
+    pub header: Option<String>,
+    pub steps: Vec<String>,
+    pub ingredients: Vec<String>,
+    pub tags: Vec<String>,
+    /// Remote url, or a data url for photos embedded in the archive
+    pub image_url: Option<String>,
+    pub prep_time: Option<String>,
+    pub cook_time: Option<String>,
+    pub total_time: Option<String>,
+    pub servings: Option<i32>,
+    pub source_url: Option<String>,
+    pub source_name: Option<String>,
+    pub original_author: Option<String>,
+}
+
+/// One recipe of an archive, `name` is its file in the archive (or its position)
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub recipe: Result<ArchivedRecipe, String>,
+}
+
+/// Recipes of an export, `format` is guessed from the content when not given.
+/// A recipe that can't be read is an `Err` entry, the others are still imported.
+pub fn read_archive(
+    bytes: &[u8],
+    format: Option<ArchiveFormat>,
+) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let format = match format {
+        Some(format) => format,
+        None => detect_format(bytes)?,
+    };
+
+    let mut budget = UnpackBudget::new();
+    let entries = match format {
+        ArchiveFormat::Paprika => paprika::read(bytes, &mut budget)?,
+        ArchiveFormat::Mealie => mealie::read(bytes, &mut budget)?,
+        ArchiveFormat::Tandoor => tandoor::read(bytes, &mut budget)?,
+    };
+
+    if entries.is_empty() {
+        return Err(ArchiveError::InvalidArchive(
+            "no recipes in the archive".to_string(),
+        ));
+    }
+    if entries.len() > MAX_ARCHIVE_RECIPES {
+        return Err(ArchiveError::TooManyRecipes(MAX_ARCHIVE_RECIPES));
+    }
+
+    Ok(entries)
+}
+
+fn detect_format(bytes: &[u8]) -> Result<ArchiveFormat, ArchiveError> {
+    if is_gzip(bytes) {
+        return Ok(ArchiveFormat::Paprika);
+    }
+    if !is_zip(bytes) {
+        return match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{' | b'[') => Ok(ArchiveFormat::Mealie),
+            _ => Err(ArchiveError::UnknownFormat),
+        };
+    }
+
+    let zip = ZipArchive::new(Cursor::new(bytes)).map_err(invalid)?;
+    let names: Vec<&str> = zip.file_names().collect();
+    if names.iter().any(|name| name.ends_with(".paprikarecipe")) {
+        Ok(ArchiveFormat::Paprika)
+    } else if names
+        .iter()
+        .any(|name| name.ends_with(".zip") || *name == "recipe.json")
+    {
+        Ok(ArchiveFormat::Tandoor)
+    } else if names.iter().any(|name| name.ends_with(".json")) {
+        Ok(ArchiveFormat::Mealie)
+    } else {
+        Err(ArchiveError::UnknownFormat)
+    }
+}
+
+// region:    Archive helpers
+
+pub(super) fn is_zip(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04")
+}
+
+fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&[0x1f, 0x8b])
+}
+
+fn invalid(ex: impl std::fmt::Display) -> ArchiveError {
+    ArchiveError::InvalidArchive(ex.to_string())
+}
+
+/// Bytes still allowed to be decompressed from one upload, shared by every zip
+/// and gzip in it so nested archives can't multiply the limits
+pub(super) struct UnpackBudget {
+    left: u64,
+}
+
+impl UnpackBudget {
+    pub(super) fn new() -> Self {
+        UnpackBudget {
+            left: MAX_UNPACKED_BYTES,
+        }
+    }
+
+    /// Reads at most `MAX_ENTRY_BYTES` and what is left of the budget,
+    /// whatever size the archive claims
+    pub(super) fn read(&mut self, reader: impl Read, name: &str) -> Result<Vec<u8>, ArchiveError> {
+        let limit = MAX_ENTRY_BYTES.min(self.left);
+        let mut content = Vec::new();
+        reader
+            .take(limit + 1)
+            .read_to_end(&mut content)
+            .map_err(|ex| ArchiveError::InvalidArchive(format!("{} - {}", name, ex)))?;
+
+        let size = content.len() as u64;
+        if size > MAX_ENTRY_BYTES {
+            return Err(ArchiveError::TooLarge(name.to_string(), MAX_ENTRY_BYTES));
+        }
+        if size > self.left {
+            return Err(ArchiveError::TooLargeUnpacked(MAX_UNPACKED_BYTES));
+        }
+        self.left -= size;
+
+        Ok(content)
+    }
+}
+
+/// (name, content) of the files of a zip that `keep` accepts by name,
+/// the others are never decompressed
+pub(super) fn zip_files(
+    bytes: &[u8],
+    budget: &mut UnpackBudget,
+    keep: impl Fn(&str) -> bool,
+) -> Result<Vec<(String, Vec<u8>)>, ArchiveError> {
+    let mut zip = ZipArchive::new(Cursor::new(bytes)).map_err(invalid)?;
+    if zip.len() > MAX_ZIP_FILES {
+        return Err(ArchiveError::TooManyFiles(MAX_ZIP_FILES));
+    }
+
+    let mut files = Vec::new();
+    for index in 0..zip.len() {
+        let file = zip.by_index(index).map_err(invalid)?;
+        if file.is_dir() || !keep(file.name()) {
+            continue;
+        }
+        let name = file.name().to_string();
+        files.push((name.clone(), budget.read(file, &name)?));
+    }
+
+    Ok(files)
+}
+
+// endregion: Archive helpers
+
+// region:    Field helpers
+
+/// Trimmed string (or number) value, `None` when blank
+fn text(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(text) => text.trim().to_string(),
+        Value::Number(number) => number.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// Non blank lines of a multi-line field
+fn lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Free text duration in the "1 hr 20 mins" form when it reads as one, else as is
+fn time_text(value: &Value) -> Option<String> {
+    let text = text(value)?;
+    Some(parse_minutes(&text).map_or(text, format_minutes))
+}
+
+/// "1 hr 20 mins" for a count of minutes, `None` for zero
+fn minutes_text(minutes: i64) -> Option<String> {
+    (minutes > 0).then(|| format_minutes(minutes.min(i32::MAX as i64) as i32))
+}
+
+/// First whole number of "4 servings", "Serves 4-6" or 4
+fn servings(value: &Value) -> Option<i32> {
+    if let Some(number) = value.as_f64() {
+        return (number >= 1.0).then_some(number.round() as i32);
+    }
+    let text = value.as_str()?;
+    let digits: String = text
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok().filter(|servings| *servings > 0)
+}
+
+/// Names of `[{"name": ...}]` lists, or of plain string lists
+fn names(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| text(item).or_else(|| text(&item["name"])))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Only http(s) urls, exports also hold local file names in url fields
+fn web_url(value: &Value) -> Option<String> {
+    text(value).filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+}
+
+/// `data:` url for a photo of the archive, `None` when too large or not an image
+fn image_data_url(name: &str, bytes: &[u8]) -> Option<String> {
+    let extension = name.rsplit('.').next()?.to_lowercase();
+    let mime = match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        _ => return None,
+    };
+    if bytes.is_empty() || bytes.len() > MAX_IMAGE_BYTES {
+        return None;
+    }
+
+    Some(format!("data:{};base64,{}", mime, STANDARD.encode(bytes)))
+}
+
+fn require_title(recipe: ArchivedRecipe) -> Result<ArchivedRecipe, String> {
+    if recipe.title.is_empty() {
+        return Err("Recipe has no name".to_string());
+    }
+    Ok(recipe)
+}
+
+// endregion: Field helpers
+
+#[cfg(test)]
+#[path = "../../_tests/utils_archive.rs"]
+mod tests;
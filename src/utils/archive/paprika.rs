@@ -0,0 +1,76 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use flate2::read::GzDecoder;
+use serde_json::Value;
+
+use super::{
+    image_data_url, is_gzip, lines, names, require_title, servings, text, time_text, web_url,
+    zip_files, ArchiveEntry, ArchiveError, ArchivedRecipe, UnpackBudget,
+};
+
+/// Recipes of a `.paprikarecipes` zip, or of a single gzipped `.paprikarecipe`
+pub(super) fn read(
+    bytes: &[u8],
+    budget: &mut UnpackBudget,
+) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    if is_gzip(bytes) {
+        return Ok(vec![entry("recipe.paprikarecipe", bytes, budget)?]);
+    }
+
+    zip_files(bytes, budget, |name| name.ends_with(".paprikarecipe"))?
+        .into_iter()
+        .map(|(name, content)| entry(&name, &content, budget))
+        .collect()
+}
+
+fn entry(
+    name: &str,
+    gzipped: &[u8],
+    budget: &mut UnpackBudget,
+) -> Result<ArchiveEntry, ArchiveError> {
+    let json = budget.read(GzDecoder::new(gzipped), name)?;
+    let recipe = serde_json::from_slice::<Value>(&json)
+        .map_err(|ex| format!("Invalid recipe json - {}", ex))
+        .and_then(|json| require_title(recipe(&json)));
+
+    Ok(ArchiveEntry {
+        name: name.to_string(),
+        recipe,
+    })
+}
+
+fn recipe(json: &Value) -> ArchivedRecipe {
+    let header = [text(&json["description"]), text(&json["notes"])]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    ArchivedRecipe {
+        title: text(&json["name"]).unwrap_or_default(),
+        header: (!header.is_empty()).then_some(header),
+        steps: lines(json["directions"].as_str().unwrap_or_default()),
+        ingredients: lines(json["ingredients"].as_str().unwrap_or_default()),
+        tags: names(&json["categories"]),
+        image_url: web_url(&json["image_url"]).or_else(|| photo(json)),
+        prep_time: time_text(&json["prep_time"]),
+        cook_time: time_text(&json["cook_time"]),
+        total_time: time_text(&json["total_time"]),
+        servings: servings(&json["servings"]),
+        source_url: web_url(&json["source_url"]),
+        source_name: text(&json["source"]),
+        original_author: None,
+    }
+}
+
+/// The photo Paprika embeds base64 encoded, named by `photo`
+fn photo(json: &Value) -> Option<String> {
+    let data: String = json["photo_data"]
+        .as_str()?
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+    let bytes = STANDARD.decode(data).ok()?;
+    let name = json["photo"].as_str().unwrap_or("photo.jpg");
+
+    image_data_url(name, &bytes)
+}
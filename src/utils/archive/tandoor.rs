@@ -0,0 +1,118 @@
+use serde_json::Value;
+
+use super::{
+    image_data_url, minutes_text, names, require_title, servings, text, web_url, zip_files,
+    ArchiveEntry, ArchiveError, ArchivedRecipe, UnpackBudget,
+};
+
+/// Recipes of a Tandoor export, a zip holding one zip per recipe (`recipe.json` and
+/// `image.*`), or a single recipe's zip. Nested zips count against the same `budget`.
+pub(super) fn read(
+    bytes: &[u8],
+    budget: &mut UnpackBudget,
+) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let files = zip_files(bytes, budget, |name| {
+        name.ends_with(".zip") || is_recipe_file(name)
+    })?;
+    if files.iter().any(|(name, _)| name == "recipe.json") {
+        return Ok(vec![entry("recipe.json", files)]);
+    }
+
+    let mut entries = Vec::new();
+    for (name, content) in files.into_iter().filter(|(name, _)| name.ends_with(".zip")) {
+        let entry = match zip_files(&content, budget, is_recipe_file) {
+            Ok(files) => entry(&name, files),
+            // the whole upload is over budget, not just this recipe
+            Err(ex @ ArchiveError::TooLargeUnpacked(_)) => return Err(ex),
+            Err(ex) => ArchiveEntry {
+                name,
+                recipe: Err(ex.to_string()),
+            },
+        };
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Files of one recipe's zip that are read
+fn is_recipe_file(name: &str) -> bool {
+    name == "recipe.json" || name.starts_with("image.")
+}
+
+/// Recipe of the files of one recipe's zip
+fn entry(name: &str, files: Vec<(String, Vec<u8>)>) -> ArchiveEntry {
+    let photo = files
+        .iter()
+        .filter(|(file, _)| file.starts_with("image."))
+        .find_map(|(file, content)| image_data_url(file, content));
+
+    let recipe = match files.iter().find(|(file, _)| file == "recipe.json") {
+        Some((_, json)) => serde_json::from_slice::<Value>(json)
+            .map_err(|ex| format!("Invalid recipe json - {}", ex))
+            .and_then(|json| require_title(recipe(&json, photo))),
+        None => Err("No recipe.json in the recipe zip".to_string()),
+    };
+
+    ArchiveEntry {
+        name: name.to_string(),
+        recipe,
+    }
+}
+
+fn recipe(json: &Value, photo: Option<String>) -> ArchivedRecipe {
+    let steps = json["steps"].as_array().cloned().unwrap_or_default();
+    let working = json["working_time"].as_i64().unwrap_or_default();
+    let waiting = json["waiting_time"].as_i64().unwrap_or_default();
+
+    ArchivedRecipe {
+        title: text(&json["name"]).unwrap_or_default(),
+        header: text(&json["description"]),
+        steps: steps
+            .iter()
+            .filter_map(|step| text(&step["instruction"]))
+            .collect(),
+        ingredients: steps
+            .iter()
+            .flat_map(|step| step["ingredients"].as_array().cloned().unwrap_or_default())
+            .filter_map(|ingredient| ingredient_line(&ingredient))
+            .collect(),
+        tags: names(&json["keywords"]),
+        image_url: photo,
+        prep_time: minutes_text(working),
+        cook_time: minutes_text(waiting),
+        // hostile exports can overflow the sum, that is no time at all
+        total_time: working.checked_add(waiting).and_then(minutes_text),
+        servings: servings(&json["servings"]),
+        source_url: web_url(&json["source_url"]),
+        source_name: None,
+        original_author: None,
+    }
+}
+
+/// The original text when Tandoor kept it, else "amount unit food note".
+/// Section headers keep their text only.
+fn ingredient_line(ingredient: &Value) -> Option<String> {
+    if let Some(original) = text(&ingredient["original_text"]) {
+        return Some(original);
+    }
+    if ingredient["is_header"].as_bool() == Some(true) {
+        return text(&ingredient["note"]).or_else(|| text(&ingredient["food"]["name"]));
+    }
+
+    let amount = ingredient["amount"]
+        .as_f64()
+        .filter(|amount| *amount > 0.0 && ingredient["no_amount"].as_bool() != Some(true));
+    let line = [
+        amount.map(|amount| amount.to_string()),
+        text(&ingredient["unit"]["name"]),
+        text(&ingredient["food"]["name"]),
+        text(&ingredient["note"]),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    (!line.is_empty()).then_some(line)
+}
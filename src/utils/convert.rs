@@ -0,0 +1,78 @@
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+
+use super::ingredient::{Ingredient, Quantity};
+use super::units::{best_unit, round_quantity, unit_def, UnitSystem};
+
+/// Rewrites the ingredient's amount in `system`, volume stays volume and weight stays weight.
+/// Counts, cans and ingredients already in `system` are left alone.
+pub fn convert_ingredient(ingredient: &Ingredient, system: UnitSystem) -> Ingredient {
+    let (Some(quantity), Some(def)) = (
+        ingredient.quantity,
+        ingredient.unit.as_deref().and_then(unit_def),
+    ) else {
+        return ingredient.clone();
+    };
+
+    if def.system == system {
+        return ingredient.clone();
+    }
+
+    let (unit, value) = best_unit(quantity.value, def, system);
+    let ratio = def.base / unit.base;
+
+    let mut converted = Ingredient {
+        quantity: Some(Quantity {
+            value: round_quantity(value, Some(unit.name)),
+            max: quantity
+                .max
+                .map(|max| round_quantity(max * ratio, Some(unit.name))),
+        }),
+        unit: Some(unit.name.to_string()),
+        ..ingredient.clone()
+    };
+    converted.raw = converted.to_string();
+
+    converted
+}
+
+/// Rewrites temperatures in step text, "Bake at 350°F" -> "Bake at 180°C".
+/// Oven temperatures are rounded the way ovens are set, others to the degree.
+pub fn convert_temperatures(text: &str, system: UnitSystem) -> String {
+    temperature_regex()
+        .replace_all(text, |caps: &Captures| {
+            let Ok(degrees) = caps[1].parse::<f64>() else {
+                return caps[0].to_string();
+            };
+            let is_fahrenheit = caps[2].to_lowercase().starts_with('f');
+
+            match (is_fahrenheit, system) {
+                (true, UnitSystem::Metric) => {
+                    let celsius = (degrees - 32.0) * 5.0 / 9.0;
+                    let step = if celsius >= 100.0 { 10.0 } else { 1.0 };
+                    format!("{}°C", (celsius / step).round() * step)
+                }
+                (false, UnitSystem::Us) => {
+                    let fahrenheit = degrees * 9.0 / 5.0 + 32.0;
+                    let step = if fahrenheit >= 200.0 { 25.0 } else { 1.0 };
+                    format!("{}°F", (fahrenheit / step).round() * step)
+                }
+                _ => caps[0].to_string(),
+            }
+        })
+        .to_string()
+}
+
+/// "350°F", "350 ° F", "180 degrees C", "180 degrees Celsius"
+fn temperature_regex() -> &'static Regex {
+    static TEMPERATURE: OnceLock<Regex> = OnceLock::new();
+    TEMPERATURE.get_or_init(|| {
+        Regex::new(r"(?i)(-?\d+(?:\.\d+)?)\s*(?:°|º|degrees?)\s*(fahrenheit|celsius|f|c)\b")
+            .unwrap()
+    })
+}
+
+#[cfg(test)]
+#[path = "../_tests/utils_convert.rs"]
+mod tests;
@@ -0,0 +1,459 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use super::ingredient::{parse_ingredient, Ingredient, Quantity};
+
+/// An `@name{quantity%unit}(preparation)` of a step
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CooklangIngredient {
+    pub name: String,
+    pub quantity: Option<String>,
+    pub unit: Option<String>,
+    pub preparation: Option<String>,
+}
+
+impl CooklangIngredient {
+    /// As an ingredient line, "1 1/2 cups cornmeal, sifted"
+    pub fn line(&self) -> String {
+        let line = [
+            self.quantity.as_deref(),
+            self.unit.as_deref(),
+            Some(&self.name),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+        let line = match &self.preparation {
+            Some(preparation) => format!("{}, {}", line, preparation),
+            None => line,
+        };
+
+        parse_ingredient(&line).to_string()
+    }
+
+    fn markup(&self) -> String {
+        let amount = match (&self.quantity, &self.unit) {
+            (Some(quantity), Some(unit)) => format!("{}%{}", quantity, unit),
+            (Some(quantity), None) => quantity.clone(),
+            _ => String::new(),
+        };
+        let preparation = self
+            .preparation
+            .as_ref()
+            .map(|preparation| format!("({})", preparation))
+            .unwrap_or_default();
+
+        format!("@{}{{{}}}{}", markup_name(&self.name), amount, preparation)
+    }
+}
+
+/// A parsed ingredient line as Cooklang markup, the note joins the preparation
+impl From<&Ingredient> for CooklangIngredient {
+    fn from(ingredient: &Ingredient) -> Self {
+        let preparation = [&ingredient.preparation, &ingredient.note]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        let name = match ingredient.name.is_empty() {
+            true => ingredient.raw.trim().to_string(),
+            false => ingredient.name.clone(),
+        };
+
+        CooklangIngredient {
+            name,
+            quantity: ingredient.quantity.map(quantity_text),
+            unit: ingredient.unit.clone(),
+            preparation: (!preparation.is_empty()).then_some(preparation),
+        }
+    }
+}
+
+/// A `~name{quantity%unit}` of a step
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CooklangTimer {
+    pub name: Option<String>,
+    pub quantity: Option<String>,
+    pub unit: Option<String>,
+}
+
+/// A recipe in Cooklang, see https://cooklang.org/docs/spec/.
+/// `steps` are plain text, `Display` marks the ingredients, cookware and
+/// durations they mention back up.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CooklangRecipe {
+    /// `>> key: value` lines or front matter, keys lowercase, in file order
+    pub metadata: Vec<(String, String)>,
+    /// `> note` lines
+    pub notes: Vec<String>,
+    pub ingredients: Vec<CooklangIngredient>,
+    pub cookware: Vec<String>,
+    pub timers: Vec<CooklangTimer>,
+    pub steps: Vec<String>,
+}
+
+impl CooklangRecipe {
+    pub fn parse(text: &str) -> Self {
+        let text = block_comment_regex().replace_all(text, "");
+        let mut recipe = CooklangRecipe::default();
+        let mut lines = text.lines().peekable();
+
+        // YAML front matter, only its `key: value` lines and `- item` lists
+        if lines.peek().map(|line| line.trim()) == Some("---") {
+            lines.next();
+            for line in lines.by_ref() {
+                let line = line.trim();
+                if line == "---" {
+                    break;
+                }
+                if let (Some(item), Some((_, value))) =
+                    (line.strip_prefix("- "), recipe.metadata.last_mut())
+                {
+                    if !value.is_empty() {
+                        value.push_str(", ");
+                    }
+                    value.push_str(item.trim());
+                } else if let Some((key, value)) = line.split_once(':') {
+                    recipe.push_metadata(key, value);
+                }
+            }
+        }
+
+        let mut paragraph: Vec<&str> = Vec::new();
+        for line in lines {
+            let line = strip_comment(line).trim();
+
+            if let Some((key, value)) = line
+                .strip_prefix(">>")
+                .and_then(|meta| meta.split_once(':'))
+            {
+                recipe.push_metadata(key, value);
+            } else if let Some(note) = line.strip_prefix('>') {
+                recipe.notes.push(note.trim().to_string());
+            } else if !line.is_empty() && !line.starts_with('=') {
+                paragraph.push(line);
+                continue;
+            }
+            // anything but a step line ends the step, sections are dropped
+            recipe.push_step(&paragraph.join(" "));
+            paragraph.clear();
+        }
+        recipe.push_step(&paragraph.join(" "));
+
+        recipe
+    }
+
+    /// First value given for any of `keys`
+    pub fn meta(&self, keys: &[&str]) -> Option<&str> {
+        keys.iter().find_map(|key| {
+            self.metadata
+                .iter()
+                .find(|(name, value)| name == key && !value.is_empty())
+                .map(|(_, value)| value.as_str())
+        })
+    }
+
+    fn push_metadata(&mut self, key: &str, value: &str) {
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        self.metadata
+            .push((key.trim().to_lowercase(), value.to_string()));
+    }
+
+    /// Reads the markup of one step, keeping its plain text
+    fn push_step(&mut self, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let step = markup_regex().replace_all(text, |caps: &Captures| {
+            let kind = &caps["kind"];
+            let preparation = caps.name("preparation").map(|m| m.as_str().trim());
+
+            let Some(name) = caps.name("name").or_else(|| caps.name("word")) else {
+                return caps[0].to_string();
+            };
+            let name = name.as_str().trim().to_string();
+            let (quantity, unit) = amount(caps.name("amount").map_or("", |m| m.as_str()));
+
+            match kind {
+                "@" if !name.is_empty() => {
+                    let ingredient = CooklangIngredient {
+                        name: name.clone(),
+                        quantity,
+                        unit,
+                        preparation: preparation.filter(|p| !p.is_empty()).map(str::to_string),
+                    };
+                    let repeated = ingredient.quantity.is_none()
+                        && self.ingredients.iter().any(|other| other.name == name);
+                    if !repeated {
+                        self.ingredients.push(ingredient);
+                    }
+                    name
+                }
+                "#" if !name.is_empty() => {
+                    if !self
+                        .cookware
+                        .iter()
+                        .any(|other| other.eq_ignore_ascii_case(&name))
+                    {
+                        self.cookware.push(name.clone());
+                    }
+                    with_preparation(name, preparation)
+                }
+                // `~` needs braces, "~5 minutes" is plain text
+                "~" if caps.name("word").is_none() => {
+                    let text = match (&quantity, &unit) {
+                        (Some(quantity), Some(unit)) => format!("{} {}", quantity, unit),
+                        (Some(quantity), None) => quantity.clone(),
+                        _ => name.clone(),
+                    };
+                    self.timers.push(CooklangTimer {
+                        name: (!name.is_empty()).then_some(name),
+                        quantity,
+                        unit,
+                    });
+                    with_preparation(text, preparation)
+                }
+                _ => caps[0].to_string(),
+            }
+        });
+
+        self.steps.push(step.trim().to_string());
+    }
+}
+
+/// Writes the recipe as a `.cook` file. Each ingredient and cookware is marked up
+/// where a step first mentions it, the ones no step mentions open the method.
+/// Text that would read back as a comment is broken up, see `without_comments`.
+impl fmt::Display for CooklangRecipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.metadata {
+            writeln!(
+                f,
+                ">> {}: {}",
+                key,
+                without_comments(&value.replace('\n', " "))
+            )?;
+        }
+        for note in &self.notes {
+            writeln!(f, "\n> {}", without_comments(note))?;
+        }
+
+        let mut steps: Vec<Vec<Segment>> = self
+            .steps
+            .iter()
+            .map(|step| mark_durations(&without_comments(step)))
+            .collect();
+
+        // "black pepper" before "pepper"
+        let mut order: Vec<usize> = (0..self.ingredients.len()).collect();
+        order.sort_by_key(|index| std::cmp::Reverse(self.ingredients[*index].name.len()));
+        let mut placed = vec![false; self.ingredients.len()];
+        for index in order {
+            let ingredient = &self.ingredients[index];
+            placed[index] = place(&mut steps, &ingredient.name, &ingredient.markup());
+        }
+        let unplaced: Vec<String> = self
+            .ingredients
+            .iter()
+            .zip(placed)
+            .filter(|(_, placed)| !placed)
+            .map(|(ingredient, _)| ingredient.markup())
+            .collect();
+        let unused: Vec<String> = self
+            .cookware
+            .iter()
+            .map(|cookware| (cookware, format!("#{}{{}}", markup_name(cookware))))
+            .filter(|(cookware, markup)| !place(&mut steps, cookware, markup))
+            .map(|(_, markup)| markup)
+            .collect();
+
+        if !unplaced.is_empty() {
+            let line = format!("Ingredients: {}.", unplaced.join(", "));
+            writeln!(f, "\n{}", without_comments(&line))?;
+        }
+        if !unused.is_empty() {
+            let line = format!("Cookware: {}.", unused.join(", "));
+            writeln!(f, "\n{}", without_comments(&line))?;
+        }
+
+        for step in steps {
+            let text: String = step.into_iter().map(Segment::into_text).collect();
+            writeln!(f, "\n{}", text)?;
+        }
+
+        Ok(())
+    }
+}
+
+// region:    Markup helpers
+
+/// Part of a step being marked up, markup is never matched again
+enum Segment {
+    Text(String),
+    Markup(String),
+}
+
+impl Segment {
+    fn into_text(self) -> String {
+        match self {
+            Segment::Text(text) | Segment::Markup(text) => text,
+        }
+    }
+}
+
+/// Replaces the first mention of `name` (or of its last word) in the steps with `markup`
+fn place(steps: &mut [Vec<Segment>], name: &str, markup: &str) -> bool {
+    let last_word = name
+        .split_whitespace()
+        .last()
+        .filter(|word| *word != name && word.len() > 2);
+
+    for word in std::iter::once(name).chain(last_word) {
+        let Ok(regex) = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(word))) else {
+            continue;
+        };
+        for step in steps.iter_mut() {
+            for index in 0..step.len() {
+                let Segment::Text(text) = &step[index] else {
+                    continue;
+                };
+                let Some(found) = regex.find(text) else {
+                    continue;
+                };
+
+                let (before, after) = (&text[..found.start()], &text[found.end()..]);
+                let parts = [
+                    Segment::Text(before.to_string()),
+                    Segment::Markup(markup.to_string()),
+                    Segment::Text(after.to_string()),
+                ];
+                step.splice(index..=index, parts);
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// "Bake for 25 minutes" -> "Bake for ~{25%minutes}"
+fn mark_durations(step: &str) -> Vec<Segment> {
+    static DURATION: OnceLock<Regex> = OnceLock::new();
+    let duration = DURATION.get_or_init(|| {
+        Regex::new(r"(?i)\b(\d+(?:[.,]\d+)?)\s*(seconds?|secs?|minutes?|mins?|hours?|hrs?)\b")
+            .unwrap()
+    });
+
+    let mut segments = Vec::new();
+    let mut rest = 0;
+    for caps in duration.captures_iter(step) {
+        let found = caps.get(0).unwrap();
+        segments.push(Segment::Text(step[rest..found.start()].to_string()));
+        segments.push(Segment::Markup(format!("~{{{}%{}}}", &caps[1], &caps[2])));
+        rest = found.end();
+    }
+    segments.push(Segment::Text(step[rest..].to_string()));
+
+    segments
+}
+
+/// Names can't hold markup characters
+fn markup_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '@' | '#' | '~' | '{' | '}'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// 2.5 -> "2.5", ranges as "2-3"
+fn quantity_text(quantity: Quantity) -> String {
+    let number = |value: f64| {
+        let text = format!("{:.2}", value);
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    };
+
+    match quantity.max {
+        Some(max) => format!("{}-{}", number(quantity.value), number(max)),
+        None => number(quantity.value),
+    }
+}
+
+/// "2%cups" -> ("2", "cups"), "=1" -> ("1", none)
+fn amount(text: &str) -> (Option<String>, Option<String>) {
+    let non_empty = |text: &str| {
+        let text = text.trim().trim_start_matches('=').trim();
+        (!text.is_empty()).then(|| text.to_string())
+    };
+
+    match text.split_once('%') {
+        Some((quantity, unit)) => (non_empty(quantity), non_empty(unit)),
+        None => (non_empty(text), None),
+    }
+}
+
+/// Parentheses after cookware or timers are plain text
+fn with_preparation(text: String, preparation: Option<&str>) -> String {
+    match preparation {
+        Some(preparation) => format!("{}({})", text, preparation),
+        None => text,
+    }
+}
+
+/// `@name{amount}`, `@word`, `#name{}`, `#word` and `~name{amount}`,
+/// with an optional `(preparation)`
+fn markup_regex() -> &'static Regex {
+    static MARKUP: OnceLock<Regex> = OnceLock::new();
+    MARKUP.get_or_init(|| {
+        Regex::new(
+            r"(?P<kind>[@#~])(?:(?P<name>[^@#~{}\n]*)\{(?P<amount>[^}]*)\}|(?P<word>[\w-]+))(?:\((?P<preparation>[^)]*)\))?",
+        )
+        .unwrap()
+    })
+}
+
+/// The line before its `--` comment. The marker has to start the line or follow
+/// a space, so urls like `https://example.com/a--b` are kept whole.
+fn strip_comment(line: &str) -> &str {
+    let mut from = 0;
+    while let Some(at) = line[from..].find("--").map(|at| from + at) {
+        if line[..at].is_empty() || line[..at].ends_with(char::is_whitespace) {
+            return &line[..at];
+        }
+        from = at + 2;
+    }
+    line
+}
+
+/// `text` with the `--` and `[-` that would start a comment shortened to `-` and `[ -`,
+/// the rest reads back unchanged
+fn without_comments(text: &str) -> String {
+    let text = text.replace("[-", "[ -");
+
+    comment_marker_regex()
+        .replace_all(&text, "${space}-")
+        .into_owned()
+}
+
+/// `--` (or longer) at the start or after whitespace
+fn comment_marker_regex() -> &'static Regex {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    MARKER.get_or_init(|| Regex::new(r"(?P<space>^|\s)-{2,}").unwrap())
+}
+
+fn block_comment_regex() -> &'static Regex {
+    static BLOCK: OnceLock<Regex> = OnceLock::new();
+    BLOCK.get_or_init(|| Regex::new(r"(?s)\[-.*?-\]").unwrap())
+}
+
+// endregion: Markup helpers
+
+#[cfg(test)]
+#[path = "../_tests/utils_cooklang.rs"]
+mod tests;
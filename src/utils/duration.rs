@@ -0,0 +1,122 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::ingredient::{normalize_fractions, parse_number};
+
+/// Longest duration kept, a year, anything past it is a typo or a hostile page
+const MAX_MINUTES: i32 = 60 * 24 * 365;
+
+/// Minutes in a free text duration, "1 hr 20 mins" -> 80.
+/// Also takes "1h20m", "1 1/2 hours", "1:20", a bare "45" and ISO 8601 "PT1H20M".
+/// Negative durations and durations over `MAX_MINUTES` give `None`.
+pub fn parse_minutes(text: &str) -> Option<i32> {
+    let text = normalize_fractions(text.trim());
+    if text.is_empty() {
+        return None;
+    }
+
+    let iso = iso_regex().captures(&text).filter(|_| text.len() > 2);
+    if let Some(caps) = iso {
+        let part = |i: usize| {
+            caps.get(i)
+                .and_then(|m| m.as_str().parse::<f64>().ok())
+                .unwrap_or(0.0)
+        };
+        let minutes = part(1) * 1440.0 + part(2) * 60.0 + part(3) + part(4) / 60.0;
+        return bounded_minutes(minutes);
+    }
+
+    if let Some(caps) = clock_regex().captures(&text) {
+        let hours: i32 = caps[1].parse().ok()?;
+        let minutes: i32 = caps[2].parse().ok()?;
+        let minutes = hours.checked_mul(60)?.checked_add(minutes)?;
+        return (minutes <= MAX_MINUTES).then_some(minutes);
+    }
+
+    if let Ok(minutes) = text.parse::<f64>() {
+        return bounded_minutes(minutes);
+    }
+
+    let mut total = 0.0;
+    let mut found = false;
+    for caps in part_regex().captures_iter(&text) {
+        let Some(amount) = parse_number(&caps[1]) else {
+            continue;
+        };
+        let unit = caps[2].to_lowercase();
+        total += match unit.chars().next() {
+            Some('d') => amount * 1440.0,
+            Some('h') => amount * 60.0,
+            Some('s') => amount / 60.0,
+            _ => amount,
+        };
+        found = true;
+    }
+
+    if !found {
+        return None;
+    }
+    bounded_minutes(total)
+}
+
+/// Rounds to whole minutes, `None` when negative, past `MAX_MINUTES` or not a number
+fn bounded_minutes(minutes: f64) -> Option<i32> {
+    let minutes = minutes.round();
+    (0.0..=MAX_MINUTES as f64)
+        .contains(&minutes)
+        .then_some(minutes as i32)
+}
+
+/// 80 -> "PT1H20M"
+pub fn iso8601_duration(minutes: i32) -> String {
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+
+    match (hours, minutes) {
+        (0, minutes) => format!("PT{}M", minutes),
+        (hours, 0) => format!("PT{}H", hours),
+        (hours, minutes) => format!("PT{}H{}M", hours, minutes),
+    }
+}
+
+/// 80 -> "1 hr 20 mins"
+pub fn format_minutes(minutes: i32) -> String {
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    let plural = |n: i32, unit: &str| match n {
+        1 => format!("1 {}", unit),
+        n => format!("{} {}s", n, unit),
+    };
+
+    match (hours, minutes) {
+        (0, minutes) => plural(minutes, "min"),
+        (hours, 0) => plural(hours, "hr"),
+        (hours, minutes) => format!("{} {}", plural(hours, "hr"), plural(minutes, "min")),
+    }
+}
+
+fn iso_regex() -> &'static Regex {
+    static ISO: OnceLock<Regex> = OnceLock::new();
+    ISO.get_or_init(|| {
+        Regex::new(r"(?i)^P(?:(\d+)D)?(?:T(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)S)?)?$").unwrap()
+    })
+}
+
+fn clock_regex() -> &'static Regex {
+    static CLOCK: OnceLock<Regex> = OnceLock::new();
+    CLOCK.get_or_init(|| Regex::new(r"^(\d+):([0-5]\d)$").unwrap())
+}
+
+/// "1 1/2 hours", "20 mins", "1h", no word boundary so "1h20m" splits too
+fn part_regex() -> &'static Regex {
+    static PART: OnceLock<Regex> = OnceLock::new();
+    PART.get_or_init(|| {
+        Regex::new(
+            r"(?i)(\d+\s+\d+/\d+|\d+/\d+|\d+(?:\.\d+)?)\s*(days?|d|hours?|hrs?|h|minutes?|mins?|m|seconds?|secs?|s)",
+        )
+        .unwrap()
+    })
+}
+
+#[cfg(test)]
+#[path = "../_tests/utils_duration.rs"]
+mod tests;
@@ -0,0 +1,257 @@
+use std::io::{Cursor, Write};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+const STYLESHEET: &str = "body { font-family: serif; line-height: 1.4; margin: 0 5%; }
+h1 { margin-bottom: 0.2em; }
+h2 { font-size: 1.1em; margin: 1.2em 0 0.4em; }
+img { display: block; max-width: 100%; margin: 0.5em auto; }
+li { margin-bottom: 0.3em; }
+.header { font-style: italic; }
+.facts, .tags, .source { font-size: 0.9em; color: #444; }
+";
+
+/// One XHTML file of the book, `body` is the markup inside `<body>`
+pub struct EpubChapter {
+    /// File name inside the book, e.g. "recipe-12.xhtml"
+    pub file: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// Picture referenced by the chapters as `images/{file}`
+pub struct EpubImage {
+    pub file: String,
+    pub media_type: String,
+    pub data: Vec<u8>,
+}
+
+/// An EPUB 3 book, chapters are read in order after the contents page.
+/// Markup in `EpubChapter::body` must be well formed XHTML, see `xml_escape`.
+pub struct EpubBook {
+    /// Unique id of the publication, e.g. "urn:uuid:..."
+    pub identifier: String,
+    pub title: String,
+    pub author: Option<String>,
+    pub language: String,
+    /// "CCYY-MM-DDThh:mm:ssZ"
+    pub modified: String,
+    pub subjects: Vec<String>,
+    pub chapters: Vec<EpubChapter>,
+    pub images: Vec<EpubImage>,
+    /// Contents page and reader navigation, (heading, indexes in `chapters`)
+    pub sections: Vec<(String, Vec<usize>)>,
+}
+
+impl EpubBook {
+    /// The `.epub` file
+    pub fn write(&self) -> Result<Vec<u8>, std::io::Error> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+        // Readers sniff the type from a stored `mimetype` first entry
+        let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+        zip.start_file("mimetype", stored)?;
+        zip.write_all(b"application/epub+zip")?;
+
+        let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        let files = [
+            ("META-INF/container.xml", CONTAINER_XML.to_string()),
+            ("OEBPS/content.opf", self.package()),
+            ("OEBPS/nav.xhtml", self.nav()),
+            ("OEBPS/style.css", STYLESHEET.to_string()),
+        ];
+        for (name, content) in files {
+            zip.start_file(name, deflated)?;
+            zip.write_all(content.as_bytes())?;
+        }
+        for chapter in &self.chapters {
+            zip.start_file(format!("OEBPS/{}", chapter.file), deflated)?;
+            zip.write_all(xhtml(&self.language, &chapter.title, &chapter.body).as_bytes())?;
+        }
+        for image in &self.images {
+            // Already compressed formats
+            zip.start_file(format!("OEBPS/images/{}", image.file), stored)?;
+            zip.write_all(&image.data)?;
+        }
+
+        Ok(zip.finish()?.into_inner())
+    }
+
+    fn package(&self) -> String {
+        let mut metadata = vec![
+            format!(
+                r#"<dc:identifier id="book-id">{}</dc:identifier>"#,
+                xml_escape(&self.identifier)
+            ),
+            format!("<dc:title>{}</dc:title>", xml_escape(&self.title)),
+            format!("<dc:language>{}</dc:language>", xml_escape(&self.language)),
+        ];
+        if let Some(author) = &self.author {
+            metadata.push(format!("<dc:creator>{}</dc:creator>", xml_escape(author)));
+        }
+        for subject in &self.subjects {
+            metadata.push(format!("<dc:subject>{}</dc:subject>", xml_escape(subject)));
+        }
+        metadata.push(format!(
+            r#"<meta property="dcterms:modified">{}</meta>"#,
+            xml_escape(&self.modified)
+        ));
+
+        // schema.org accessibility metadata
+        let mut access_modes = vec!["textual"];
+        if !self.images.is_empty() {
+            access_modes.push("visual");
+        }
+        for mode in access_modes {
+            metadata.push(format!(
+                r#"<meta property="schema:accessMode">{}</meta>"#,
+                mode
+            ));
+        }
+        for (property, value) in [
+            ("schema:accessModeSufficient", "textual"),
+            ("schema:accessibilityFeature", "structuralNavigation"),
+            ("schema:accessibilityFeature", "tableOfContents"),
+            ("schema:accessibilityHazard", "none"),
+            (
+                "schema:accessibilitySummary",
+                "Recipes as text with headings, pictures are decorative.",
+            ),
+        ] {
+            metadata.push(format!(r#"<meta property="{}">{}</meta>"#, property, value));
+        }
+
+        let mut manifest = vec![
+            r#"<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>"#
+                .to_string(),
+            r#"<item id="style" href="style.css" media-type="text/css"/>"#.to_string(),
+        ];
+        let mut spine = vec![r#"<itemref idref="nav"/>"#.to_string()];
+        for (index, chapter) in self.chapters.iter().enumerate() {
+            manifest.push(format!(
+                r#"<item id="chapter-{}" href="{}" media-type="application/xhtml+xml"/>"#,
+                index,
+                xml_escape(&chapter.file)
+            ));
+            spine.push(format!(r#"<itemref idref="chapter-{}"/>"#, index));
+        }
+        for (index, image) in self.images.iter().enumerate() {
+            manifest.push(format!(
+                r#"<item id="image-{}" href="images/{}" media-type="{}"/>"#,
+                index,
+                xml_escape(&image.file),
+                xml_escape(&image.media_type)
+            ));
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id" xml:lang="{}">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    {}
+  </metadata>
+  <manifest>
+    {}
+  </manifest>
+  <spine>
+    {}
+  </spine>
+</package>
+"#,
+            xml_escape(&self.language),
+            metadata.join("\n    "),
+            manifest.join("\n    "),
+            spine.join("\n    ")
+        )
+    }
+
+    fn nav(&self) -> String {
+        let mut body = format!(
+            "<h1>{}</h1>\n<nav epub:type=\"toc\" id=\"toc\">\n<h2>Contents</h2>\n<ol>\n",
+            xml_escape(&self.title)
+        );
+        for (heading, chapters) in &self.sections {
+            body.push_str(&format!("<li><span>{}</span>\n<ol>\n", xml_escape(heading)));
+            for chapter in chapters
+                .iter()
+                .filter_map(|index| self.chapters.get(*index))
+            {
+                body.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a></li>\n",
+                    xml_escape(&chapter.file),
+                    xml_escape(&chapter.title)
+                ));
+            }
+            body.push_str("</ol>\n</li>\n");
+        }
+        body.push_str("</ol>\n</nav>\n");
+
+        xhtml(&self.language, &self.title, &body)
+    }
+}
+
+/// Text for XHTML content and attribute values
+pub fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            // Not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// (media type, bytes) of a `data:image/...;base64,` url, the form archive imports store photos in
+pub fn data_url_image(url: &str) -> Option<(String, Vec<u8>)> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    let media_type = header.strip_suffix(";base64")?;
+    if !matches!(
+        media_type,
+        "image/jpeg" | "image/png" | "image/gif" | "image/webp"
+    ) {
+        return None;
+    }
+
+    let bytes = STANDARD.decode(data.trim()).ok()?;
+    Some((media_type.to_string(), bytes))
+}
+
+fn xhtml(language: &str, title: &str, body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="{0}" lang="{0}">
+<head>
+<meta charset="UTF-8"/>
+<title>{1}</title>
+<link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+{2}</body>
+</html>
+"#,
+        xml_escape(language),
+        xml_escape(title),
+        body
+    )
+}
+
+#[cfg(test)]
+#[path = "../_tests/utils_epub.rs"]
+mod tests;
@@ -0,0 +1,300 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::units::format_quantity;
+
+/// An amount, `max` is set for ranges like "2-3"
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Quantity {
+    pub value: f64,
+    pub max: Option<f64>,
+}
+
+/// A free text ingredient line split into its parts, e.g.
+/// "2 1/2 cups all-purpose flour, sifted (divided)"
+/// -> 2.5 / cup / all-purpose flour / sifted / divided
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ingredient {
+    pub quantity: Option<Quantity>,
+    pub unit: Option<String>,
+    pub name: String,
+    pub preparation: Option<String>,
+    pub note: Option<String>,
+    pub raw: String,
+}
+
+/// Writes the ingredient back as a line, "2 1/2 cup all-purpose flour, sifted (divided)"
+impl fmt::Display for Ingredient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = Vec::new();
+
+        if let Some(quantity) = self.quantity {
+            let unit = self.unit.as_deref();
+            match quantity.max {
+                Some(max) => parts.push(format!(
+                    "{}-{}",
+                    format_quantity(quantity.value, unit),
+                    format_quantity(max, unit)
+                )),
+                None => parts.push(format_quantity(quantity.value, unit)),
+            }
+        }
+        if let Some(unit) = &self.unit {
+            let amount = self.quantity.map_or(1.0, |q| q.max.unwrap_or(q.value));
+            parts.push(unit_label(unit, amount));
+        }
+        if !self.name.is_empty() {
+            parts.push(self.name.clone());
+        }
+
+        write!(f, "{}", parts.join(" "))?;
+        if let Some(preparation) = &self.preparation {
+            write!(f, ", {}", preparation)?;
+        }
+        if let Some(note) = &self.note {
+            write!(f, " ({})", note)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// (spellings, canonical unit), spellings are matched lowercase without a trailing "."
+const UNITS: &[(&[&str], &str)] = &[
+    (&["teaspoon", "teaspoons", "tsp", "tsps", "t"], "tsp"),
+    (
+        &[
+            "tablespoon",
+            "tablespoons",
+            "tbsp",
+            "tbsps",
+            "tbs",
+            "tbl",
+            "T",
+        ],
+        "tbsp",
+    ),
+    (&["cup", "cups", "c"], "cup"),
+    (&["fluid ounce", "fluid ounces", "fl oz", "fl. oz"], "fl oz"),
+    (&["ounce", "ounces", "oz"], "oz"),
+    (&["pound", "pounds", "lb", "lbs"], "lb"),
+    (&["pint", "pints", "pt", "pts"], "pint"),
+    (&["quart", "quarts", "qt", "qts"], "quart"),
+    (&["gallon", "gallons", "gal", "gals"], "gallon"),
+    (
+        &[
+            "milliliter",
+            "milliliters",
+            "millilitre",
+            "millilitres",
+            "ml",
+        ],
+        "ml",
+    ),
+    (&["liter", "liters", "litre", "litres", "l"], "l"),
+    (&["gram", "grams", "g", "gr"], "g"),
+    (&["kilogram", "kilograms", "kg", "kgs"], "kg"),
+    (&["milligram", "milligrams", "mg"], "mg"),
+    (&["pinch", "pinches"], "pinch"),
+    (&["dash", "dashes"], "dash"),
+    (&["clove", "cloves"], "clove"),
+    (&["can", "cans"], "can"),
+    (&["stick", "sticks"], "stick"),
+    (&["slice", "slices"], "slice"),
+    (&["package", "packages", "pkg"], "package"),
+    (&["bunch", "bunches"], "bunch"),
+    (&["sprig", "sprigs"], "sprig"),
+];
+
+const VULGAR_FRACTIONS: &[(char, &str)] = &[
+    ('½', "1/2"),
+    ('⅓', "1/3"),
+    ('⅔', "2/3"),
+    ('¼', "1/4"),
+    ('¾', "3/4"),
+    ('⅕', "1/5"),
+    ('⅖', "2/5"),
+    ('⅗', "3/5"),
+    ('⅘', "4/5"),
+    ('⅙', "1/6"),
+    ('⅚', "5/6"),
+    ('⅛', "1/8"),
+    ('⅜', "3/8"),
+    ('⅝', "5/8"),
+    ('⅞', "7/8"),
+];
+
+/// Rewrites unicode fractions and dashes to ascii, "1½–2 cups" -> "1 1/2-2 cups"
+pub fn normalize_fractions(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match VULGAR_FRACTIONS.iter().find(|(vulgar, _)| *vulgar == c) {
+            Some((_, ascii)) => {
+                if normalized.ends_with(|prev: char| prev.is_ascii_digit()) {
+                    normalized.push(' ');
+                }
+                normalized.push_str(ascii);
+            }
+            None => match c {
+                '⁄' => normalized.push('/'),
+                '–' | '—' => normalized.push('-'),
+                '\u{a0}' => normalized.push(' '),
+                c => normalized.push(c),
+            },
+        }
+    }
+
+    normalized
+}
+
+pub fn parse_ingredient(raw: &str) -> Ingredient {
+    let text = normalize_fractions(raw.trim());
+
+    // -- Parenthetical notes, "(14 oz)", "(optional)"
+    let notes: Vec<String> = paren_regex()
+        .captures_iter(&text)
+        .map(|c| c[1].trim().to_string())
+        .filter(|note| !note.is_empty())
+        .collect();
+    let text = paren_regex().replace_all(&text, " ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    // -- Preparation after the first comma, "onion, finely diced"
+    let (text, preparation) = match text.split_once(',') {
+        Some((before, after)) => (before.trim().to_string(), non_empty(after)),
+        None => (text, None),
+    };
+
+    // -- Quantity
+    let (quantity, rest) = match quantity_regex().captures(&text) {
+        Some(caps) => {
+            let value = caps.get(1).and_then(|m| parse_number(m.as_str()));
+            let max = caps.get(2).and_then(|m| parse_number(m.as_str()));
+            let end = caps.get(0).map_or(0, |m| m.end());
+            match value {
+                Some(value) => (Some(Quantity { value, max }), text[end..].trim()),
+                None => (None, text.as_str()),
+            }
+        }
+        None => (None, text.as_str()),
+    };
+
+    // -- Unit, only looked for after a quantity so "cups" in a name is left alone
+    let (unit, rest) = match quantity {
+        Some(_) => split_unit(rest),
+        None => (None, rest),
+    };
+
+    let name = rest.strip_prefix("of ").unwrap_or(rest).trim().to_string();
+
+    Ingredient {
+        quantity,
+        unit,
+        name,
+        preparation,
+        note: Some(notes.join("; ")).filter(|note| !note.is_empty()),
+        raw: raw.to_string(),
+    }
+}
+
+/// "2", "2.5", ".5", "1/2", "2 1/2"
+pub(super) fn parse_number(text: &str) -> Option<f64> {
+    let mut total = 0.0;
+
+    for part in text.split_whitespace() {
+        total += match part.split_once('/') {
+            Some((num, den)) => {
+                let den: f64 = den.parse().ok()?;
+                if den == 0.0 {
+                    return None;
+                }
+                num.parse::<f64>().ok()? / den
+            }
+            None => part.parse::<f64>().ok()?,
+        };
+    }
+
+    Some(total)
+}
+
+/// Splits a leading unit off `text`, trying two word units ("fl oz") first
+fn split_unit(text: &str) -> (Option<String>, &str) {
+    let words: Vec<&str> = text.splitn(3, ' ').collect();
+
+    for len in [2, 1] {
+        if words.len() < len {
+            continue;
+        }
+        let candidate = words[..len].join(" ");
+        if let Some(unit) = canonical_unit(&candidate) {
+            let rest = text[candidate.len()..].trim_start();
+            return (Some(unit.to_string()), rest);
+        }
+    }
+
+    (None, text)
+}
+
+/// Canonical name of a unit spelling, "Tablespoons" -> "tbsp".
+/// Single letters are case sensitive, "T" is a tablespoon and "t" a teaspoon.
+pub fn canonical_unit(word: &str) -> Option<&'static str> {
+    let word = word.trim_end_matches('.');
+    let lower = word.to_lowercase();
+
+    UNITS
+        .iter()
+        .find(|(spellings, _)| {
+            spellings.iter().any(|spelling| {
+                if spelling.len() == 1 {
+                    *spelling == word
+                } else {
+                    *spelling == lower
+                }
+            })
+        })
+        .map(|(_, unit)| *unit)
+}
+
+/// Plural of spelled out units, "2 cups", "3 pinches", abbreviations stay as they are
+fn unit_label(unit: &str, amount: f64) -> String {
+    const ABBREVIATIONS: &[&str] = &[
+        "tsp", "tbsp", "fl oz", "oz", "lb", "ml", "l", "g", "kg", "mg",
+    ];
+
+    if amount <= 1.0 || ABBREVIATIONS.contains(&unit) {
+        unit.to_string()
+    } else if unit.ends_with("ch") || unit.ends_with("sh") {
+        format!("{}es", unit)
+    } else {
+        format!("{}s", unit)
+    }
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+fn paren_regex() -> &'static Regex {
+    static PAREN: OnceLock<Regex> = OnceLock::new();
+    PAREN.get_or_init(|| Regex::new(r"\(([^)]*)\)").unwrap())
+}
+
+fn quantity_regex() -> &'static Regex {
+    static QUANTITY: OnceLock<Regex> = OnceLock::new();
+    QUANTITY.get_or_init(|| {
+        let number = r"(?:\d+\s+\d+/\d+|\d+/\d+|\d+(?:\.\d+)?|\.\d+)";
+        Regex::new(&format!(
+            r"^({number})(?:\s*(?:-|to)\s*({number}))?",
+            number = number
+        ))
+        .unwrap()
+    })
+}
+
+#[cfg(test)]
+#[path = "../_tests/utils_ingredient.rs"]
+mod tests;
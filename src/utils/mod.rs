@@ -1,3 +1,28 @@
+mod archive;
+mod convert;
+mod cooklang;
+mod duration;
+mod epub;
+mod ingredient;
+mod obsidian;
+mod pdf;
+mod scaling;
 mod scraping;
+mod units;
 
-pub use scraping::scrape_recipe_content;
+pub use archive::{
+    read_archive, ArchiveEntry, ArchiveError, ArchiveFormat, ArchivedRecipe, MAX_ARCHIVE_RECIPES,
+};
+pub use convert::{convert_ingredient, convert_temperatures};
+pub use cooklang::{CooklangIngredient, CooklangRecipe, CooklangTimer};
+pub use duration::{format_minutes, iso8601_duration, parse_minutes};
+pub use epub::{data_url_image, xml_escape, EpubBook, EpubChapter, EpubImage};
+pub use ingredient::{canonical_unit, normalize_fractions, parse_ingredient, Ingredient, Quantity};
+pub use obsidian::{read_vault, write_vault, RecipeNote, VaultEntry};
+pub use pdf::{Font, PageSize, PdfDocument};
+pub use scaling::scale_ingredient;
+pub use scraping::{
+    discover_site, fetch_html_if_modified, normalize_source_url, scrape_recipe_html, FetchPolicy,
+    Fetched, ScrapeError, ScrapedRecipe, Validators,
+};
+pub use units::UnitSystem;
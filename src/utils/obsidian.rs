@@ -0,0 +1,414 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{Cursor, Write};
+
+use serde_yaml::{Mapping, Value};
+use zip::{write::FileOptions, ZipWriter};
+
+use super::archive::{is_zip, zip_files, UnpackBudget};
+use super::ArchiveError;
+
+/// Notes imported by one request, each is saved before the response
+pub const MAX_VAULT_NOTES: usize = 200;
+
+/// Characters Obsidian doesn't allow in note names
+const FORBIDDEN_NAME_CHARS: &[char] = &[
+    '*', '"', '\\', '/', '<', '>', ':', '|', '?', '#', '^', '[', ']',
+];
+
+/// A recipe as an Obsidian note, YAML frontmatter for the fields and
+/// `## Ingredients`, `## Steps` and `## Equipment` sections.
+/// `None` lists are sections the note doesn't have.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecipeNote {
+    /// Recipe the note was exported from, `None` for notes written in the vault
+    pub id: Option<i64>,
+    pub title: String,
+    pub header: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub prep_time: Option<String>,
+    pub cook_time: Option<String>,
+    pub total_time: Option<String>,
+    pub servings: Option<i32>,
+    pub image_url: Option<String>,
+    pub source_url: Option<String>,
+    pub source_name: Option<String>,
+    pub original_author: Option<String>,
+    /// When the recipe was last saved at export (RFC 3339), later saves are edits made in the app
+    pub updated: Option<String>,
+    pub ingredients: Option<Vec<String>>,
+    pub steps: Option<Vec<String>>,
+    pub equipment: Option<Vec<String>>,
+}
+
+/// One markdown file of a vault, `name` is its path in the upload
+#[derive(Clone, Debug, PartialEq)]
+pub struct VaultEntry {
+    pub name: String,
+    pub note: Result<RecipeNote, String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Header,
+    Ingredients,
+    Steps,
+    Equipment,
+    Other,
+}
+
+impl RecipeNote {
+    /// Reads a note, the title falls back to the first `# ` heading then to `file_stem`
+    pub fn parse(text: &str, file_stem: &str) -> Result<Self, String> {
+        let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+        let (frontmatter, body) = split_frontmatter(&text);
+
+        let mut note = match frontmatter {
+            Some(yaml) => from_frontmatter(yaml)?,
+            None => RecipeNote::default(),
+        };
+
+        let mut heading = None;
+        let mut header: Vec<&str> = Vec::new();
+        let mut section = Section::Header;
+        for line in body.lines() {
+            if let Some(title) = line.strip_prefix("# ") {
+                heading.get_or_insert_with(|| title.trim().to_string());
+                section = Section::Header;
+                continue;
+            }
+            if let Some(title) = line.strip_prefix("## ") {
+                section = section_of(title);
+                if let Some(items) = note.section_mut(section) {
+                    items.get_or_insert_with(Vec::new);
+                }
+                continue;
+            }
+            // "### For the sauce" within a section
+            if line.starts_with("###") {
+                continue;
+            }
+
+            match note.section_mut(section) {
+                Some(items) => push_item(items.get_or_insert_with(Vec::new), line),
+                None if section == Section::Header => header.push(line.trim_end()),
+                None => {}
+            }
+        }
+
+        let header = header.join("\n").trim().to_string();
+        if !header.is_empty() {
+            note.header = Some(header);
+        }
+        if note.title.is_empty() {
+            note.title = heading.unwrap_or_else(|| file_stem.trim().to_string());
+        }
+        if note.title.is_empty() {
+            return Err("Note has no title".to_string());
+        }
+
+        Ok(note)
+    }
+
+    /// "{title}.md" without the characters Obsidian rejects in names
+    pub fn file_name(&self) -> String {
+        let name: String = self
+            .title
+            .chars()
+            .filter(|c| !FORBIDDEN_NAME_CHARS.contains(c) && !c.is_control())
+            .collect();
+        let name = name.trim().trim_start_matches('.').trim();
+        match (name.is_empty(), self.id) {
+            (false, _) => format!("{}.md", name),
+            (true, Some(id)) => format!("Recipe {}.md", id),
+            (true, None) => "Recipe.md".to_string(),
+        }
+    }
+
+    fn section_mut(&mut self, section: Section) -> Option<&mut Option<Vec<String>>> {
+        match section {
+            Section::Ingredients => Some(&mut self.ingredients),
+            Section::Steps => Some(&mut self.steps),
+            Section::Equipment => Some(&mut self.equipment),
+            Section::Header | Section::Other => None,
+        }
+    }
+
+    fn frontmatter(&self) -> Mapping {
+        let mut yaml = Mapping::new();
+        let mut insert = |key: &str, value: Value| {
+            yaml.insert(Value::from(key), value);
+        };
+
+        if let Some(id) = self.id {
+            insert("id", Value::from(id));
+        }
+        insert("title", Value::from(self.title.as_str()));
+        if let Some(tags) = &self.tags {
+            insert("tags", Value::from(tags.clone()));
+        }
+        let texts = [
+            ("prep_time", &self.prep_time),
+            ("cook_time", &self.cook_time),
+            ("total_time", &self.total_time),
+        ];
+        for (key, value) in texts {
+            if let Some(value) = value.as_deref().filter(|value| !value.is_empty()) {
+                insert(key, Value::from(value));
+            }
+        }
+        if let Some(servings) = self.servings {
+            insert("servings", Value::from(servings));
+        }
+        let texts = [
+            ("image", &self.image_url),
+            ("source", &self.source_url),
+            ("source_name", &self.source_name),
+            ("author", &self.original_author),
+            ("updated", &self.updated),
+        ];
+        for (key, value) in texts {
+            if let Some(value) = value.as_deref().filter(|value| !value.is_empty()) {
+                insert(key, Value::from(value));
+            }
+        }
+
+        yaml
+    }
+}
+
+impl fmt::Display for RecipeNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yaml = serde_yaml::to_string(&self.frontmatter()).map_err(|_| fmt::Error)?;
+        write!(f, "---\n{}---\n\n# {}\n", yaml, self.title)?;
+
+        if let Some(header) = self.header.as_deref().map(str::trim) {
+            if !header.is_empty() {
+                write!(f, "\n{}\n", header)?;
+            }
+        }
+
+        let sections = [
+            ("Ingredients", &self.ingredients, false),
+            ("Steps", &self.steps, true),
+            ("Equipment", &self.equipment, false),
+        ];
+        for (title, items, numbered) in sections {
+            let Some(items) = items.as_ref().filter(|items| !items.is_empty()) else {
+                continue;
+            };
+            write!(f, "\n## {}\n\n", title)?;
+            for (index, item) in items.iter().enumerate() {
+                let item = item.replace('\n', " ");
+                match numbered {
+                    true => writeln!(f, "{}. {}", index + 1, item)?,
+                    false => writeln!(f, "- {}", item)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Zip of one `.md` file per note, names made unique with the recipe id
+pub fn write_vault(notes: &[RecipeNote]) -> Result<Vec<u8>, std::io::Error> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let mut names = HashSet::new();
+
+    for note in notes {
+        let mut name = note.file_name();
+        if !names.insert(name.to_lowercase()) {
+            let stem = name.trim_end_matches(".md").to_string();
+            let mut count = 1;
+            loop {
+                name = match (note.id, count) {
+                    (Some(id), 1) => format!("{} ({}).md", stem, id),
+                    _ => format!("{} ({}).md", stem, count),
+                };
+                if names.insert(name.to_lowercase()) {
+                    break;
+                }
+                count += 1;
+            }
+        }
+
+        zip.start_file(name, FileOptions::default())?;
+        zip.write_all(note.to_string().as_bytes())?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Notes of a zipped vault folder, or of a single markdown file.
+/// Obsidian's own folders (`.obsidian/`, `.trash/`) and other files are never unzipped.
+pub fn read_vault(bytes: &[u8]) -> Result<Vec<VaultEntry>, ArchiveError> {
+    let files = match is_zip(bytes) {
+        true => zip_files(bytes, &mut UnpackBudget::new(), is_note)?,
+        false => vec![("note.md".to_string(), bytes.to_vec())],
+    };
+
+    let entries: Vec<VaultEntry> = files
+        .into_iter()
+        .map(|(name, content)| {
+            let stem = name
+                .rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(".md")
+                .to_string();
+            let note = String::from_utf8(content)
+                .map_err(|_| "Note is not UTF-8 text".to_string())
+                .and_then(|text| RecipeNote::parse(&text, &stem));
+            VaultEntry { name, note }
+        })
+        .collect();
+
+    if entries.is_empty() {
+        return Err(ArchiveError::InvalidArchive(
+            "no markdown notes in the vault".to_string(),
+        ));
+    }
+    if entries.len() > MAX_VAULT_NOTES {
+        return Err(ArchiveError::TooManyRecipes(MAX_VAULT_NOTES));
+    }
+
+    Ok(entries)
+}
+
+/// Markdown file outside the hidden folders of a vault
+fn is_note(name: &str) -> bool {
+    name.to_lowercase().ends_with(".md")
+        && !name
+            .split('/')
+            .any(|part| part.starts_with('.') || part == "__MACOSX")
+}
+
+// region:    Note helpers
+
+/// (YAML, body), the frontmatter sits between `---` lines at the very top
+fn split_frontmatter(text: &str) -> (Option<&str>, &str) {
+    let Some(rest) = text.strip_prefix("---\n") else {
+        return (None, text);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, text)
+}
+
+fn from_frontmatter(yaml: &str) -> Result<RecipeNote, String> {
+    let value: Value =
+        serde_yaml::from_str(yaml).map_err(|ex| format!("Invalid frontmatter - {}", ex))?;
+    let yaml = match value {
+        Value::Mapping(yaml) => yaml,
+        Value::Null => Mapping::new(),
+        _ => return Err("Invalid frontmatter - expected `key: value` lines".to_string()),
+    };
+    let field = |keys: &[&str]| keys.iter().find_map(|key| yaml.get(*key).and_then(text));
+
+    let id = match yaml.get("id") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(
+            text(value)
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| "Frontmatter `id` is not a recipe id".to_string())?,
+        ),
+    };
+
+    Ok(RecipeNote {
+        id,
+        title: field(&["title"]).unwrap_or_default(),
+        tags: yaml.get("tags").map(tags),
+        prep_time: field(&["prep_time", "prep"]),
+        cook_time: field(&["cook_time", "cook"]),
+        total_time: field(&["total_time", "time"]),
+        servings: field(&["servings", "yield"]).and_then(|servings| {
+            let digits: String = servings.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        }),
+        image_url: field(&["image", "cover"]),
+        source_url: field(&["source", "url"]),
+        source_name: field(&["source_name"]),
+        original_author: field(&["author"]),
+        updated: field(&["updated"]),
+        ..Default::default()
+    })
+}
+
+/// Trimmed string (or number) value, `None` when blank
+fn text(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(text) => text.trim().to_string(),
+        Value::Number(number) => number.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// A list, or the comma separated string Obsidian also accepts, without `#`
+fn tags(value: &Value) -> Vec<String> {
+    let tags: Vec<String> = match value {
+        Value::Sequence(items) => items.iter().filter_map(text).collect(),
+        value => text(value)
+            .map(|tags| tags.split(',').map(str::to_string).collect())
+            .unwrap_or_default(),
+    };
+    tags.iter()
+        .map(|tag| tag.trim().trim_start_matches('#').trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+fn section_of(title: &str) -> Section {
+    match title.trim().to_lowercase().as_str() {
+        "ingredients" => Section::Ingredients,
+        "steps" | "instructions" | "directions" | "method" => Section::Steps,
+        "equipment" | "cookware" | "tools" => Section::Equipment,
+        _ => Section::Other,
+    }
+}
+
+/// Adds a list line without its marker, indented lines continue the previous item
+fn push_item(items: &mut Vec<String>, line: &str) {
+    if line.trim().is_empty() {
+        return;
+    }
+    let item = strip_marker(line.trim());
+    match items.last_mut() {
+        Some(last) if line.starts_with([' ', '\t']) && item.len() == line.trim().len() => {
+            last.push(' ');
+            last.push_str(item);
+        }
+        _ => items.push(item.to_string()),
+    }
+}
+
+/// "- [ ] 2 eggs", "* 2 eggs" or "2. Whisk" without the list marker
+fn strip_marker(line: &str) -> &str {
+    let line = match line.strip_prefix(['-', '*', '+']) {
+        Some(rest) if rest.starts_with(' ') => rest.trim_start(),
+        _ => {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            match line[digits..].strip_prefix(['.', ')']) {
+                Some(rest) if digits > 0 && rest.starts_with(' ') => rest.trim_start(),
+                _ => line,
+            }
+        }
+    };
+    ["[ ] ", "[x] ", "[X] "]
+        .iter()
+        .find_map(|checkbox| line.strip_prefix(checkbox))
+        .unwrap_or(line)
+        .trim()
+}
+
+// endregion: Note helpers
+
+#[cfg(test)]
+#[path = "../_tests/utils_obsidian.rs"]
+mod tests;
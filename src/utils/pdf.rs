@@ -0,0 +1,380 @@
+use pdf_writer::types::{ActionType, AnnotationType};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use serde::{Deserialize, Serialize};
+
+/// Space left blank around the text on every side, in points
+const MARGIN: f32 = 60.0;
+/// Distance between baselines, relative to the font size
+const LINE_SPACING: f32 = 1.35;
+
+/// Paper the document is laid out for
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+    #[default]
+    Letter,
+    A4,
+}
+
+impl PageSize {
+    /// (width, height) in points
+    pub fn dimensions(self) -> (f32, f32) {
+        match self {
+            PageSize::Letter => (612.0, 792.0),
+            PageSize::A4 => (595.28, 841.89),
+        }
+    }
+}
+
+/// Standard PDF fonts, every reader has them so nothing is embedded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+    Italic,
+}
+
+impl Font {
+    const ALL: [Font; 3] = [Font::Regular, Font::Bold, Font::Italic];
+
+    fn resource(self) -> Name<'static> {
+        match self {
+            Font::Regular => Name(b"F1"),
+            Font::Bold => Name(b"F2"),
+            Font::Italic => Name(b"F3"),
+        }
+    }
+
+    fn base_font(self) -> Name<'static> {
+        match self {
+            Font::Regular => Name(b"Helvetica"),
+            Font::Bold => Name(b"Helvetica-Bold"),
+            Font::Italic => Name(b"Helvetica-Oblique"),
+        }
+    }
+}
+
+struct Text {
+    font: Font,
+    size: f32,
+    x: f32,
+    y: f32,
+    text: String,
+}
+
+/// A clickable area going to another page of the document
+struct Link {
+    rect: Rect,
+    page: usize,
+}
+
+#[derive(Default)]
+struct Page {
+    texts: Vec<Text>,
+    links: Vec<Link>,
+}
+
+/// A text only document laid out top to bottom, a new page starts when one is full.
+/// Text is WinAnsi encoded, characters outside of it print as "?".
+pub struct PdfDocument {
+    size: PageSize,
+    pages: Vec<Page>,
+    /// Top of the next line on the last page, from the bottom
+    y: f32,
+}
+
+impl PdfDocument {
+    pub fn new(size: PageSize) -> Self {
+        PdfDocument {
+            size,
+            pages: Vec::new(),
+            y: 0.0,
+        }
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Starts a page and returns its index
+    pub fn new_page(&mut self) -> usize {
+        self.pages.push(Page::default());
+        self.y = self.size.dimensions().1 - MARGIN;
+        self.pages.len() - 1
+    }
+
+    /// Leaves `points` blank, or starts a page when that reaches the bottom
+    pub fn space(&mut self, points: f32) {
+        self.y -= points;
+        if self.y < MARGIN {
+            self.new_page();
+        }
+    }
+
+    /// Text wrapped to the page width less `indent`, continued on new pages as needed
+    pub fn paragraph(&mut self, text: &str, font: Font, size: f32, indent: f32) {
+        let width = self.text_width() - indent;
+        for line in wrap(text, font, size, width) {
+            let y = self.next_line(size);
+            self.push_text(font, size, MARGIN + indent, y, line);
+        }
+    }
+
+    /// One line centered on the page
+    pub fn centered(&mut self, text: &str, font: Font, size: f32) {
+        for line in wrap(text, font, size, self.text_width()) {
+            let y = self.next_line(size);
+            let x = (self.size.dimensions().0 - text_width(&line, font, size)) / 2.0;
+            self.push_text(font, size, x, y, line);
+        }
+    }
+
+    /// A table of contents line, `text` on the left and `label` right aligned,
+    /// the whole line links to the page at index `target` of the final document
+    pub fn link_line(
+        &mut self,
+        text: &str,
+        label: &str,
+        font: Font,
+        size: f32,
+        indent: f32,
+        target: usize,
+    ) {
+        let right = MARGIN + self.text_width();
+        let label_width = text_width(label, font, size);
+        let width = self.text_width() - indent - label_width - size;
+
+        let lines = wrap(text, font, size, width);
+        let last = lines.len().saturating_sub(1);
+        for (index, line) in lines.into_iter().enumerate() {
+            let y = self.next_line(size);
+            let line_width = text_width(&line, font, size);
+            self.push_text(font, size, MARGIN + indent, y, line);
+            if index == last {
+                self.push_text(font, size, right - label_width, y, label.to_string());
+            }
+
+            let rect = Rect::new(
+                MARGIN + indent,
+                y - size * 0.3,
+                right.max(MARGIN + indent + line_width),
+                y + size,
+            );
+            if let Some(page) = self.pages.last_mut() {
+                page.links.push(Link { rect, page: target });
+            }
+        }
+    }
+
+    /// Prints the page number (index + 1) centered at the bottom of the pages from `from` on
+    pub fn number_pages(&mut self, from: usize) {
+        let width = self.size.dimensions().0;
+        for (index, page) in self.pages.iter_mut().enumerate().skip(from) {
+            let label = (index + 1).to_string();
+            let x = (width - text_width(&label, Font::Regular, 9.0)) / 2.0;
+            page.texts.push(Text {
+                font: Font::Regular,
+                size: 9.0,
+                x,
+                y: MARGIN / 2.0,
+                text: label,
+            });
+        }
+    }
+
+    /// Adds the pages of `other` after the pages of this document
+    pub fn append(&mut self, other: PdfDocument) {
+        self.pages.extend(other.pages);
+        self.y = other.y;
+    }
+
+    /// The PDF file, `title` goes to the document info
+    pub fn finish(self, title: &str) -> Vec<u8> {
+        let (width, height) = self.size.dimensions();
+        let catalog_id = Ref::new(1);
+        let page_tree_id = Ref::new(2);
+        let info_id = Ref::new(3);
+        let font_ids = [Ref::new(4), Ref::new(5), Ref::new(6)];
+        let page_ids: Vec<Ref> = (0..self.pages.len())
+            .map(|index| Ref::new(7 + 2 * index as i32))
+            .collect();
+
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.pages(page_tree_id)
+            .kids(page_ids.iter().copied())
+            .count(page_ids.len() as i32);
+        pdf.document_info(info_id).title(TextStr(title));
+        for (font, id) in Font::ALL.into_iter().zip(font_ids) {
+            pdf.type1_font(id)
+                .base_font(font.base_font())
+                .encoding_predefined(Name(b"WinAnsiEncoding"));
+        }
+
+        for (page, page_id) in self.pages.iter().zip(&page_ids) {
+            let content_id = Ref::new(page_id.get() + 1);
+
+            let mut writer = pdf.page(*page_id);
+            writer
+                .parent(page_tree_id)
+                .media_box(Rect::new(0.0, 0.0, width, height))
+                .contents(content_id);
+            let mut resources = writer.resources();
+            let mut fonts = resources.fonts();
+            for (font, id) in Font::ALL.into_iter().zip(font_ids) {
+                fonts.pair(font.resource(), id);
+            }
+            fonts.finish();
+            resources.finish();
+
+            let mut annotations = writer.annotations();
+            for link in &page.links {
+                let Some(target) = page_ids.get(link.page) else {
+                    continue;
+                };
+                let mut annotation = annotations.push();
+                annotation
+                    .subtype(AnnotationType::Link)
+                    .rect(link.rect)
+                    .border(0.0, 0.0, 0.0, None);
+                annotation
+                    .action()
+                    .action_type(ActionType::GoTo)
+                    .destination()
+                    .page(*target)
+                    .fit();
+            }
+            annotations.finish();
+            writer.finish();
+
+            let mut content = Content::new();
+            for text in &page.texts {
+                content
+                    .begin_text()
+                    .set_font(text.font.resource(), text.size)
+                    .next_line(text.x, text.y)
+                    .show(Str(&win_ansi(&text.text)))
+                    .end_text();
+            }
+            pdf.stream(content_id, &content.finish());
+        }
+
+        pdf.finish()
+    }
+
+    fn text_width(&self) -> f32 {
+        self.size.dimensions().0 - 2.0 * MARGIN
+    }
+
+    /// Baseline of a line of `size` text, on a new page when it doesn't fit
+    fn next_line(&mut self, size: f32) -> f32 {
+        let height = size * LINE_SPACING;
+        if self.pages.is_empty() || self.y - height < MARGIN {
+            self.new_page();
+        }
+        self.y -= height;
+        self.y + size * (LINE_SPACING - 1.0)
+    }
+
+    fn push_text(&mut self, font: Font, size: f32, x: f32, y: f32, text: String) {
+        if let Some(page) = self.pages.last_mut() {
+            page.texts.push(Text {
+                font,
+                size,
+                x,
+                y,
+                text,
+            });
+        }
+    }
+}
+
+// region:    Text helpers
+
+/// Lines of at most `width` points, words longer than a line are cut
+fn wrap(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let candidate = match line.is_empty() {
+            true => word.to_string(),
+            false => format!("{} {}", line, word),
+        };
+        if text_width(&candidate, font, size) <= width {
+            line = candidate;
+            continue;
+        }
+
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            if !line.is_empty() && text_width(&format!("{}{}", line, c), font, size) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Width in points, from the Helvetica metrics (bold runs about 10% wider)
+fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    let units: u32 = text.chars().map(char_width).sum();
+    let scale = match font {
+        Font::Bold => 1.1,
+        Font::Regular | Font::Italic => 1.0,
+    };
+    units as f32 * size * scale / 1000.0
+}
+
+/// Helvetica advance widths of ' ' to '~', in thousandths of the font size
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278,
+    278, // ' ' to '/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // '0' to '9'
+    278, 278, 584, 584, 584, 556, 1015, // ':' to '@'
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // 'A' to 'M'
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // 'N' to 'Z'
+    278, 278, 278, 469, 556, 333, // '[' to '`'
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // 'a' to 'm'
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // 'n' to 'z'
+    334, 260, 334, 584, // '{' to '~'
+];
+
+fn char_width(c: char) -> u32 {
+    match c {
+        ' '..='~' => HELVETICA_WIDTHS[c as usize - ' ' as usize] as u32,
+        _ => 556,
+    }
+}
+
+/// WinAnsi bytes of `text`, Latin-1 as is plus the typographic quotes and dashes
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201c}' => 0x93,
+            '\u{201d}' => 0x94,
+            '\u{2022}' => 0x95,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            '\u{2026}' => 0x85,
+            '\u{20ac}' => 0x80,
+            _ => b'?',
+        })
+        .collect()
+}
+
+// endregion: Text helpers
+
+#[cfg(test)]
+#[path = "../_tests/utils_pdf.rs"]
+mod tests;
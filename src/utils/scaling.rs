@@ -0,0 +1,43 @@
+use super::ingredient::{Ingredient, Quantity};
+use super::units::{best_unit, round_quantity, unit_def};
+
+/// Multiplies the ingredient's amount by `factor`, rounded to something a cook can measure
+/// and moved to a more readable unit when it grows or shrinks past one (48 tsp -> 1 cup)
+pub fn scale_ingredient(ingredient: &Ingredient, factor: f64) -> Ingredient {
+    let Some(quantity) = ingredient.quantity else {
+        return ingredient.clone();
+    };
+
+    let value = quantity.value * factor;
+    let max = quantity.max.map(|max| max * factor);
+
+    let (unit, value, max) = match ingredient.unit.as_deref().and_then(unit_def) {
+        Some(def) => {
+            let (best, value) = best_unit(value, def, def.system);
+            let ratio = def.base / best.base;
+            (
+                Some(best.name.to_string()),
+                value,
+                max.map(|max| max * ratio),
+            )
+        }
+        // counts, cloves, cans... only the number changes
+        None => (ingredient.unit.clone(), value, max),
+    };
+
+    let mut scaled = Ingredient {
+        quantity: Some(Quantity {
+            value: round_quantity(value, unit.as_deref()),
+            max: max.map(|max| round_quantity(max, unit.as_deref())),
+        }),
+        unit,
+        ..ingredient.clone()
+    };
+    scaled.raw = scaled.to_string();
+
+    scaled
+}
+
+#[cfg(test)]
+#[path = "../_tests/utils_scaling.rs"]
+mod tests;
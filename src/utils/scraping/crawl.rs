@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use regex::Regex;
+use reqwest::Url;
+
+use super::fetch::{fetch_text, parse_url, FetchPolicy};
+use super::ScrapeError;
+
+/// Sitemaps read for one site, indexes included, and sitemaps kept from its robots.txt
+const MAX_SITEMAPS: usize = 20;
+/// Allow / Disallow rules kept per robots.txt group, every page is checked against them
+const MAX_ROBOTS_RULES: usize = 500;
+/// Longest Crawl-delay honored, longer ones would keep a crawl job alive for days
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(5);
+
+/// Rules of the robots.txt group that applies to us, see https://www.rfc-editor.org/rfc/rfc9309
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Robots {
+    /// (allow, path pattern)
+    rules: Vec<(bool, String)>,
+    pub crawl_delay: Option<Duration>,
+    pub sitemaps: Vec<String>,
+}
+
+impl Robots {
+    /// Rules for `user_agent` in `text`, from the group naming its product token
+    /// (compared case-insensitively) when there is one, else from `*`
+    pub fn parse(text: &str, user_agent: &str) -> Self {
+        let token = user_agent
+            .split(['/', ' '])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        let mut groups: Vec<Group> = Vec::new();
+        let mut sitemaps = Vec::new();
+        let mut in_agents = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim().to_lowercase(), value.trim());
+
+            match key.as_str() {
+                "user-agent" => {
+                    // consecutive user-agent lines share one group
+                    if !in_agents {
+                        groups.push(Group::default());
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_lowercase());
+                    }
+                    in_agents = true;
+                }
+                "allow" | "disallow" => {
+                    in_agents = false;
+                    // an empty disallow allows everything, same as no rule
+                    if let (Some(group), false) = (groups.last_mut(), value.is_empty()) {
+                        if group.rules.len() < MAX_ROBOTS_RULES {
+                            group.rules.push((key == "allow", value.to_string()));
+                        }
+                    }
+                }
+                "crawl-delay" => {
+                    in_agents = false;
+                    if let (Some(group), Ok(secs)) = (groups.last_mut(), value.parse::<f64>()) {
+                        group.crawl_delay = Duration::try_from_secs_f64(secs)
+                            .ok()
+                            .map(|delay| delay.min(MAX_CRAWL_DELAY));
+                    }
+                }
+                "sitemap" if sitemaps.len() < MAX_SITEMAPS => sitemaps.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        let group = groups
+            .iter()
+            .find(|group| {
+                group
+                    .agents
+                    .iter()
+                    .any(|agent| !token.is_empty() && *agent == token)
+            })
+            .or_else(|| {
+                groups
+                    .iter()
+                    .find(|group| group.agents.iter().any(|agent| agent == "*"))
+            });
+
+        match group {
+            Some(group) => Robots {
+                rules: group.rules.clone(),
+                crawl_delay: group.crawl_delay,
+                sitemaps,
+            },
+            None => Robots {
+                sitemaps,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Whether `path` (with its query) may be fetched. The longest matching rule wins,
+    /// `Allow` on a tie.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| rule_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// One `User-agent` group of a robots.txt
+#[derive(Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+/// robots.txt patterns, a prefix with `*` for any run of characters and `$` for the end
+fn rule_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return true;
+    };
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i == parts.len() - 1;
+        match (last && anchored, rest.find(part)) {
+            (true, _) => return rest.ends_with(part),
+            (false, Some(at)) => rest = &rest[at + part.len()..],
+            (false, None) => return false,
+        }
+    }
+
+    !anchored || rest.is_empty()
+}
+
+/// Page urls of a `<urlset>` or child sitemaps of a `<sitemapindex>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sitemap {
+    pub pages: Vec<String>,
+    pub sitemaps: Vec<String>,
+}
+
+impl Sitemap {
+    pub fn parse(xml: &str) -> Self {
+        static LOC: OnceLock<Regex> = OnceLock::new();
+        let loc = LOC.get_or_init(|| Regex::new(r"(?s)<loc>\s*(.*?)\s*</loc>").unwrap());
+
+        let locs: Vec<String> = loc
+            .captures_iter(xml)
+            .map(|caps| unescape_xml(&caps[1]))
+            .collect();
+
+        if xml.contains("<sitemapindex") {
+            Sitemap {
+                sitemaps: locs,
+                ..Default::default()
+            }
+        } else {
+            Sitemap {
+                pages: locs,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+fn unescape_xml(text: &str) -> String {
+    let text = text.trim_start_matches("<![CDATA[").trim_end_matches("]]>");
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// What the crawl of a site found to import
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Discovery {
+    /// Same host pages robots.txt lets us fetch, in sitemap order
+    pub pages: Vec<String>,
+    /// Pages robots.txt keeps us out of, at most `max_pages` of them
+    pub disallowed: Vec<String>,
+    /// Disallowed pages past `max_pages`, only counted
+    pub disallowed_more: usize,
+    pub crawl_delay: Option<Duration>,
+}
+
+/// Reads robots.txt and the sitemaps of the site `url` is on (the ones robots.txt lists,
+/// else /sitemap.xml), following sitemap indexes, up to `max_pages` pages
+pub async fn discover_site(
+    url: &str,
+    policy: &FetchPolicy,
+    max_pages: usize,
+) -> Result<Discovery, ScrapeError> {
+    let root = parse_url(url)?;
+    let site_url = |path: &str| {
+        root.join(path)
+            .map(|url| url.to_string())
+            .map_err(|_| ScrapeError::InvalidUrl(url.to_string()))
+    };
+
+    // a site without robots.txt (any 4xx) allows everything
+    let robots = match fetch_text(&site_url("/robots.txt")?, policy).await {
+        Ok((_, text)) => Robots::parse(&text, &policy.user_agent),
+        Err(ScrapeError::FetchFailed(_, reason)) if reason.starts_with("HTTP 4") => {
+            Robots::default()
+        }
+        Err(ex) => return Err(ex),
+    };
+
+    let mut queue: Vec<String> = if robots.sitemaps.is_empty() {
+        vec![site_url("/sitemap.xml")?]
+    } else {
+        // popped from the back
+        robots.sitemaps.iter().rev().cloned().collect()
+    };
+    let mut read = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut discovery = Discovery {
+        crawl_delay: robots.crawl_delay,
+        ..Default::default()
+    };
+
+    while let Some(sitemap_url) = queue.pop() {
+        if read.len() >= MAX_SITEMAPS || discovery.pages.len() >= max_pages {
+            break;
+        }
+        if !read.insert(sitemap_url.clone()) || !same_host(&root, &sitemap_url) {
+            continue;
+        }
+
+        let sitemap = match fetch_text(&sitemap_url, policy).await {
+            Ok((_, xml)) => Sitemap::parse(&xml),
+            // a broken child sitemap shouldn't sink the whole crawl
+            Err(ScrapeError::FetchFailed(..)) if read.len() > 1 => continue,
+            Err(ex) => return Err(ex),
+        };
+
+        // depth first, in document order
+        queue.extend(sitemap.sitemaps.into_iter().rev());
+
+        for page in sitemap.pages {
+            let Some(page_url) = Url::parse(&page).ok().filter(|_| same_host(&root, &page)) else {
+                continue;
+            };
+            if !seen.insert(page.clone()) {
+                continue;
+            }
+
+            let path = match page_url.query() {
+                Some(query) => format!("{}?{}", page_url.path(), query),
+                None => page_url.path().to_string(),
+            };
+            if robots.is_allowed(&path) {
+                if discovery.pages.len() < max_pages {
+                    discovery.pages.push(page);
+                }
+            } else if discovery.disallowed.len() < max_pages {
+                discovery.disallowed.push(page);
+            } else {
+                discovery.disallowed_more += 1;
+            }
+        }
+    }
+
+    Ok(discovery)
+}
+
+/// Same host as the site, "www." or not
+fn same_host(root: &Url, url: &str) -> bool {
+    let strip = |host: &str| host.trim_start_matches("www.").to_lowercase();
+
+    match (root.host_str(), Url::parse(url).ok()) {
+        (Some(root), Some(url)) => url.host_str().map(strip) == Some(strip(root)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+#[path = "../../_tests/utils_scraping_crawl.rs"]
+mod tests;
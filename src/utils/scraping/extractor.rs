@@ -0,0 +1,208 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use scraper::Html;
+
+use super::heuristic::HeuristicExtractor;
+use super::json_ld::JsonLdExtractor;
+use super::microdata::MicrodataExtractor;
+use crate::utils::duration::format_minutes;
+
+/// What one extractor found on a page, fields it couldn't find are left empty
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExtractedRecipe {
+    pub title: Option<String>,
+    pub header: Option<String>,
+    pub image_url: Option<String>,
+    pub image_width: Option<String>,
+    pub image_height: Option<String>,
+    pub author: Option<String>,
+    /// Canonical url of the recipe, from og:url or the JSON-LD `url`
+    pub canonical_url: Option<String>,
+    /// Name of the site or publisher, e.g. "Serious Eats"
+    pub site_name: Option<String>,
+    pub ingredients: Vec<String>,
+    pub steps: Vec<String>,
+    /// `recipeYield` as written, "4 servings", "1 loaf"
+    pub recipe_yield: Option<String>,
+    pub servings: Option<i32>,
+    pub prep_time: Option<String>,
+    pub cook_time: Option<String>,
+    pub total_time: Option<String>,
+    pub prep_minutes: Option<i32>,
+    pub cook_minutes: Option<i32>,
+    pub total_minutes: Option<i32>,
+    /// e.g. calories -> "240 kcal"
+    pub nutrition: BTreeMap<String, String>,
+}
+
+impl ExtractedRecipe {
+    /// Share of the fields a recipe needs that were found, from 0 to 1.
+    /// Ingredients and steps weigh the most, a page without them isn't much of a recipe.
+    pub fn completeness(&self) -> f32 {
+        let found = [
+            (self.title.is_some(), 0.15),
+            (!self.ingredients.is_empty(), 0.35),
+            (!self.steps.is_empty(), 0.35),
+            (self.image_url.is_some(), 0.05),
+            (self.total_minutes.or(self.cook_minutes).is_some(), 0.05),
+            (self.servings.is_some(), 0.05),
+        ];
+
+        found
+            .iter()
+            .filter(|(found, _)| *found)
+            .map(|(_, w)| w)
+            .sum()
+    }
+
+    /// Fills the fields still empty from `other`, lists are taken whole or not at all
+    pub fn fill_missing(&mut self, other: &ExtractedRecipe) {
+        fn fill<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+            if field.is_none() {
+                field.clone_from(other);
+            }
+        }
+
+        fill(&mut self.title, &other.title);
+        fill(&mut self.header, &other.header);
+        fill(&mut self.image_url, &other.image_url);
+        fill(&mut self.image_width, &other.image_width);
+        fill(&mut self.image_height, &other.image_height);
+        fill(&mut self.author, &other.author);
+        fill(&mut self.canonical_url, &other.canonical_url);
+        fill(&mut self.site_name, &other.site_name);
+        fill(&mut self.recipe_yield, &other.recipe_yield);
+        fill(&mut self.servings, &other.servings);
+        fill(&mut self.prep_time, &other.prep_time);
+        fill(&mut self.cook_time, &other.cook_time);
+        fill(&mut self.total_time, &other.total_time);
+        fill(&mut self.prep_minutes, &other.prep_minutes);
+        fill(&mut self.cook_minutes, &other.cook_minutes);
+        fill(&mut self.total_minutes, &other.total_minutes);
+        if self.ingredients.is_empty() {
+            self.ingredients.clone_from(&other.ingredients);
+        }
+        if self.steps.is_empty() {
+            self.steps.clone_from(&other.steps);
+        }
+        if self.nutrition.is_empty() {
+            self.nutrition.clone_from(&other.nutrition);
+        }
+    }
+
+    /// Time texts for the minutes that came without one, e.g. from ISO 8601 durations
+    pub(super) fn with_time_texts(mut self) -> Self {
+        for (text, minutes) in [
+            (&mut self.prep_time, self.prep_minutes),
+            (&mut self.cook_time, self.cook_minutes),
+            (&mut self.total_time, self.total_minutes),
+        ] {
+            if text.is_none() {
+                *text = minutes.map(format_minutes);
+            }
+        }
+        self
+    }
+}
+
+/// Finds a recipe in a page. Implement it for a site whose markup needs custom handling
+/// and register it with `ExtractorRegistry::register_site`.
+pub trait RecipeExtractor: Send + Sync {
+    /// Reported as the `source` of the scraped recipe
+    fn name(&self) -> &'static str;
+
+    fn extract(&self, html: &Html) -> Option<ExtractedRecipe>;
+
+    /// How much the markup this extractor reads can be trusted, from 0 to 1
+    fn trust(&self) -> f32 {
+        1.0
+    }
+}
+
+/// Best result of an extractor chain
+#[derive(Debug)]
+pub struct Extraction {
+    pub recipe: ExtractedRecipe,
+    pub source: &'static str,
+    pub confidence: f32,
+}
+
+/// Site specific extractors keyed by host, tried along with the generic ones
+#[derive(Default)]
+pub struct ExtractorRegistry {
+    sites: HashMap<String, Vec<Box<dyn RecipeExtractor>>>,
+    generic: Vec<Box<dyn RecipeExtractor>>,
+}
+
+impl ExtractorRegistry {
+    /// The generic JSON-LD, microdata and heuristic extractors,
+    /// site specific extractors get registered here too
+    pub fn with_defaults() -> Self {
+        let mut registry = ExtractorRegistry::default();
+        registry.register_generic(Box::new(JsonLdExtractor));
+        registry.register_generic(Box::new(MicrodataExtractor));
+        registry.register_generic(Box::new(HeuristicExtractor));
+        registry
+    }
+
+    /// Registers `extractor` for `host`, "www." is ignored so it covers both
+    pub fn register_site(&mut self, host: &str, extractor: Box<dyn RecipeExtractor>) {
+        self.sites
+            .entry(normalize_host(host).to_string())
+            .or_default()
+            .push(extractor);
+    }
+
+    pub fn register_generic(&mut self, extractor: Box<dyn RecipeExtractor>) {
+        self.generic.push(extractor);
+    }
+
+    /// Runs the extractors for `host` and the generic ones, keeps the result with the highest
+    /// confidence (trust * completeness) and fills its gaps from the others, best first
+    pub fn extract(&self, host: Option<&str>, html: &Html) -> Option<Extraction> {
+        let site = host
+            .and_then(|host| self.sites.get(normalize_host(host)))
+            .into_iter()
+            .flatten();
+
+        let mut results: Vec<(f32, &'static str, ExtractedRecipe)> = site
+            .chain(self.generic.iter())
+            .filter_map(|extractor| {
+                let recipe = extractor.extract(html)?;
+                let confidence = extractor.trust() * recipe.completeness();
+                Some((confidence, extractor.name(), recipe))
+            })
+            .filter(|(confidence, _, _)| *confidence > 0.0)
+            .collect();
+
+        // stable, so on a tie the earlier extractor (site specific first) wins
+        results.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut results = results.into_iter();
+        let (confidence, source, mut recipe) = results.next()?;
+        for (_, _, other) in results {
+            recipe.fill_missing(&other);
+        }
+
+        Some(Extraction {
+            recipe,
+            source,
+            confidence,
+        })
+    }
+}
+
+/// Registry used by `scrape_recipe_html`
+pub fn default_registry() -> &'static ExtractorRegistry {
+    static REGISTRY: OnceLock<ExtractorRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(ExtractorRegistry::with_defaults)
+}
+
+fn normalize_host(host: &str) -> &str {
+    host.strip_prefix("www.").unwrap_or(host)
+}
+
+#[cfg(test)]
+#[path = "../../_tests/utils_scraping_extractor.rs"]
+mod tests;
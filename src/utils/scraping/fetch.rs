@@ -0,0 +1,357 @@
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use reqwest::{
+    header::{
+        CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LOCATION, USER_AGENT,
+    },
+    redirect, Client, Response, StatusCode, Url,
+};
+
+use super::ScrapeError;
+
+/// User-Agent sent when fetching recipe pages, some sites block the reqwest default
+const ENV_SCRAPE_USER_AGENT: &str = "SCRAPE_USER_AGENT";
+const DEFAULT_USER_AGENT: &str = concat!("digital_cookbook/", env!("CARGO_PKG_VERSION"));
+
+/// Limits applied when fetching a page on behalf of a user
+#[derive(Clone, Debug)]
+pub struct FetchPolicy {
+    pub connect_timeout: Duration,
+    /// Whole request, from connecting to the last byte of the body
+    pub timeout: Duration,
+    pub max_bytes: usize,
+    pub max_redirects: usize,
+    pub user_agent: String,
+    /// Lets loopback and private addresses through. Tests only, this is what keeps
+    /// the scraper from being used to reach internal services
+    pub allow_private: bool,
+}
+
+impl Default for FetchPolicy {
+    fn default() -> Self {
+        FetchPolicy {
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(20),
+            max_bytes: 5 * 1024 * 1024,
+            max_redirects: 5,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            allow_private: false,
+        }
+    }
+}
+
+impl FetchPolicy {
+    /// Default limits, with the User-Agent from `SCRAPE_USER_AGENT` when set
+    pub fn from_env() -> Self {
+        let mut policy = FetchPolicy::default();
+        if let Ok(user_agent) = env::var(ENV_SCRAPE_USER_AGENT) {
+            if !user_agent.trim().is_empty() {
+                policy.user_agent = user_agent;
+            }
+        }
+        policy
+    }
+}
+
+/// Validators of a cached copy of a page, sent back so an unchanged page costs a 304
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Fetched {
+    Page {
+        /// Final url, after redirects
+        url: Url,
+        body: String,
+        validators: Validators,
+    },
+    /// The cached copy the validators came from is still current
+    NotModified,
+}
+
+/// Html of the page at `url`, following redirects by hand so every hop gets checked.
+/// Returns the final url along with the body.
+pub async fn fetch_html(url: &str, policy: &FetchPolicy) -> Result<(Url, String), ScrapeError> {
+    match fetch_html_if_modified(url, policy, None).await? {
+        Fetched::Page { url, body, .. } => Ok((url, body)),
+        // nothing was cached, so nothing to be unmodified against
+        Fetched::NotModified => Err(ScrapeError::FetchFailed(
+            url.to_string(),
+            "HTTP 304 Not Modified".into(),
+        )),
+    }
+}
+
+/// Same as `fetch_html`, as a conditional request when `cached` validators are given
+pub async fn fetch_html_if_modified(
+    url: &str,
+    policy: &FetchPolicy,
+    cached: Option<&Validators>,
+) -> Result<Fetched, ScrapeError> {
+    fetch(url, policy, cached, true).await
+}
+
+/// Body of any text document (robots.txt, sitemaps) with the same checks and limits as pages
+pub async fn fetch_text(url: &str, policy: &FetchPolicy) -> Result<(Url, String), ScrapeError> {
+    match fetch(url, policy, None, false).await? {
+        Fetched::Page { url, body, .. } => Ok((url, body)),
+        Fetched::NotModified => Err(ScrapeError::FetchFailed(
+            url.to_string(),
+            "HTTP 304 Not Modified".into(),
+        )),
+    }
+}
+
+async fn fetch(
+    url: &str,
+    policy: &FetchPolicy,
+    cached: Option<&Validators>,
+    html_only: bool,
+) -> Result<Fetched, ScrapeError> {
+    let mut page_url = parse_url(url)?;
+    let mut redirects = 0;
+
+    let response = loop {
+        let response = send(&page_url, policy, cached).await?;
+
+        if cached.is_some() && response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified);
+        }
+        if !response.status().is_redirection() {
+            break response;
+        }
+
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| {
+                ScrapeError::FetchFailed(url.to_string(), "redirect without a location".into())
+            })?;
+
+        redirects += 1;
+        if redirects > policy.max_redirects {
+            return Err(ScrapeError::TooManyRedirects(url.to_string()));
+        }
+
+        let next = page_url
+            .join(location)
+            .map_err(|_| ScrapeError::InvalidUrl(location.to_string()))?;
+        page_url = parse_url(next.as_str())?;
+    };
+
+    if !response.status().is_success() {
+        return Err(ScrapeError::FetchFailed(
+            url.to_string(),
+            format!("HTTP {}", response.status()),
+        ));
+    }
+
+    // pages without a content type get the benefit of the doubt
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("text/html")
+        .to_string();
+    if html_only && !content_type.contains("html") {
+        return Err(ScrapeError::NotHtml(url.to_string(), content_type));
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let validators = Validators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+
+    let body = read_body(url, response, policy.max_bytes).await?;
+
+    Ok(Fetched::Page {
+        url: page_url,
+        body,
+        validators,
+    })
+}
+
+/// Only absolute http(s) urls can be scraped
+pub(super) fn parse_url(url: &str) -> Result<Url, ScrapeError> {
+    let parsed = Url::parse(url).map_err(|_| ScrapeError::InvalidUrl(url.to_string()))?;
+
+    match parsed.scheme() {
+        "http" | "https" if parsed.host_str().is_some() => Ok(parsed),
+        _ => Err(ScrapeError::InvalidUrl(url.to_string())),
+    }
+}
+
+/// One request without following redirects. The host is resolved and checked here,
+/// then the client is pinned to those addresses so a second lookup can't swap them.
+async fn send(
+    url: &Url,
+    policy: &FetchPolicy,
+    cached: Option<&Validators>,
+) -> Result<Response, ScrapeError> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| ScrapeError::InvalidUrl(url.to_string()))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.trim_matches(['[', ']']), port))
+        .await
+        .map_err(|ex| ScrapeError::FetchFailed(url.to_string(), ex.to_string()))?
+        .collect();
+
+    if addrs.is_empty() {
+        return Err(ScrapeError::FetchFailed(
+            url.to_string(),
+            "host has no address".into(),
+        ));
+    }
+    if !policy.allow_private && addrs.iter().any(|addr| is_blocked_ip(addr.ip())) {
+        return Err(ScrapeError::BlockedAddress(url.to_string()));
+    }
+
+    let client = Client::builder()
+        .redirect(redirect::Policy::none())
+        .connect_timeout(policy.connect_timeout)
+        .timeout(policy.timeout)
+        .resolve_to_addrs(host, &addrs)
+        // A proxy from HTTP(S)_PROXY would connect in our place, past the pinned addresses
+        .no_proxy()
+        .build()
+        .map_err(|ex| ScrapeError::FetchFailed(url.to_string(), ex.to_string()))?;
+
+    let mut request = client
+        .get(url.clone())
+        .header(USER_AGENT, &policy.user_agent);
+    if let Some(cached) = cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    request
+        .send()
+        .await
+        .map_err(|ex| fetch_error(url.as_str(), ex))
+}
+
+/// Reads the body a chunk at a time, giving up as soon as it grows past `max_bytes`
+async fn read_body(
+    url: &str,
+    mut response: Response,
+    max_bytes: usize,
+) -> Result<String, ScrapeError> {
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_bytes as u64)
+    {
+        return Err(ScrapeError::TooLarge(url.to_string(), max_bytes));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|ex| fetch_error(url, ex))? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(ScrapeError::TooLarge(url.to_string(), max_bytes));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+fn fetch_error(url: &str, ex: reqwest::Error) -> ScrapeError {
+    if ex.is_timeout() {
+        ScrapeError::Timeout(url.to_string())
+    } else {
+        ScrapeError::FetchFailed(url.to_string(), ex.to_string())
+    }
+}
+
+/// Loopback, private, link-local (cloud metadata lives at 169.254.169.254), shared,
+/// multicast and reserved addresses, nothing a recipe site should resolve to
+pub fn is_blocked_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_blocked_ipv4(ip),
+        IpAddr::V6(ip) => match embedded_ipv4(ip) {
+            Some(ip) => is_blocked_ipv4(ip),
+            None => is_blocked_ipv6(ip),
+        },
+    }
+}
+
+/// IPv4 address an IPv6 one reaches: IPv4-mapped `::ffff:a.b.c.d`, IPv4-compatible `::a.b.c.d`,
+/// NAT64 `64:ff9b::a.b.c.d` and 6to4 `2002:aabb:ccdd::`
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let [s0, s1, s2, s3, s4, s5, s6, s7] = ip.segments();
+    let ipv4 = |high: u16, low: u16| Ipv4Addr::from(((high as u32) << 16) | low as u32);
+
+    if let Some(ip) = ip.to_ipv4_mapped() {
+        return Some(ip);
+    }
+    match (s0, s1) {
+        // `::` and `::1` stay with the IPv6 checks
+        (0, 0) if [s2, s3, s4, s5].iter().all(|&s| s == 0) && (s6 != 0 || s7 > 1) => {
+            Some(ipv4(s6, s7))
+        }
+        (0x0064, 0xff9b) if [s2, s3, s4, s5].iter().all(|&s| s == 0) => Some(ipv4(s6, s7)),
+        (0x2002, _) => Some(ipv4(s1, s2)),
+        _ => None,
+    }
+}
+
+fn is_blocked_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        // shared address space (carrier-grade NAT), 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b))
+        // IETF protocol assignments, 192.0.0.0/24
+        || (a == 192 && b == 0 && c == 0)
+        // benchmarking, 198.18.0.0/15
+        || (a == 198 && (18..20).contains(&b))
+        // reserved, 240.0.0.0/4
+        || a >= 240
+}
+
+fn is_blocked_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // unique local, fc00::/7 (AWS metadata is fd00:ec2::254)
+        || (first & 0xfe00) == 0xfc00
+        // link-local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+        // documentation, 2001:db8::/32
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8)
+        // local-use NAT64, 64:ff9b:1::/48, translates into the operator's own network
+        || (first == 0x0064 && ip.segments()[1] == 0xff9b && ip.segments()[2] == 0x0001)
+}
+
+#[cfg(test)]
+#[path = "../../_tests/utils_scraping_fetch.rs"]
+mod tests;
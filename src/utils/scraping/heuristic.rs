@@ -0,0 +1,166 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+
+use super::extractor::{ExtractedRecipe, RecipeExtractor};
+use crate::utils::duration::parse_minutes;
+use crate::utils::ingredient::normalize_fractions;
+
+/// og:meta tags for the title, image and site, lists under divs whose class mentions
+/// "ingredient" or "step"/"instruction"/"prep", and "Prep Time:" style labels
+pub struct HeuristicExtractor;
+
+impl RecipeExtractor for HeuristicExtractor {
+    fn name(&self) -> &'static str {
+        "heuristic"
+    }
+
+    fn extract(&self, html: &Html) -> Option<ExtractedRecipe> {
+        Some(extract_heuristic_recipe(html))
+    }
+
+    /// Class names are guesses, structured data wins on equal completeness
+    fn trust(&self) -> f32 {
+        0.6
+    }
+}
+
+pub fn extract_heuristic_recipe(html: &Html) -> ExtractedRecipe {
+    // SCRAPE SELECTORS
+    let meta_selector = Selector::parse("meta").unwrap();
+    let div_selector = Selector::parse("div").unwrap();
+    let span_selector = Selector::parse("span").unwrap();
+
+    let mut recipe = ExtractedRecipe::default();
+
+    for element in html.select(&meta_selector) {
+        let element_content = element.value().attr("content").unwrap_or("");
+        if element_content.is_empty() {
+            continue;
+        }
+
+        // Grabs meta data that website will include for search engine results
+        let field = match element.value().attr("property") {
+            Some("og:title") => &mut recipe.title,
+            Some("og:description") => &mut recipe.header,
+            Some("og:image") => &mut recipe.image_url,
+            Some("og:image:height") => &mut recipe.image_height,
+            Some("og:image:width") => &mut recipe.image_width,
+            Some("og:url") => &mut recipe.canonical_url,
+            Some("og:site_name") => &mut recipe.site_name,
+            _ => continue,
+        };
+        *field = Some(element_content.to_string());
+    }
+
+    // Grabbing steps and ingredients
+    for element in html.select(&div_selector) {
+        for class in element.value().classes() {
+            let class = class.to_lowercase();
+
+            // Looks through the children of the element if it (element) has a class that contains the string instruction
+            if recipe.steps.is_empty()
+                && (class.contains("step") | class.contains("instruction") | class.contains("prep"))
+            {
+                if let Some(list) = child_list_items(element) {
+                    recipe.steps = list
+                        .iter()
+                        .map(|child| child_text(child).replace('\n', ""))
+                        .collect();
+                }
+            }
+
+            // Grabbing the ingredients
+            if recipe.ingredients.is_empty() && class.contains("ingredient") {
+                if let Some(list) = child_list_items(element) {
+                    recipe.ingredients = list
+                        .iter()
+                        .map(|child| {
+                            // Keep unicode fractions (½) as ascii before dropping the rest
+                            normalize_fractions(&child_text(child).replace('\t', ""))
+                                .chars()
+                                .filter(|c| c.is_ascii())
+                                .collect::<String>()
+                                .trim()
+                                .to_owned()
+                        })
+                        .collect();
+                }
+            }
+        }
+    }
+
+    // Grabbing cook, prep, and total time values by searching all span elements
+    for element in html
+        .select(&span_selector)
+        .chain(html.select(&div_selector))
+    {
+        let el_text = element.text().collect::<Vec<_>>().join("");
+        let el_text = el_text.trim();
+        let label = el_text.strip_suffix(':').unwrap_or(el_text);
+
+        let (text, minutes) = if label.eq_ignore_ascii_case("prep time") {
+            (&mut recipe.prep_time, &mut recipe.prep_minutes)
+        } else if label.eq_ignore_ascii_case("cook time") {
+            (&mut recipe.cook_time, &mut recipe.cook_minutes)
+        } else if label.eq_ignore_ascii_case("total time") {
+            (&mut recipe.total_time, &mut recipe.total_minutes)
+        } else {
+            continue;
+        };
+
+        if text.is_none() {
+            if let Some(time) = scrape_recipe_time(element) {
+                *minutes = parse_minutes(&time);
+                *text = Some(time);
+            }
+        }
+    }
+
+    recipe
+}
+
+/// Takes in an html element reference and returns the text from within
+/// This is to be used on recipes to grab the prep time, cook time and total time
+pub fn scrape_recipe_time(element: ElementRef) -> Option<String> {
+    // Grab the sibling of the element
+    if let Some(sibling) = element.next_sibling() {
+        let mut time = String::new();
+        if let Some(sibling_element) = ElementRef::wrap(sibling) {
+            // Keep the page order, "1 hr 20 mins" rather than sorted "1 20 hr mins"
+            time = sibling_element
+                .text()
+                .flat_map(|s| s.split_whitespace())
+                .collect::<Vec<&str>>()
+                .join(" ");
+        }
+        return Some(time);
+    }
+    None
+}
+
+/// `li` items of the first `ul`/`ol` directly under `element`
+fn child_list_items(element: ElementRef) -> Option<Vec<ElementRef>> {
+    element
+        .children()
+        .filter_map(ElementRef::wrap)
+        .find(|child| matches!(child.value().name(), "ul" | "ol"))
+        .map(|list| {
+            list.children()
+                .filter_map(ElementRef::wrap)
+                .filter(|child| child.value().name() == "li")
+                .collect()
+        })
+}
+
+fn child_text(element: &ElementRef) -> String {
+    html_regex()
+        .replace(&element.text().collect::<Vec<_>>().join(""), "")
+        .to_string()
+}
+
+fn html_regex() -> &'static Regex {
+    static HTML: OnceLock<Regex> = OnceLock::new();
+    HTML.get_or_init(|| Regex::new(r"<[^>]*>").unwrap())
+}
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+use super::extractor::{ExtractedRecipe, RecipeExtractor};
+use crate::utils::duration::parse_minutes;
+use crate::utils::ingredient::normalize_fractions;
+
+/// Reads the schema.org `Recipe` most sites embed for search engines, see https://schema.org/Recipe
+pub struct JsonLdExtractor;
+
+impl RecipeExtractor for JsonLdExtractor {
+    fn name(&self) -> &'static str {
+        "json-ld"
+    }
+
+    fn extract(&self, html: &Html) -> Option<ExtractedRecipe> {
+        extract_json_ld_recipe(html)
+    }
+}
+
+/// First schema.org `Recipe` found in the page's `application/ld+json` scripts,
+/// looking through `@graph` wrappers, arrays and `mainEntity`
+pub fn extract_json_ld_recipe(html: &Html) -> Option<ExtractedRecipe> {
+    let selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+
+    html.select(&selector)
+        // some sites leave invalid json in these, skip those scripts
+        .filter_map(|script| serde_json::from_str::<Value>(&script.text().collect::<String>()).ok())
+        .find_map(|value| find_recipe_node(&value).map(recipe_from_node))
+}
+
+fn find_recipe_node(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(find_recipe_node),
+        Value::Object(object) => {
+            if is_type(value, "Recipe") {
+                return Some(value);
+            }
+            ["@graph", "mainEntity", "mainEntityOfPage"]
+                .iter()
+                .filter_map(|key| object.get(*key))
+                .find_map(find_recipe_node)
+        }
+        _ => None,
+    }
+}
+
+/// `@type` is either a string or an array of strings
+fn is_type(value: &Value, typ: &str) -> bool {
+    match &value["@type"] {
+        Value::String(t) => t == typ,
+        Value::Array(types) => types.iter().any(|t| t.as_str() == Some(typ)),
+        _ => false,
+    }
+}
+
+fn recipe_from_node(node: &Value) -> ExtractedRecipe {
+    let recipe_yield = first_text(&node["recipeYield"]);
+
+    ExtractedRecipe {
+        title: text(&node["name"]),
+        header: text(&node["description"]),
+        image_url: image_url(&node["image"]),
+        author: author(&node["author"]),
+        canonical_url: text(&node["url"]),
+        site_name: author(&node["publisher"]),
+        ingredients: texts(match &node["recipeIngredient"] {
+            Value::Null => &node["ingredients"],
+            ingredients => ingredients,
+        })
+        .into_iter()
+        .map(|ingredient| normalize_fractions(&ingredient))
+        .collect(),
+        steps: instructions(&node["recipeInstructions"]),
+        servings: recipe_yield.as_deref().and_then(leading_number),
+        recipe_yield,
+        prep_minutes: text(&node["prepTime"]).and_then(|t| parse_minutes(&t)),
+        cook_minutes: text(&node["cookTime"]).and_then(|t| parse_minutes(&t)),
+        total_minutes: text(&node["totalTime"]).and_then(|t| parse_minutes(&t)),
+        nutrition: nutrition(&node["nutrition"]),
+        ..Default::default()
+    }
+    .with_time_texts()
+}
+
+/// `recipeInstructions` comes as one string, a list of strings,
+/// `HowToStep`s or `HowToSection`s grouping more steps
+fn instructions(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(text) => text.lines().filter_map(clean_text).collect(),
+        Value::Array(items) => items.iter().flat_map(instructions).collect(),
+        Value::Object(object) => {
+            if let Some(items) = object.get("itemListElement") {
+                return instructions(items);
+            }
+            object
+                .get("text")
+                .or_else(|| object.get("name"))
+                .and_then(text)
+                .into_iter()
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// `image` is a url, an `ImageObject` or a list of either
+fn image_url(value: &Value) -> Option<String> {
+    match value {
+        Value::String(url) => Some(url.clone()),
+        Value::Array(items) => items.iter().find_map(image_url),
+        Value::Object(object) => object.get("url").and_then(image_url),
+        _ => None,
+    }
+}
+
+/// `author` (or `publisher`) is a name, a `Person`/`Organization` or a list of either
+fn author(value: &Value) -> Option<String> {
+    match value {
+        Value::String(_) => text(value),
+        Value::Array(items) => {
+            let names: Vec<String> = items.iter().filter_map(author).collect();
+            Some(names.join(", ")).filter(|names| !names.is_empty())
+        }
+        Value::Object(object) => object.get("name").and_then(text),
+        _ => None,
+    }
+}
+
+fn nutrition(value: &Value) -> BTreeMap<String, String> {
+    let Value::Object(object) = value else {
+        return BTreeMap::new();
+    };
+
+    object
+        .iter()
+        .filter(|(key, _)| !key.starts_with('@'))
+        .filter_map(|(key, value)| first_text(value).map(|value| (key.clone(), value)))
+        .collect()
+}
+
+/// "4 servings" -> 4, "Makes 12" -> 12
+pub(super) fn leading_number(text: &str) -> Option<i32> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .find(|part| !part.is_empty())
+        .and_then(|number| number.parse().ok())
+}
+
+fn first_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Array(items) => items.iter().find_map(first_text),
+        value => text(value),
+    }
+}
+
+fn texts(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().filter_map(text).collect(),
+        value => text(value).into_iter().collect(),
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => clean_text(text),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// JSON-LD strings often carry html entities and tags, keep only the text
+pub(super) fn clean_text(text: &str) -> Option<String> {
+    let fragment = Html::parse_fragment(text);
+    let text = fragment.root_element().text().collect::<String>();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    Some(text).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+#[path = "../../_tests/utils_scraping_json_ld.rs"]
+mod tests;
@@ -0,0 +1,161 @@
+use scraper::{ElementRef, Html, Selector};
+
+use super::extractor::{ExtractedRecipe, RecipeExtractor};
+use super::json_ld::{clean_text, leading_number};
+use crate::utils::duration::parse_minutes;
+use crate::utils::ingredient::normalize_fractions;
+
+/// Reads schema.org `Recipe` microdata, `itemscope`/`itemprop` attributes in the page markup
+pub struct MicrodataExtractor;
+
+impl RecipeExtractor for MicrodataExtractor {
+    fn name(&self) -> &'static str {
+        "microdata"
+    }
+
+    fn extract(&self, html: &Html) -> Option<ExtractedRecipe> {
+        extract_microdata_recipe(html)
+    }
+}
+
+pub fn extract_microdata_recipe(html: &Html) -> Option<ExtractedRecipe> {
+    let scope_selector = Selector::parse("[itemscope][itemtype]").unwrap();
+    let scope = html.select(&scope_selector).find(|element| {
+        element.value().attr("itemtype").is_some_and(|types| {
+            types
+                .split_whitespace()
+                .any(|typ| typ.trim_end_matches('/').ends_with("schema.org/Recipe"))
+        })
+    })?;
+
+    let properties = scope_properties(scope);
+    let first = |name: &str| {
+        properties
+            .iter()
+            .filter(|(prop, _)| prop == name)
+            .find_map(|(_, element)| property_value(*element))
+    };
+    let all = |names: &[&str]| -> Vec<ElementRef> {
+        properties
+            .iter()
+            .filter(|(prop, _)| names.contains(&prop.as_str()))
+            .map(|(_, element)| *element)
+            .collect()
+    };
+
+    let recipe_yield = first("recipeYield");
+
+    let recipe = ExtractedRecipe {
+        title: first("name"),
+        header: first("description"),
+        image_url: first("image"),
+        author: first("author"),
+        canonical_url: first("url"),
+        site_name: first("publisher"),
+        ingredients: all(&["recipeIngredient", "ingredients"])
+            .into_iter()
+            .filter_map(property_value)
+            .map(|ingredient| normalize_fractions(&ingredient))
+            .collect(),
+        steps: all(&["recipeInstructions"])
+            .into_iter()
+            .flat_map(instructions)
+            .collect(),
+        servings: recipe_yield.as_deref().and_then(leading_number),
+        recipe_yield,
+        prep_minutes: first("prepTime").and_then(|t| parse_minutes(&t)),
+        cook_minutes: first("cookTime").and_then(|t| parse_minutes(&t)),
+        total_minutes: first("totalTime").and_then(|t| parse_minutes(&t)),
+        nutrition: all(&["nutrition"])
+            .into_iter()
+            .flat_map(scope_properties)
+            .filter_map(|(name, element)| property_value(element).map(|value| (name, value)))
+            .collect(),
+        ..Default::default()
+    };
+
+    Some(recipe.with_time_texts())
+}
+
+/// `itemprop` elements of `scope` itself, leaving out those of items nested in it
+fn scope_properties(scope: ElementRef) -> Vec<(String, ElementRef)> {
+    let prop_selector = Selector::parse("[itemprop]").unwrap();
+
+    scope
+        .select(&prop_selector)
+        .filter(|element| nearest_scope(*element) == Some(scope))
+        .flat_map(|element| {
+            element
+                .value()
+                .attr("itemprop")
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(move |name| (name.to_string(), element))
+        })
+        .collect()
+}
+
+fn nearest_scope(element: ElementRef) -> Option<ElementRef> {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|ancestor| ancestor.value().attr("itemscope").is_some())
+}
+
+/// Value of a property as the microdata spec reads it, from `content`, `src`, `href`,
+/// `datetime` or the text. A nested item (an author `Person`) gives its name.
+fn property_value(element: ElementRef) -> Option<String> {
+    let value = element.value();
+
+    if value.attr("itemscope").is_some() {
+        return scope_properties(element)
+            .into_iter()
+            .filter(|(name, _)| name == "name" || name == "text")
+            .find_map(|(_, element)| property_value(element));
+    }
+
+    let attr = match value.name() {
+        "meta" => value.attr("content"),
+        "img" | "audio" | "video" | "source" => value.attr("src"),
+        "a" | "link" => value.attr("href"),
+        "time" => value.attr("datetime"),
+        "data" | "meter" => value.attr("value"),
+        _ => value.attr("content"),
+    };
+
+    match attr {
+        Some(attr) => clean_text(attr),
+        None => clean_text(&element.text().collect::<String>()),
+    }
+}
+
+/// A `recipeInstructions` property is a `HowToStep`/`HowToSection` item,
+/// a list of steps or one block of text
+fn instructions(element: ElementRef) -> Vec<String> {
+    if element.value().attr("itemscope").is_some() {
+        return scope_properties(element)
+            .into_iter()
+            .flat_map(|(name, element)| match name.as_str() {
+                "itemListElement" => instructions(element),
+                "text" => property_value(element).into_iter().collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+    }
+
+    let li_selector = Selector::parse("li").unwrap();
+    let items: Vec<String> = element
+        .select(&li_selector)
+        .filter_map(|li| clean_text(&li.text().collect::<String>()))
+        .collect();
+
+    if items.is_empty() {
+        property_value(element).into_iter().collect()
+    } else {
+        items
+    }
+}
+
+#[cfg(test)]
+#[path = "../../_tests/utils_scraping_microdata.rs"]
+mod tests;
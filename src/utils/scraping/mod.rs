@@ -0,0 +1,211 @@
+mod crawl;
+mod extractor;
+mod fetch;
+mod heuristic;
+mod json_ld;
+mod microdata;
+
+use std::collections::BTreeMap;
+
+use reqwest::Url;
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use thiserror::Error as ThisError;
+
+use super::convert::{convert_ingredient, convert_temperatures};
+use super::ingredient::{parse_ingredient, Ingredient};
+use super::units::UnitSystem;
+
+pub use crawl::{discover_site, Discovery, Robots, Sitemap};
+pub use extractor::{
+    default_registry, ExtractedRecipe, Extraction, ExtractorRegistry, RecipeExtractor,
+};
+pub use fetch::{
+    fetch_html, fetch_html_if_modified, fetch_text, is_blocked_ip, FetchPolicy, Fetched, Validators,
+};
+
+#[derive(ThisError, Debug)]
+pub enum ScrapeError {
+    #[error("Invalid recipe url - {0}")]
+    InvalidUrl(String),
+
+    #[error("Failed to fetch {0} - {1}")]
+    FetchFailed(String, String),
+
+    #[error("Not an html page - {0} is {1}")]
+    NotHtml(String, String),
+
+    #[error("No recipe found at {0}")]
+    NoRecipeFound(String),
+
+    #[error("Timed out fetching {0}")]
+    Timeout(String),
+
+    #[error("Not allowed to fetch {0} - it resolves to a private address")]
+    BlockedAddress(String),
+
+    #[error("Page at {0} is larger than {1} bytes")]
+    TooLarge(String, usize),
+
+    #[error("Too many redirects fetching {0}")]
+    TooManyRedirects(String),
+}
+
+/// A recipe preview scraped from a page, converts into a `RecipePatch`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScrapedRecipe {
+    pub url: String,
+    /// Name of the extractor the recipe came from, see `RecipeExtractor`
+    pub source: String,
+    pub confidence: f32,
+    pub title: Option<String>,
+    pub header: Option<String>,
+    pub image_url: Option<String>,
+    pub image_width: Option<String>,
+    pub image_height: Option<String>,
+    pub author: Option<String>,
+    /// Canonical url the page gives for the recipe, `url` when it gives none
+    pub source_url: String,
+    pub source_name: Option<String>,
+    #[serde(rename = "yield")]
+    pub recipe_yield: Option<String>,
+    pub servings: Option<i32>,
+    pub prep_time: Option<String>,
+    pub cook_time: Option<String>,
+    pub total_time: Option<String>,
+    pub prep_minutes: Option<i32>,
+    pub cook_minutes: Option<i32>,
+    pub total_minutes: Option<i32>,
+    pub nutrition: BTreeMap<String, String>,
+    pub ingredients: Vec<String>,
+    pub steps: Vec<String>,
+    pub parsed_ingredients: Vec<Ingredient>,
+    pub tags: Vec<String>,
+}
+
+impl ScrapedRecipe {
+    /// Ingredient amounts and step temperatures rewritten in `units`, when given
+    pub fn with_units(mut self, units: Option<UnitSystem>) -> Self {
+        let Some(system) = units else {
+            return self;
+        };
+
+        self.parsed_ingredients = self
+            .parsed_ingredients
+            .iter()
+            .map(|ingredient| convert_ingredient(ingredient, system))
+            .collect();
+        self.ingredients = self
+            .parsed_ingredients
+            .iter()
+            .map(|i| i.raw.clone())
+            .collect();
+        self.steps = self
+            .steps
+            .iter()
+            .map(|step| convert_temperatures(step, system))
+            .collect();
+        self
+    }
+}
+
+/// Recipe preview of an html page, from the extractors registered for its host
+/// and the generic JSON-LD, microdata and heuristic ones
+pub fn scrape_recipe_html(
+    url: &str,
+    response: &str,
+    units: Option<UnitSystem>,
+) -> Result<ScrapedRecipe, ScrapeError> {
+    let html = Html::parse_document(response);
+    let host = Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string));
+
+    let extraction = default_registry()
+        .extract(host.as_deref(), &html)
+        .ok_or_else(|| ScrapeError::NoRecipeFound(url.to_string()))?;
+    let recipe = extraction.recipe;
+
+    // Same fallback as stored recipes, total is prep + cook when the page has none
+    let total_minutes = match (
+        recipe.total_minutes,
+        recipe.prep_minutes,
+        recipe.cook_minutes,
+    ) {
+        (None, None, None) => None,
+        (None, prep, cook) => Some(prep.unwrap_or(0).saturating_add(cook.unwrap_or(0))),
+        (total, _, _) => total,
+    };
+
+    let parsed_ingredients: Vec<Ingredient> = recipe
+        .ingredients
+        .iter()
+        .map(|raw| parse_ingredient(raw))
+        .collect();
+
+    Ok(ScrapedRecipe {
+        url: url.to_string(),
+        source: extraction.source.to_string(),
+        confidence: extraction.confidence,
+        title: recipe.title,
+        header: recipe.header,
+        image_url: recipe.image_url,
+        image_width: recipe.image_width,
+        image_height: recipe.image_height,
+        author: recipe.author,
+        source_url: recipe
+            .canonical_url
+            .as_deref()
+            .and_then(|canonical| Url::parse(url).ok()?.join(canonical).ok())
+            .filter(|canonical| matches!(canonical.scheme(), "http" | "https"))
+            .map(|canonical| normalize_source_url(canonical.as_str()))
+            .unwrap_or_else(|| normalize_source_url(url)),
+        source_name: recipe.site_name,
+        recipe_yield: recipe.recipe_yield,
+        servings: recipe.servings,
+        prep_time: recipe.prep_time,
+        cook_time: recipe.cook_time,
+        total_time: recipe.total_time,
+        prep_minutes: recipe.prep_minutes,
+        cook_minutes: recipe.cook_minutes,
+        total_minutes,
+        nutrition: recipe.nutrition,
+        ingredients: recipe.ingredients,
+        steps: recipe.steps,
+        parsed_ingredients,
+        tags: Vec::new(),
+    }
+    .with_units(units))
+}
+
+/// Url a recipe is stored and looked up under, without the fragment,
+/// tracking parameters and trailing slash that vary between links to the same page
+pub fn normalize_source_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.trim().to_string();
+    };
+
+    parsed.set_fragment(None);
+
+    let query: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_"))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    if query.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(query);
+    }
+
+    let path = parsed.path().to_string();
+    if path.len() > 1 && path.ends_with('/') {
+        parsed.set_path(path.trim_end_matches('/'));
+    }
+
+    parsed.to_string()
+}
+
+#[cfg(test)]
+#[path = "../../_tests/utils_scraping.rs"]
+mod tests;
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dimension {
+    Volume,
+    Weight,
+}
+
+/// `?units=metric` or `?units=us` (`imperial` is accepted too)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[serde(alias = "imperial")]
+    Us,
+    Metric,
+}
+
+/// A measurable unit, `base` is its size in ml (volume) or g (weight)
+#[derive(Debug)]
+pub struct UnitDef {
+    pub name: &'static str,
+    pub dimension: Dimension,
+    pub system: UnitSystem,
+    pub base: f64,
+}
+
+/// Canonical unit names, as produced by `canonical_unit`
+const UNIT_DEFS: &[UnitDef] = &[
+    unit("tsp", Dimension::Volume, UnitSystem::Us, 4.928_92),
+    unit("tbsp", Dimension::Volume, UnitSystem::Us, 14.786_8),
+    unit("fl oz", Dimension::Volume, UnitSystem::Us, 29.573_5),
+    unit("cup", Dimension::Volume, UnitSystem::Us, 236.588),
+    unit("pint", Dimension::Volume, UnitSystem::Us, 473.176),
+    unit("quart", Dimension::Volume, UnitSystem::Us, 946.353),
+    unit("gallon", Dimension::Volume, UnitSystem::Us, 3_785.41),
+    unit("ml", Dimension::Volume, UnitSystem::Metric, 1.0),
+    unit("l", Dimension::Volume, UnitSystem::Metric, 1_000.0),
+    unit("oz", Dimension::Weight, UnitSystem::Us, 28.349_5),
+    unit("lb", Dimension::Weight, UnitSystem::Us, 453.592),
+    unit("mg", Dimension::Weight, UnitSystem::Metric, 0.001),
+    unit("g", Dimension::Weight, UnitSystem::Metric, 1.0),
+    unit("kg", Dimension::Weight, UnitSystem::Metric, 1_000.0),
+];
+
+const fn unit(name: &'static str, dimension: Dimension, system: UnitSystem, base: f64) -> UnitDef {
+    UnitDef {
+        name,
+        dimension,
+        system,
+        base,
+    }
+}
+
+/// (unit, smallest amount worth writing in it)
+type Ladder = &'static [(&'static str, f64)];
+
+/// Units amounts get promoted or demoted between, largest first
+const LADDERS: &[(Dimension, UnitSystem, Ladder)] = &[
+    (
+        Dimension::Volume,
+        UnitSystem::Us,
+        &[("cup", 0.25), ("tbsp", 1.0), ("tsp", 0.0)],
+    ),
+    (
+        Dimension::Weight,
+        UnitSystem::Us,
+        &[("lb", 1.0), ("oz", 0.0)],
+    ),
+    (
+        Dimension::Volume,
+        UnitSystem::Metric,
+        &[("l", 1.0), ("ml", 0.0)],
+    ),
+    (
+        Dimension::Weight,
+        UnitSystem::Metric,
+        &[("kg", 1.0), ("g", 0.0)],
+    ),
+];
+
+pub fn unit_def(name: &str) -> Option<&'static UnitDef> {
+    UNIT_DEFS.iter().find(|def| def.name == name)
+}
+
+/// Re-expresses `amount` of `unit` in the most readable unit of `system`,
+/// e.g. 48 tsp -> 1 cup, 1500 g -> 1.5 kg. Units without a ladder are kept as they are.
+pub fn best_unit(
+    amount: f64,
+    unit: &'static UnitDef,
+    system: UnitSystem,
+) -> (&'static UnitDef, f64) {
+    let Some((_, _, ladder)) = LADDERS.iter().find(|(dimension, ladder_system, _)| {
+        *dimension == unit.dimension && *ladder_system == system
+    }) else {
+        return (unit, amount);
+    };
+
+    // Same system but off the ladder (fl oz, pint...), leave it be
+    if unit.system == system && !ladder.iter().any(|(name, _)| *name == unit.name) {
+        return (unit, amount);
+    }
+
+    let base_amount = amount * unit.base;
+    for (name, min) in ladder.iter() {
+        if let Some(def) = unit_def(name) {
+            let converted = base_amount / def.base;
+            if converted >= *min {
+                return (def, converted);
+            }
+        }
+    }
+
+    (unit, amount)
+}
+
+/// Rounds to what a cook can measure: eighths and thirds for US units,
+/// whole numbers for g and ml, hundredths for kg and l
+pub fn round_quantity(value: f64, unit: Option<&str>) -> f64 {
+    match unit.and_then(unit_def) {
+        Some(def) if def.system == UnitSystem::Metric => {
+            if def.base >= 1_000.0 {
+                (value * 100.0).round() / 100.0
+            } else if value >= 10.0 {
+                value.round()
+            } else {
+                (value * 10.0).round() / 10.0
+            }
+        }
+        _ => round_to_fraction(value),
+    }
+}
+
+/// Nearest whole, eighth or third, never rounding a positive amount down to 0
+fn round_to_fraction(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+
+    let whole = value.trunc();
+    let fraction = value - whole;
+    let candidates = [
+        0.0,
+        0.125,
+        0.25,
+        1.0 / 3.0,
+        0.375,
+        0.5,
+        0.625,
+        2.0 / 3.0,
+        0.75,
+        0.875,
+        1.0,
+    ];
+
+    let nearest = candidates
+        .iter()
+        .copied()
+        .min_by(|a, b| (a - fraction).abs().total_cmp(&(b - fraction).abs()))
+        .unwrap_or(0.0);
+
+    let rounded = whole + nearest;
+    if rounded == 0.0 {
+        0.125
+    } else {
+        rounded
+    }
+}
+
+/// 1.5 -> "1 1/2", 0.333.. -> "1/3", 250.0 -> "250", 1.25 (kg) -> "1.25"
+pub fn format_quantity(value: f64, unit: Option<&str>) -> String {
+    if let Some(def) = unit.and_then(unit_def) {
+        if def.system == UnitSystem::Metric {
+            return format!("{}", value);
+        }
+    }
+
+    let whole = value.trunc();
+    let fraction = value - whole;
+    let fraction = [
+        (0.125, "1/8"),
+        (0.25, "1/4"),
+        (1.0 / 3.0, "1/3"),
+        (0.375, "3/8"),
+        (0.5, "1/2"),
+        (0.625, "5/8"),
+        (2.0 / 3.0, "2/3"),
+        (0.75, "3/4"),
+        (0.875, "7/8"),
+    ]
+    .iter()
+    .find(|(f, _)| (f - fraction).abs() < 0.01)
+    .map(|(_, text)| *text);
+
+    match (whole as i64, fraction) {
+        (0, Some(fraction)) => fraction.to_string(),
+        (whole, Some(fraction)) => format!("{} {}", whole, fraction),
+        (whole, None) if (value - whole as f64).abs() < 0.01 => whole.to_string(),
+        _ => format!("{:.2}", value)
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+#[path = "../_tests/utils_units.rs"]
+mod tests;
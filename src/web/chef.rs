@@ -8,11 +8,14 @@ use warp::{reply::Json, Filter};
 use crate::entities::prelude::Chef;
 use crate::{
     entities::{chef, recipe},
-    model::{ChefMac, ChefPatch},
+    model::{
+        ChefMac, ChefPatch, CookbookMac, CookbookOptions, ListOptions, ObsidianMac, Page,
+        RecipeView,
+    },
     security::UserCtx,
 };
 
-use super::{filter_auth::do_auth, filter_utils::with_db};
+use super::{filter_auth::do_auth, filter_utils::with_db, handle_rejection};
 
 pub fn chef_rest_filters(
     base_path: &'static str,
@@ -61,11 +64,58 @@ pub fn chef_rest_filters(
 
     let get_recipes = chefs_path
         .and(warp::get())
-        .and(common)
+        .and(common.clone())
         .and(warp::path("recipes"))
+        .and(warp::query::<ListOptions>())
         .and_then(chef_get_recipes);
 
-    get_recipes.or(create).or(update).or(delete).or(get)
+    // GET cookbook 'GET /chefs/cookbook.pdf?tags=easy&page_size=a4'
+    // Errors are answered here, a rejection would fall through to `get`
+    let cookbook_pdf = chefs_path
+        .and(warp::path("cookbook.pdf"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(
+            common
+                .clone()
+                .and(warp::query::<CookbookOptions>())
+                .and_then(chef_cookbook_pdf)
+                .recover(handle_rejection),
+        );
+
+    // GET cookbook 'GET /chefs/cookbook.epub?tags=easy'
+    let cookbook_epub = chefs_path
+        .and(warp::path("cookbook.epub"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(
+            common
+                .clone()
+                .and(warp::query::<CookbookOptions>())
+                .and_then(chef_cookbook_epub)
+                .recover(handle_rejection),
+        );
+
+    // GET vault 'GET /chefs/vault.zip?tags=easy', one Obsidian note per recipe
+    let vault = chefs_path
+        .and(warp::path("vault.zip"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(
+            common
+                .and(warp::query::<CookbookOptions>())
+                .and_then(chef_vault)
+                .recover(handle_rejection),
+        );
+
+    cookbook_pdf
+        .or(cookbook_epub)
+        .or(vault)
+        .or(get_recipes)
+        .or(create)
+        .or(update)
+        .or(delete)
+        .or(get)
 
     // endregion: api paths
 }
@@ -112,10 +162,53 @@ async fn chef_update(
 async fn chef_get_recipes(
     db: Arc<DatabaseConnection>,
     utx: UserCtx,
+    options: ListOptions,
 ) -> Result<Json, warp::Rejection> {
-    let recipes: Vec<recipe::Model> = ChefMac::get_recipes(&db, utx.user_id).await?;
+    let recipes: Page<recipe::Model> = ChefMac::get_recipes(&db, utx.user_id, options).await?;
+
+    json_response(recipes.map(RecipeView::from))
+}
+
+async fn chef_cookbook_pdf(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    options: CookbookOptions,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let pdf: Vec<u8> = CookbookMac::pdf(&db, &utx, &options).await?;
+
+    Ok(warp::reply::with_header(
+        warp::reply::with_header(pdf, "Content-Type", "application/pdf"),
+        "Content-Disposition",
+        "attachment; filename=\"cookbook.pdf\"",
+    ))
+}
+
+async fn chef_cookbook_epub(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    options: CookbookOptions,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let epub: Vec<u8> = CookbookMac::epub(&db, &utx, &options).await?;
+
+    Ok(warp::reply::with_header(
+        warp::reply::with_header(epub, "Content-Type", "application/epub+zip"),
+        "Content-Disposition",
+        "attachment; filename=\"cookbook.epub\"",
+    ))
+}
 
-    json_response(recipes)
+async fn chef_vault(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    options: CookbookOptions,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let vault: Vec<u8> = ObsidianMac::export(&db, &utx, &options).await?;
+
+    Ok(warp::reply::with_header(
+        warp::reply::with_header(vault, "Content-Type", "application/zip"),
+        "Content-Disposition",
+        "attachment; filename=\"recipes.zip\"",
+    ))
 }
 
 // endregion: Chef API functions
@@ -3,9 +3,18 @@ use std::{convert::Infallible, path::Path, sync::Arc};
 use sea_orm::DatabaseConnection;
 
 use serde_json::json;
-use warp::{reject::MethodNotAllowed, Filter, Rejection, Reply};
+use warp::{
+    body::BodyDeserializeError,
+    http::StatusCode,
+    reject::{InvalidQuery, MethodNotAllowed},
+    Filter, Rejection, Reply,
+};
 
-use crate::{model, security, web::chef::chef_rest_filters};
+use crate::{
+    model, security,
+    utils::{ArchiveError, ScrapeError},
+    web::chef::chef_rest_filters,
+};
 
 use self::recipe::recipe_rest_filters;
 
@@ -54,35 +63,41 @@ pub async fn start_web(
 }
 
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
-    let mut code = 405;
-    let message;
-
-    if err.is_not_found() {
-        code = 404;
-        message = "NOT FOUND";
-    } else if let Some(e) = err.find::<WebErrorMessage>() {
-        code = warp::http::StatusCode::BAD_REQUEST.into();
-        message = &e.message;
+    let (status, code, message) = if let Some(e) = err.find::<WebErrorMessage>() {
+        (e.status, e.typ, e.message.clone())
+    } else if err.is_not_found() {
+        (StatusCode::NOT_FOUND, "NOT_FOUND", "Not found".to_string())
+    } else if let Some(e) = err.find::<BodyDeserializeError>() {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "INVALID_BODY",
+            e.to_string(),
+        )
+    } else if let Some(e) = err.find::<InvalidQuery>() {
+        (StatusCode::BAD_REQUEST, "INVALID_QUERY", e.to_string())
+    } else if err.find::<MethodNotAllowed>().is_some() {
+        (
+            StatusCode::METHOD_NOT_ALLOWED,
+            "METHOD_NOT_ALLOWED",
+            "Method not allowed".to_string(),
+        )
     } else {
-        message = "Unable to parse error"
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "UNHANDLED_REJECTION",
+            "Unhandled rejection".to_string(),
+        )
     };
+
     // Print to server side
     println!("Error - {:?}", err);
 
     // TODO - Call log api for capture and store
     // Build user message
-    let user_message = match err.find::<WebErrorMessage>() {
-        Some(err) => err.typ.to_string(),
-        None => "Unhandled rejection".to_string(),
-    };
-
-    let result = json!({ "error": user_message });
+    let result = json!({ "error": { "code": code, "message": message } });
     let result = warp::reply::json(&result);
 
-    Ok(warp::reply::with_status(
-        result,
-        warp::http::StatusCode::BAD_REQUEST,
-    ))
+    Ok(warp::reply::with_status(result, status))
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -95,8 +110,10 @@ pub enum Error {
 }
 
 // region: Warp Custom Error
+/// Rejection carrying the http status, a stable machine-readable code (`typ`) and a human message
 #[derive(Debug)]
 pub struct WebErrorMessage {
+    pub status: StatusCode,
     pub typ: &'static str,
     pub message: String,
 }
@@ -104,26 +121,146 @@ pub struct WebErrorMessage {
 impl warp::reject::Reject for WebErrorMessage {}
 
 impl WebErrorMessage {
-    pub fn rejection(typ: &'static str, message: String) -> warp::Rejection {
-        warp::reject::custom(WebErrorMessage { typ, message })
+    pub fn rejection(status: StatusCode, typ: &'static str, message: String) -> warp::Rejection {
+        warp::reject::custom(WebErrorMessage {
+            status,
+            typ,
+            message,
+        })
     }
 }
 
 impl From<self::Error> for warp::Rejection {
     fn from(other: self::Error) -> Self {
-        WebErrorMessage::rejection("web::Error", format!("{:?}", other))
+        let (status, typ) = match other {
+            Error::FailAuthMissingXAuth => (StatusCode::UNAUTHORIZED, "AUTH_MISSING_TOKEN"),
+            Error::FailStartWebFolderNotFound(_) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR")
+            }
+        };
+
+        WebErrorMessage::rejection(status, typ, other.to_string())
     }
 }
 
 impl From<model::Error> for warp::Rejection {
     fn from(other: model::Error) -> Self {
-        WebErrorMessage::rejection("web::Error", format!("{:?}", other))
+        match other {
+            model::Error::EntityNotFound(..) => WebErrorMessage::rejection(
+                StatusCode::NOT_FOUND,
+                "ENTITY_NOT_FOUND",
+                other.to_string(),
+            ),
+            model::Error::EntityAlreadyExists => WebErrorMessage::rejection(
+                StatusCode::CONFLICT,
+                "ENTITY_ALREADY_EXISTS",
+                other.to_string(),
+            ),
+            model::Error::InvalidListOption(_) | model::Error::InvalidScale(_) => {
+                WebErrorMessage::rejection(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_QUERY",
+                    other.to_string(),
+                )
+            }
+            model::Error::InvalidRecipe(_) => WebErrorMessage::rejection(
+                StatusCode::BAD_REQUEST,
+                "INVALID_RECIPE",
+                other.to_string(),
+            ),
+            model::Error::InvalidImport(_) => WebErrorMessage::rejection(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "INVALID_IMPORT",
+                other.to_string(),
+            ),
+            model::Error::TooManyImportJobs(_) => WebErrorMessage::rejection(
+                StatusCode::TOO_MANY_REQUESTS,
+                "TOO_MANY_IMPORT_JOBS",
+                other.to_string(),
+            ),
+            model::Error::AccessDenied(_) => WebErrorMessage::rejection(
+                StatusCode::FORBIDDEN,
+                "ACCESS_DENIED",
+                other.to_string(),
+            ),
+            model::Error::ScrapeErr(ex) => ex.into(),
+            model::Error::ArchiveErr(ex) => ex.into(),
+            // Keep database and io details server side
+            model::Error::SeaOrmErr(_) | model::Error::SqlxErr(_) => {
+                println!("ERROR - model - {:?}", other);
+                WebErrorMessage::rejection(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "DATABASE_ERROR",
+                    "Database error".to_string(),
+                )
+            }
+            model::Error::IOErr(_)
+            | model::Error::InvalidMigration(_)
+            | model::Error::MigrationChecksumMismatch(_)
+            | model::Error::MigrationMissing(_) => {
+                println!("ERROR - model - {:?}", other);
+                WebErrorMessage::rejection(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "INTERNAL_ERROR",
+                    "Internal error".to_string(),
+                )
+            }
+        }
     }
 }
 
 impl From<security::Error> for warp::Rejection {
     fn from(other: security::Error) -> Self {
-        WebErrorMessage::rejection("web::Error", format!("{:?}", other))
+        match other {
+            security::Error::InvalidToken(_) => WebErrorMessage::rejection(
+                StatusCode::UNAUTHORIZED,
+                "AUTH_INVALID_TOKEN",
+                other.to_string(),
+            ),
+            security::Error::FailLoadKeySet(_) | security::Error::FailMissingEnv(_) => {
+                println!("ERROR - security - {:?}", other);
+                WebErrorMessage::rejection(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "AUTH_UNAVAILABLE",
+                    "Authentication is unavailable".to_string(),
+                )
+            }
+        }
+    }
+}
+
+impl From<ScrapeError> for warp::Rejection {
+    fn from(other: ScrapeError) -> Self {
+        let (status, typ) = match other {
+            ScrapeError::InvalidUrl(_) => (StatusCode::BAD_REQUEST, "SCRAPE_INVALID_URL"),
+            ScrapeError::FetchFailed(..) => (StatusCode::BAD_GATEWAY, "SCRAPE_FETCH_FAILED"),
+            ScrapeError::NotHtml(..) => (StatusCode::UNPROCESSABLE_ENTITY, "SCRAPE_NOT_HTML"),
+            ScrapeError::NoRecipeFound(_) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "SCRAPE_NO_RECIPE_FOUND")
+            }
+            ScrapeError::Timeout(_) => (StatusCode::GATEWAY_TIMEOUT, "SCRAPE_TIMEOUT"),
+            ScrapeError::BlockedAddress(_) => (StatusCode::FORBIDDEN, "SCRAPE_BLOCKED_ADDRESS"),
+            ScrapeError::TooLarge(..) => (StatusCode::UNPROCESSABLE_ENTITY, "SCRAPE_TOO_LARGE"),
+            ScrapeError::TooManyRedirects(_) => {
+                (StatusCode::BAD_GATEWAY, "SCRAPE_TOO_MANY_REDIRECTS")
+            }
+        };
+
+        WebErrorMessage::rejection(status, typ, other.to_string())
+    }
+}
+
+impl From<ArchiveError> for warp::Rejection {
+    fn from(other: ArchiveError) -> Self {
+        let typ = match other {
+            ArchiveError::InvalidArchive(_) => "ARCHIVE_INVALID",
+            ArchiveError::UnknownFormat => "ARCHIVE_UNKNOWN_FORMAT",
+            ArchiveError::TooLarge(..) | ArchiveError::TooLargeUnpacked(_) => "ARCHIVE_TOO_LARGE",
+            ArchiveError::TooManyRecipes(_) => "ARCHIVE_TOO_MANY_RECIPES",
+            ArchiveError::TooManyFiles(_) => "ARCHIVE_TOO_MANY_FILES",
+        };
+
+        WebErrorMessage::rejection(StatusCode::UNPROCESSABLE_ENTITY, typ, other.to_string())
     }
 }
 // endregion: Warp Custom Error
@@ -1,4 +1,6 @@
-use std::{collections::HashSet, ops::Deref, str::from_utf8, sync::Arc};
+use std::{
+    collections::HashSet, future::Future, ops::Deref, str::from_utf8, sync::Arc, time::Duration,
+};
 
 use regex::Regex;
 use reqwest::get;
@@ -10,13 +12,24 @@ use warp::{reply::Json, Filter};
 
 use crate::{
     entities::recipe,
-    model::{RecipeMac, RecipePatch},
+    model::{
+        ArchiveImportOptions, BatchImportRequest, CooklangImportOptions, CrawlRequest,
+        ImportJobMac, ImportJobView, ImportRequest, ListOptions, ObsidianMac, RecipeMac, RecipePatch, RecipeView,
+        ScaleOptions, ScrapeCache, SearchOptions, ViewOptions,
+    },
     security::UserCtx,
-    utils::scrape_recipe_content,
+    utils::{data_url_image, read_archive, read_vault, CooklangRecipe, ScrapeError},
 };
 
 use super::{filter_auth::do_auth, filter_utils::with_db};
 
+/// Pause between two pages of a crawl, longer when the site's robots.txt asks for it
+const CRAWL_DELAY: Duration = Duration::from_secs(1);
+/// Largest export archive accepted, photos included
+const MAX_ARCHIVE_BYTES: u64 = 64 * 1024 * 1024;
+/// Largest `.cook` file accepted
+const MAX_COOKLANG_BYTES: u64 = 1024 * 1024;
+
 pub fn recipe_rest_filters(
     base_path: &'static str,
     db: Arc<DatabaseConnection>,
@@ -30,20 +43,125 @@ pub fn recipe_rest_filters(
         .and(warp::get())
         .and(warp::path::end())
         .and(common.clone())
+        .and(warp::query::<ListOptions>())
         .and_then(recipe_list);
 
     let get = recipes_path
         .and(warp::get())
         .and(common.clone())
         .and(warp::path::param::<i64>())
+        .and(warp::path::end())
+        .and(warp::query::<ViewOptions>())
         .and_then(recipe_get);
 
+    // COOKLANG export 'GET recipes/{id}.cook'
+    let cooklang = recipes_path
+        .and(warp::get())
+        .and(common.clone())
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and_then(recipe_cooklang);
+
+    // SCALED recipe 'GET recipes/{id}/scaled?servings=N' or '?factor=F'
+    let scaled = recipes_path
+        .and(warp::get())
+        .and(common.clone())
+        .and(warp::path::param::<i64>())
+        .and(warp::path("scaled"))
+        .and(warp::path::end())
+        .and(warp::query::<ScaleOptions>())
+        .and_then(recipe_scaled);
+
+    // IMAGE 'GET recipes/{id}/image', the photo an archive import stored with the recipe
+    let image = recipes_path
+        .and(warp::get())
+        .and(common.clone())
+        .and(warp::path::param::<i64>())
+        .and(warp::path("image"))
+        .and(warp::path::end())
+        .and_then(recipe_image);
+
     let create = recipes_path
         .and(warp::post())
+        .and(warp::path::end())
         .and(common.clone())
         .and(warp::body::json::<RecipePatch>())
         .and_then(recipe_create);
 
+    // IMPORT recipe 'POST recipes/import', scrapes the url and saves the recipe
+    let import = recipes_path
+        .and(warp::post())
+        .and(warp::path("import"))
+        .and(warp::path::end())
+        .and(common.clone())
+        .and(warp::body::json::<ImportRequest>())
+        .and_then(recipe_import);
+
+    // BATCH IMPORT 'POST recipes/import/batch', runs in the background and returns the job
+    let import_batch = recipes_path
+        .and(warp::post())
+        .and(warp::path("import"))
+        .and(warp::path("batch"))
+        .and(warp::path::end())
+        .and(common.clone())
+        .and(warp::body::json::<BatchImportRequest>())
+        .and_then(recipe_import_batch);
+
+    // CRAWL IMPORT 'POST recipes/import/crawl', imports a site from its sitemaps in the background
+    let import_crawl = recipes_path
+        .and(warp::post())
+        .and(warp::path("import"))
+        .and(warp::path("crawl"))
+        .and(warp::path::end())
+        .and(common.clone())
+        .and(warp::body::json::<CrawlRequest>())
+        .and_then(recipe_import_crawl);
+
+    // ARCHIVE IMPORT 'POST recipes/import/archive?format=paprika|mealie|tandoor', the export as body
+    let import_archive = recipes_path
+        .and(warp::post())
+        .and(warp::path("import"))
+        .and(warp::path("archive"))
+        .and(warp::path::end())
+        .and(common.clone())
+        .and(warp::query::<ArchiveImportOptions>())
+        .and(warp::body::content_length_limit(MAX_ARCHIVE_BYTES))
+        .and(warp::body::bytes())
+        .and_then(recipe_import_archive);
+
+    // COOKLANG IMPORT 'POST recipes/import/cooklang?title=...', the .cook file as body
+    let import_cooklang = recipes_path
+        .and(warp::post())
+        .and(warp::path("import"))
+        .and(warp::path("cooklang"))
+        .and(warp::path::end())
+        .and(common.clone())
+        .and(warp::query::<CooklangImportOptions>())
+        .and(warp::body::content_length_limit(MAX_COOKLANG_BYTES))
+        .and(warp::body::bytes())
+        .and_then(recipe_import_cooklang);
+
+    // OBSIDIAN IMPORT 'POST recipes/import/obsidian', a zipped vault folder or one note as body
+    let import_obsidian = recipes_path
+        .and(warp::post())
+        .and(warp::path("import"))
+        .and(warp::path("obsidian"))
+        .and(warp::path::end())
+        .and(common.clone())
+        .and(warp::body::content_length_limit(MAX_ARCHIVE_BYTES))
+        .and(warp::body::bytes())
+        .and_then(recipe_import_obsidian);
+
+    // IMPORT JOB report 'GET recipes/import/batch/{id}', crawls and archives included
+    let import_job = recipes_path
+        .and(warp::get())
+        .and(warp::path("import"))
+        .and(warp::path("batch"))
+        .and(common.clone())
+        .and(warp::path::param::<i64>())
+        .and(warp::path::end())
+        .and_then(recipe_import_job);
+
     let update = recipes_path
         .and(warp::patch())
         .and(common.clone())
@@ -59,50 +177,239 @@ pub fn recipe_rest_filters(
 
     let get_by_tag = recipes_path
         .and(warp::get())
-        .and(common)
+        .and(common.clone())
         .and(warp::path("tags"))
         .and(warp::path::param::<String>())
+        .and(warp::query::<ListOptions>())
         .and_then(recipe_get_by_tag);
 
+    let search = recipes_path
+        .and(warp::get())
+        .and(common.clone())
+        .and(warp::path("search"))
+        .and(warp::path::end())
+        .and(warp::query::<SearchOptions>())
+        .and_then(recipe_search);
+
     let scrape_url = recipes_path
         .and(warp::get())
-        // .and(common.clone())
+        .and(common)
         .and(warp::path("scrape"))
         .and(warp::path::param::<String>())
+        .and(warp::query::<ViewOptions>())
         .and_then(scrape_recipe);
 
     list.or(get)
+        .or(scaled)
+        .or(image)
         .or(create)
+        .or(import)
+        .or(import_batch)
+        .or(import_crawl)
+        .or(import_archive)
+        .or(import_cooklang)
+        .or(import_obsidian)
+        .or(cooklang)
+        .or(import_job)
         .or(delete)
         .or(update)
         .or(get_by_tag)
+        .or(search)
         .or(scrape_url)
 }
 
-async fn recipe_list(db: Arc<DatabaseConnection>, utx: UserCtx) -> Result<Json, warp::Rejection> {
-    // FIXME: Add proper error handling
-    let recipes = RecipeMac::list(&db).await?;
+async fn recipe_list(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    options: ListOptions,
+) -> Result<Json, warp::Rejection> {
+    let recipes = RecipeMac::list(&db, utx, options).await?;
 
-    json_response(recipes)
+    json_response(recipes.map(RecipeView::from))
 }
 
 async fn recipe_get(
     db: Arc<DatabaseConnection>,
     utx: UserCtx,
     id: i64,
+    options: ViewOptions,
 ) -> Result<Json, warp::Rejection> {
-    let recipe = RecipeMac::get(&db, id).await?;
+    let recipe = RecipeMac::get(&db, utx, id).await?;
+
+    json_response(RecipeView::from(recipe).with_options(&options))
+}
+
+/// `file` is "{id}.cook", other names are left to the other routes
+async fn recipe_cooklang(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    file: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let Some(id) = file
+        .strip_suffix(".cook")
+        .and_then(|id| id.parse::<i64>().ok())
+    else {
+        return Err(warp::reject::not_found());
+    };
+    let recipe = RecipeMac::get(&db, utx, id).await?;
+    let text = CooklangRecipe::from(&recipe).to_string();
+
+    Ok(warp::reply::with_header(
+        text,
+        "Content-Type",
+        "text/plain; charset=utf-8",
+    ))
+}
+
+async fn recipe_scaled(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    id: i64,
+    options: ScaleOptions,
+) -> Result<Json, warp::Rejection> {
+    let recipe = RecipeMac::scaled(&db, utx, id, options).await?;
 
     json_response(recipe)
 }
 
+/// Bytes of a photo stored inline, photos stored as links have no image here
+async fn recipe_image(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    id: i64,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let recipe = RecipeMac::get(&db, utx, id).await?;
+    let Some((media_type, bytes)) = recipe.image_url.as_deref().and_then(data_url_image) else {
+        return Err(warp::reject::not_found());
+    };
+
+    Ok(warp::reply::with_header(bytes, "Content-Type", media_type))
+}
+
 async fn recipe_create(
     db: Arc<DatabaseConnection>,
     utx: UserCtx,
     patch: RecipePatch,
 ) -> Result<Json, warp::Rejection> {
     let recipe = RecipeMac::create(&db, patch, utx).await?;
-    json_response(recipe)
+    json_response(RecipeView::from(recipe))
+}
+
+async fn recipe_import(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    request: ImportRequest,
+) -> Result<Json, warp::Rejection> {
+    // Already imported, no need to fetch the page again
+    if let Some(recipe) = RecipeMac::find_by_source_url(&db, &utx, &request.url).await? {
+        return json_response(RecipeView::from(recipe));
+    }
+
+    let scraped = ScrapeCache::from_env()
+        .scrape(&db, &request.url, None)
+        .await?;
+    let imported = RecipeMac::import(&db, utx, scraped, request).await?;
+
+    json_response(RecipeView::from(imported.recipe))
+}
+
+async fn recipe_import_batch(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    request: BatchImportRequest,
+) -> Result<Json, warp::Rejection> {
+    let job = ImportJobMac::create_batch(&db, &utx, &request).await?;
+
+    let cache = ScrapeCache::from_env();
+    let run = ImportJobMac::run_batch(db.clone(), utx, job.clone(), request.tags, cache);
+    spawn_job(db, job.id, run);
+
+    json_response(job)
+}
+
+async fn recipe_import_crawl(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    request: CrawlRequest,
+) -> Result<Json, warp::Rejection> {
+    let job = ImportJobMac::create_crawl(&db, &utx, &request).await?;
+
+    let cache = ScrapeCache::from_env();
+    let run = ImportJobMac::run_crawl(db.clone(), utx, job.clone(), request, cache, CRAWL_DELAY);
+    spawn_job(db, job.id, run);
+
+    json_response(job)
+}
+
+async fn recipe_import_archive(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    options: ArchiveImportOptions,
+    body: warp::hyper::body::Bytes,
+) -> Result<Json, warp::Rejection> {
+    // unzipping is cpu bound, keep it off the async workers
+    let entries = tokio::task::spawn_blocking(move || read_archive(&body, options.format))
+        .await
+        .map_err(|ex| crate::model::Error::IOErr(ex.into()))??;
+    let job = ImportJobMac::create_archive(&db, &utx, &entries).await?;
+
+    let run = ImportJobMac::run_archive(db.clone(), utx, job.clone(), entries);
+    spawn_job(db, job.id, run);
+
+    json_response(job)
+}
+
+/// Runs an import job in the background, a job that errors or panics is marked failed
+fn spawn_job<F>(db: Arc<DatabaseConnection>, id: i64, run: F)
+where
+    F: Future<Output = Result<ImportJobView, crate::model::Error>> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let ex = match tokio::spawn(run).await {
+            Ok(Ok(_)) => return,
+            Ok(Err(ex)) => ex,
+            Err(ex) => crate::model::Error::IOErr(ex.into()),
+        };
+        if let Err(ex) = ImportJobMac::fail(&db, id, ex).await {
+            println!("ERROR - import job {} - {:?}", id, ex);
+        }
+    });
+}
+
+async fn recipe_import_cooklang(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    options: CooklangImportOptions,
+    body: warp::hyper::body::Bytes,
+) -> Result<Json, warp::Rejection> {
+    let text = from_utf8(&body)
+        .map_err(|_| crate::model::Error::InvalidImport("the file is not utf-8".to_string()))?;
+    let recipe = RecipeMac::import_cooklang(&db, utx, text, options).await?;
+
+    json_response(RecipeView::from(recipe))
+}
+
+async fn recipe_import_obsidian(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    body: warp::hyper::body::Bytes,
+) -> Result<Json, warp::Rejection> {
+    let entries = tokio::task::spawn_blocking(move || read_vault(&body))
+        .await
+        .map_err(|ex| crate::model::Error::IOErr(ex.into()))??;
+    let report = ObsidianMac::import(&db, &utx, entries).await;
+
+    json_response(report)
+}
+
+async fn recipe_import_job(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    id: i64,
+) -> Result<Json, warp::Rejection> {
+    let job = ImportJobMac::get(&db, &utx, id).await?;
+
+    json_response(job)
 }
 
 async fn recipe_delete(
@@ -122,21 +429,43 @@ async fn recipe_update(
 ) -> Result<Json, warp::Rejection> {
     let recipe = RecipeMac::update(&db, patch, utx, id).await?;
 
-    json_response(recipe)
+    json_response(RecipeView::from(recipe))
 }
 
 async fn recipe_get_by_tag(
     db: Arc<DatabaseConnection>,
     utx: UserCtx,
     tag: String,
+    options: ListOptions,
 ) -> Result<Json, warp::Rejection> {
-    let recipes = RecipeMac::get_by_tag(&db, utx, tag.as_str()).await?;
+    let recipes = RecipeMac::get_by_tag(&db, utx, tag.as_str(), options).await?;
 
-    json_response(recipes)
+    json_response(recipes.map(RecipeView::from))
 }
 
-async fn scrape_recipe(encoded_url: String) -> Result<Json, warp::Rejection> {
-    scrape_recipe_content(encoded_url).await
+async fn recipe_search(
+    db: Arc<DatabaseConnection>,
+    utx: UserCtx,
+    options: SearchOptions,
+) -> Result<Json, warp::Rejection> {
+    let hits = RecipeMac::search(&db, utx, options).await?;
+
+    json_response(hits)
+}
+
+async fn scrape_recipe(
+    db: Arc<DatabaseConnection>,
+    _utx: UserCtx,
+    encoded_url: String,
+    options: ViewOptions,
+) -> Result<Json, warp::Rejection> {
+    let url = urlencoding::decode(&encoded_url)
+        .map_err(|_| ScrapeError::InvalidUrl(encoded_url.clone()))?;
+    let recipe = ScrapeCache::from_env()
+        .scrape(&db, &url, options.units)
+        .await?;
+
+    json_response(recipe)
 }
 
 // region: Utils